@@ -1,17 +1,261 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * Klucz AES wyprowadzony przez HKDF, który nie opuszcza Rusta – JS dostaje
+ * tylko uchwyt szyfrujący i odszyfrowujący pliki tak jak algorytm `aes-gcm`
+ */
+export declare class AesKey {
+  /**
+   * Wyprowadza klucz ze wspólnego sekretu (base64) tak jak `deriveEcdhKey`;
+   * domyślnie powstaje 32-bajtowy klucz AES-256
+   */
+  static fromSharedSecret(sharedSecret: string, options?: HkdfOptions | undefined | null): AesKey
+  /**
+   * Wykonuje ECDH i HKDF w jednym kroku, więc wspólny sekret nie trafia do JS.
+   * Tryb `expand` jest odrzucany: wynik ECDH nie jest jednorodnym kluczem PRK.
+   */
+  static fromEcdh(privateKey: string, publicKey: string, passphrase?: string | undefined | null, curve?: string | undefined | null, options?: HkdfOptions | undefined | null): AesKey
+  /** Szyfruje plik AES-GCM i zwraca ścieżkę wyniku (format jak `aes-gcm`) */
+  encryptFile(filePath: string): string
+  /** Odszyfrowuje plik zaszyfrowany tym samym kluczem */
+  decryptFile(filePath: string): string
+  /** Długość klucza w bitach (128, 192 lub 256) */
+  get bits(): number
+}
+/**
+ * Uchwyt pozwalający przerwać trwające generowanie kluczy, odpowiednik
+ * `AbortController` po stronie JS
+ */
+export declare class KeygenAbortHandle {
+  constructor()
+  abort(): void
+  get aborted(): boolean
+}
+/**
+ * Sesja protokołu Noise (`Noise_{NN,XX,IK}_25519_{AESGCM,ChaChaPoly}_SHA256`).
+ * Najpierw wymienia wiadomości uzgadniania, a po ostatniej z nich te same
+ * metody szyfrują i odszyfrowują wiadomości transportowe.
+ */
+export declare class NoiseSession {
+  /**
+   * `staticKey` to własny klucz prywatny X25519 (base64, JWK lub PEM jak w
+   * ECDH), `remoteStaticKey` – klucz publiczny odpowiadającego, podawany
+   * tylko przez inicjatora IK. `prologue` (base64) musi być taki sam po obu
+   * stronach.
+   */
+  constructor(protocol: string, initiator: boolean, staticKey?: string | undefined | null, remoteStaticKey?: string | undefined | null, prologue?: string | undefined | null, passphrase?: string | undefined | null)
+  /**
+   * Zwraca kolejną wiadomość do wysłania (base64) z opcjonalnym ładunkiem
+   * `payload` (base64)
+   */
+  writeMessage(payload?: string | undefined | null): string
+  /** Przetwarza wiadomość drugiej strony (base64) i zwraca jej ładunek (base64) */
+  readMessage(message: string): string
+  get handshakeFinished(): boolean
+  /**
+   * Skrót transkryptu uzgadniania (base64), taki sam po obu stronach –
+   * można go porównać poza kanałem lub użyć do powiązania kanału
+   */
+  get handshakeHash(): string | null
+  /** Uwierzytelniony klucz publiczny X25519 drugiej strony (base64) */
+  get remoteStaticKey(): string | null
+}
+/**
+ * Sesja Double Ratchet zainicjowana przez X3DH. Każda wiadomość ma
+ * osobny klucz, a stan (`serialize`/`restore`) można zapisać między
+ * uruchomieniami aplikacji – zawiera klucze tajne.
+ */
+export declare class RatchetSession {
+  /**
+   * Rozpoczyna sesję z właścicielem pakietu kluczy wstępnych `bundle` (JSON).
+   * Pakiet z nieprawidłowym podpisem klucza wstępnego jest odrzucany.
+   */
+  static initiate(identityKey: string, bundle: string, passphrase?: string | undefined | null): RatchetSession
+  /**
+   * Przyjmuje sesję z pierwszej wiadomości inicjatora (base64). Treść tej
+   * wiadomości odczytuje się potem zwykłym `decrypt`. Hasło `passphrase`
+   * odszyfrowuje wszystkie klucze zapisane jako PEM przez `generate_x3dh_prekeys`.
+   */
+  static respond(identityKey: string, prekey: string, oneTimePrekey: string | undefined | null, message: string, passphrase?: string | undefined | null): RatchetSession
+  /** Odtwarza sesję ze stanu zwróconego przez `serialize` */
+  static restore(state: string): RatchetSession
+  /** Stan sesji jako JSON; należy go zapisać po każdym `encrypt` i `decrypt` */
+  serialize(): string
+  /** Szyfruje `plaintext` (base64) i zwraca wiadomość (base64) */
+  encrypt(plaintext: string): string
+  /**
+   * Odszyfrowuje wiadomość (base64), także spóźnioną lub w innej kolejności;
+   * przy błędzie stan sesji się nie zmienia
+   */
+  decrypt(message: string): string
+  /** Klucz tożsamości X25519 rozmówcy (base64) */
+  get remoteIdentityKey(): string
+}
+/**
+ * Audyt partii kluczy publicznych RSA atakami faktoryzacji (Fermat, rho i
+ * p−1 Pollarda, Wiener, wspólne czynniki). Zwraca raport JSON z odtworzonymi
+ * czynnikami złamanych kluczy.
+ */
+export declare function auditRsaKeys(keys: Array<string>, passphrase?: string | undefined | null): Promise<string>
+
 export declare function clearLogs(): void
 
-export declare function computeEcdhSharedSecret(privateKey: string, publicKey: string): string
+/**
+ * Krótki ciąg uwierzytelniający (SAS) do porównania poza kanałem: sześć cyfr,
+ * siedem emoji i odpowiadające im słowa. Obie strony otrzymują ten sam wynik
+ * tylko wtedy, gdy nikt nie podmienił kluczy publicznych.
+ *
+ * Sześć cyfr to tylko ok. 20 bitów: pośrednik, który zna oba klucze przed
+ * wyborem własnych, może przetestować ok. 2^20 par, aż ciągi się zgodzą.
+ * Podanie `peer_commitment` (wynik `ecdh_public_key_commitment` otrzymany od
+ * rozmówcy, zanim ujawniło się własny klucz) zamyka tę lukę – klucz rozmówcy
+ * niezgodny ze zobowiązaniem kończy się błędem.
+ */
+export declare function computeEcdhSas(privateKey: string, publicKey: string, passphrase?: string | undefined | null, curve?: string | undefined | null, peerCommitment?: string | undefined | null): string
+
+export declare function computeEcdhSharedSecret(privateKey: string, publicKey: string, passphrase?: string | undefined | null, curve?: string | undefined | null): string
+
+export declare function decrypt(filePath: string, key: string, algorithm: string, passphrase?: string | undefined | null): string
+
+/**
+ * HKDF (RFC 5869) ze wspólnego sekretu w base64. Opcje jak w `HkdfOptions`:
+ * `hash` to `sha256` (domyślnie), `sha384` lub `sha512`, `length` – długość
+ * wyniku w bajtach, a `mode` wybiera etapy: `extract-and-expand`
+ * (domyślnie), `extract` (zwraca PRK) lub `expand` (sekret jest już kluczem PRK).
+ */
+export declare function deriveEcdhKey(sharedSecret: string, options?: HkdfOptions | undefined | null): string
+
+/**
+ * Zobowiązanie (SHA-256, base64) do klucza publicznego ECDH. Wyślij je
+ * rozmówcy przed samym kluczem, a swój klucz ujawnij dopiero po otrzymaniu
+ * jego zobowiązania – `compute_ecdh_sas` sprawdzi je wtedy z kluczem rozmówcy.
+ */
+export declare function ecdhPublicKeyCommitment(publicKey: string, curve?: string | undefined | null): string
+
+/** Odcisk SHA-256 klucza publicznego ECDH (hex, base64, emoji i grupy cyfr) */
+export declare function ecdhPublicKeyFingerprint(publicKey: string, curve?: string | undefined | null): string
 
-export declare function decrypt(filePath: string, key: string, algorithm: string): string
+/** Odcisk SHA-256 klucza publicznego Ed25519 (base64 zgodny z `ssh-keygen -lf`) */
+export declare function ed25519PublicKeyFingerprint(publicKey: string): string
 
-export declare function deriveEcdhKey(sharedSecret: string, salt?: string | undefined | null, info?: string | undefined | null): string
+/** Podpisuje tekst (UTF-8) i zwraca podpis Ed25519 w base64 */
+export declare function ed25519Sign(privateKey: string, message: string, passphrase?: string | undefined | null): string
 
-export declare function encrypt(filePath: string, key: string, algorithm: string): string
+/** Podpisuje zawartość pliku i zwraca podpis Ed25519 w base64 */
+export declare function ed25519SignFile(privateKey: string, filePath: string, passphrase?: string | undefined | null): string
+
+/** Weryfikuje podpis Ed25519 (base64) tekstu */
+export declare function ed25519Verify(publicKey: string, message: string, signature: string): boolean
+
+/** Weryfikuje podpis Ed25519 (base64) zawartości pliku */
+export declare function ed25519VerifyFile(publicKey: string, filePath: string, signature: string): boolean
+
+export declare function encrypt(filePath: string, key: string, algorithm: string, passphrase?: string | undefined | null): string
 
 export declare function exportLogs(): string
 
-export declare function generateEcdhKeypair(): string
+export declare function exportRsaKey(key: string, format: string, passphrase?: string | undefined | null, exportPassphrase?: string | undefined | null): string
+
+/**
+ * Generuje tożsamość age X25519 w formacie pliku `age-keygen` oraz
+ * odpowiadającego jej odbiorcę `age1...`
+ */
+export declare function generateAgeIdentity(): string
+
+/**
+ * Generuje parę kluczy ECDH na krzywej `curve`: `x25519` (domyślnie), `x448`,
+ * `p256` lub `p384`. Klucz publiczny NIST to punkt SEC1 w base64 – nieskompresowany
+ * (`base64`) lub skompresowany (`compressed`). Podanie `seed` włącza tryb
+ * deterministyczny (ChaCha20) – tylko do testów i pokazów.
+ */
+export declare function generateEcdhKeypair(format?: string | undefined | null, passphrase?: string | undefined | null, seed?: string | undefined | null, curve?: string | undefined | null): string
+
+/**
+ * Generuje parę kluczy Ed25519 w formacie `base64` (domyślnie), `pem` (PKCS#8
+ * i SPKI) lub `openssh`. Podanie `passphrase` zwraca klucz prywatny jako
+ * zaszyfrowany PKCS#8, a `seed` włącza tryb deterministyczny (ChaCha20).
+ */
+export declare function generateEd25519Keypair(format?: string | undefined | null, passphrase?: string | undefined | null, seed?: string | undefined | null): string
+
+/**
+ * Generuje parę kluczy RSA w puli wątków libuv. Postęp jest raportowany po
+ * każdym teście pierwszości, a `abort` pozwala przerwać obliczenia. Podanie
+ * `passphrase` zwraca klucz prywatny jako zaszyfrowany PKCS#8, a `primes`
+ * (3–5) tworzy klucz wieloczynnikowy. `publicExponent` zmienia e (małe e
+ * tylko w trybie edukacyjnym). Podanie `seed` włącza tryb deterministyczny
+ * (ChaCha20) – tylko do testów i pokazów.
+ */
+export declare function generateRsaKeypair(options: RsaKeygenOptions, onProgress?: ((arg: RsaKeygenProgress) => void) | undefined | null, abort?: KeygenAbortHandle | undefined | null): Promise<string>
+
+/**
+ * Generuje klucze wstępne X3DH: klucz tożsamości (chyba że podano własny
+ * `identityKey`), klucz wstępny i jednorazowy klucz wstępny. Zwraca JSON
+ * z publicznym pakietem `bundle` do przekazania rozmówcy oraz kluczami
+ * prywatnymi `private` (base64, a po podaniu `passphrase` – PEM PKCS#8
+ * zaszyfrowany PBES2 jak w `generate_ecdh_keypair`). Klucz wstępny w pakiecie
+ * jest podpisany kluczem tożsamości (XEdDSA).
+ */
+export declare function generateX3dhPrekeys(identityKey?: string | undefined | null, passphrase?: string | undefined | null): string
+
+/**
+ * Atak z wybranym szyfrogramem. Wyrocznię symuluje `oracle_key`: odszyfrowuje
+ * dowolne bloki poza tymi z `ciphertext`.
+ */
+export declare function rsaChosenCiphertextAttack(publicKey: string, ciphertext: string, oracleKey: string, passphrase?: string | undefined | null): string
+
+/** Atak na wspólny moduł: jedna wiadomość zaszyfrowana kluczami (n, e1) i (n, e2) */
+export declare function rsaCommonModulusAttack(firstKey: string, firstCiphertext: string, secondKey: string, secondCiphertext: string): string
+
+/**
+ * Atak Håstada: ta sama wiadomość zaszyfrowana małym `e` dla `e` odbiorców.
+ * `keys[i]` to klucz publiczny, którym utworzono `ciphertexts[i]`.
+ */
+export declare function rsaHastadAttack(keys: Array<string>, ciphertexts: Array<string>): string
+
+/** Odcisk SHA-256 klucza publicznego RSA (hex, base64, emoji i grupy cyfr) */
+export declare function rsaKeyFingerprint(key: string, passphrase?: string | undefined | null): string
+
+/** Parametry HKDF dla `deriveEcdhKey` i `AesKey` */
+export interface HkdfOptions {
+  /** Sól w base64 */
+  salt?: string
+  /** Kontekst `info` w base64 */
+  info?: string
+  /** `sha256` (domyślnie), `sha384` lub `sha512` */
+  hash?: string
+  /** Długość wyniku w bajtach (domyślnie 32); klucz `AesKey` ma 16, 24 lub 32 bajty */
+  length?: number
+  /** `extract-and-expand` (domyślnie), `extract` lub `expand` */
+  mode?: string
+}
+
+/** Parametry `generate_rsa_keypair`; pominięte pola mają wartości domyślne */
+export interface RsaKeygenOptions {
+  /** Długość modułu w bitach */
+  bits: number
+  /** `json` (domyślnie), `pkcs1`, `pkcs8` lub `jwk` */
+  format?: string
+  /** Dopuszcza moduły krótsze niż 2048 bitów */
+  educational?: boolean
+  /** Uzupełnia test Millera–Rabina o silny test Lucasa (Baillie–PSW) */
+  lucasTest?: boolean
+  /** Szyfruje klucz prywatny (PKCS#8 PBES2) */
+  passphrase?: string
+  /** Liczba czynników pierwszych, 2 (domyślnie) do 5 */
+  primes?: number
+  /** Eksponent publiczny (domyślnie 65537); małe wartości, np. 3, tylko w trybie edukacyjnym */
+  publicExponent?: number
+  /** Ziarno trybu deterministycznego (ChaCha20) – tylko do testów i pokazów */
+  seed?: string
+}
+
+export interface RsaKeygenProgress {
+  candidatesTested: number
+  primesFound: number
+}
+
+/** Odzyskuje krótką wiadomość (m^e < n) jako pierwiastek stopnia e z szyfrogramu */
+export declare function rsaSmallMessageAttack(key: string, ciphertext: string): string
 
-export declare function generateRsaKeypair(bits: number): string
+/** Sprawdza klucz RSA i zwraca raport JSON z wynikiem każdego testu */
+export declare function validateRsaKey(key: string, educational?: boolean | undefined | null, passphrase?: string | undefined | null): string
@@ -1,4 +1,6 @@
+use crate::algorithms::age::AgeCipher;
 use crate::algorithms::caesar::CaesarCipher;
+use crate::algorithms::ecies::EciesCipher;
 use crate::algorithms::rsa::RsaCipher;
 use crate::algorithms::vigenere::VigenereCipher;
 use crate::error::CryptoError;
@@ -12,6 +14,7 @@ impl AlgorithmAdapter {
         file_path: String,
         key: String,
         algorithm: String,
+        passphrase: Option<String>,
     ) -> Result<String, CryptoError> {
         log(LogLevel::INFO, "Adapter", &format!("Wybrano algorytm szyfrowania: {}", algorithm));
         match algorithm.as_str() {
@@ -24,7 +27,7 @@ impl AlgorithmAdapter {
                 cipher.encrypt(&file_path)
             }
             "rsa" => {
-                let cipher = RsaCipher::new(&key)?;
+                let cipher = RsaCipher::new(&key, passphrase.as_deref())?;
                 cipher.encrypt(&file_path)
             }
             "running-key-cipher" => {
@@ -36,6 +39,14 @@ impl AlgorithmAdapter {
                 let cipher = crate::algorithms::aes::AesCipher::new(&key)?;
                 cipher.encrypt(&file_path)
             }
+            "x25519-ecies" => {
+                let cipher = EciesCipher::new(&key, passphrase.as_deref())?;
+                cipher.encrypt(&file_path)
+            }
+            "age" | "age-armor" => {
+                let cipher = AgeCipher::new(&key, passphrase.as_deref(), algorithm == "age-armor")?;
+                cipher.encrypt(&file_path)
+            }
             _ => Err(CryptoError::UnsupportedAlgorithm(algorithm)),
         }
     }
@@ -44,6 +55,7 @@ impl AlgorithmAdapter {
         file_path: String,
         key: String,
         algorithm: String,
+        passphrase: Option<String>,
     ) -> Result<String, CryptoError> {
         log(LogLevel::INFO, "Adapter", &format!("Wybrano algorytm deszyfrowania: {}", algorithm));
         match algorithm.as_str() {
@@ -56,7 +68,7 @@ impl AlgorithmAdapter {
                 cipher.decrypt(&file_path)
             }
             "rsa" => {
-                let cipher = RsaCipher::new(&key)?;
+                let cipher = RsaCipher::new(&key, passphrase.as_deref())?;
                 cipher.decrypt(&file_path)
             }
             "running-key-cipher" => {
@@ -68,6 +80,14 @@ impl AlgorithmAdapter {
                 let cipher = crate::algorithms::aes::AesCipher::new(&key)?;
                 cipher.decrypt(&file_path)
             }
+            "x25519-ecies" => {
+                let cipher = EciesCipher::new(&key, passphrase.as_deref())?;
+                cipher.decrypt(&file_path)
+            }
+            "age" | "age-armor" => {
+                let cipher = AgeCipher::new(&key, passphrase.as_deref(), algorithm == "age-armor")?;
+                cipher.decrypt(&file_path)
+            }
             _ => Err(CryptoError::UnsupportedAlgorithm(algorithm)),
         }
     }
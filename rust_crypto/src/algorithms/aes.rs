@@ -27,6 +27,18 @@ impl AesCipher {
             key: key_bytes.to_vec(),
         })
     }
+
+    /// Surowy klucz AES-128, AES-192 lub AES-256, np. wyprowadzony przez HKDF
+    pub fn from_bytes(key: &[u8]) -> Result<Self, CryptoError> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(CryptoError::InvalidKey(format!(
+                "Klucz AES musi mieć 16, 24 lub 32 bajty, otrzymano {}",
+                key.len()
+            )));
+        }
+
+        Ok(AesCipher { key: key.to_vec() })
+    }
 }
 
 impl Algorithm for AesCipher {
@@ -110,7 +122,7 @@ impl Algorithm for AesCipher {
             format!("{}.{}", file_stem, extension)
         };
         let aad = original_filename.as_bytes();
-        let decrypted = aes_gcm_decrypt(&encrypted_bytes, aad, &self.key)
+        let decrypted = aes_gcm_decrypt_file(&encrypted_bytes, aad, &self.key)
             .map_err(|e| CryptoError::DecryptionError(e.to_string()))?;
 
         let decrypted_text = decrypted
@@ -183,6 +195,8 @@ pub fn key_expansion(key: &[u8]) -> RoundKeys {
     let nk = key.len() / 4;
     let nr = match nk {
         4 => 10,
+        6 => 12,
+        8 => 14,
         _ => panic!("Nieobsługiwany rozmiar klucza AES"),
     };
     let nb = 4;
@@ -248,13 +262,40 @@ pub fn aes_encrypt_block(plaintext_block: Block, round_keys: &RoundKeys) -> Bloc
     state
 }
 
-/// Mnożenie w GF(2^128) dla GHASH
+type GfMul = fn(u128, u128) -> u128;
+
+/// Mnożenie w GF(2^128) dla GHASH (SP 800-38D, algorytm 1)
 /// Używa redukcji z polinomem R = 0xe1 << 120 (x^128 + x^7 + x^2 + x + 1)
 fn gf128_mul(x: u128, y: u128) -> u128 {
     let mut z = 0u128;
     let mut v = x;
     let mut y_bits = y;
 
+    for _ in 0..128 {
+        if (y_bits >> 127) == 1 {
+            z ^= v;
+        }
+
+        let lsb = v & 1;
+        v >>= 1;
+
+        if lsb == 1 {
+            v ^= 0xe1000000000000000000000000000000u128;
+        }
+
+        y_bits <<= 1;
+    }
+
+    z
+}
+
+/// Wcześniejsze mnożenie, które czytało bity `y` od najmłodszego, przez co
+/// tagi nie zgadzały się ze standardem; służy tylko do odczytu starych danych
+fn gf128_mul_legacy(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = x;
+    let mut y_bits = y;
+
     for _ in 0..128 {
         if (y_bits & 1) == 1 {
             z ^= v;
@@ -290,27 +331,27 @@ fn u128_to_bytes(value: u128) -> [u8; 16] {
 }
 
 /// GHASH: funkcja autentykacji dla GCM
-fn ghash(h: u128, aad: &[u8], ciphertext: &[u8]) -> u128 {
+fn ghash(h: u128, aad: &[u8], ciphertext: &[u8], mul: GfMul) -> u128 {
     let mut y = 0u128;
 
     for chunk in aad.chunks(16) {
         let mut block = [0u8; 16];
         block[..chunk.len()].copy_from_slice(chunk);
         let x = bytes_to_u128(&block);
-        y = gf128_mul(y ^ x, h);
+        y = mul(y ^ x, h);
     }
 
     for chunk in ciphertext.chunks(16) {
         let mut block = [0u8; 16];
         block[..chunk.len()].copy_from_slice(chunk);
         let x = bytes_to_u128(&block);
-        y = gf128_mul(y ^ x, h);
+        y = mul(y ^ x, h);
     }
 
     let aad_bits = (aad.len() as u128) * 8;
     let ct_bits = (ciphertext.len() as u128) * 8;
     let len_block = (aad_bits << 64) | ct_bits;
-    y = gf128_mul(y ^ len_block, h);
+    y = mul(y ^ len_block, h);
 
     y
 }
@@ -323,11 +364,8 @@ fn derive_j0(nonce: &[u8], h: u128) -> Block {
         j0[15] = 0x01;
         j0
     } else {
-        let ghash_result = ghash(h, &[], nonce);
-        let nonce_bits = (nonce.len() as u128) * 8;
-        let len_block = nonce_bits;
-        let j0_value = gf128_mul(ghash_result ^ len_block, h);
-        u128_to_bytes(j0_value)
+        // GHASH(IV || 0 || [len(IV)]64) – blok długości dopisuje już ghash
+        u128_to_bytes(ghash(h, &[], nonce, gf128_mul))
     }
 }
 
@@ -351,50 +389,94 @@ fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     result == 0
 }
 
-pub fn aes_gcm_encrypt(plaintext: &[u8], aad: &[u8], key: &[u8]) -> Vec<u8> {
-    log(LogLevel::INFO, "AES-GCM", "Generowanie nonce...");
-    let mut rng = rand::thread_rng();
-    let mut nonce = [0u8; 12];
-    rng.fill(&mut nonce);
+/// Szyfrowanie CTR od licznika następnego po J0
+fn gctr(round_keys: &RoundKeys, j0: Block, data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len());
+    let mut current_counter = j0;
+    inc32(&mut current_counter);
 
-    log(LogLevel::INFO, "AES-GCM", "Rozszerzanie klucza...");
-    let round_keys = key_expansion(key);
+    for chunk in data.chunks(BLOCK_SIZE) {
+        let keystream = aes_encrypt_block(current_counter, round_keys);
 
-    let zero_block = [0u8; 16];
-    let h_block = aes_encrypt_block(zero_block, &round_keys);
-    let h = bytes_to_u128(&h_block);
+        for i in 0..chunk.len() {
+            output.push(chunk[i] ^ keystream[i]);
+        }
 
-    let j0 = derive_j0(&nonce, h);
+        inc32(&mut current_counter);
+    }
 
-    let mut counter_block = j0;
-    inc32(&mut counter_block);
+    output
+}
 
-    let mut ciphertext = Vec::new();
-    let mut current_counter = counter_block;
+fn gcm_tag(
+    round_keys: &RoundKeys,
+    h: u128,
+    j0: Block,
+    aad: &[u8],
+    ciphertext: &[u8],
+    mul: GfMul,
+) -> [u8; 16] {
+    let s = ghash(h, aad, ciphertext, mul);
+    let ej0 = aes_encrypt_block(j0, round_keys);
+    u128_to_bytes(bytes_to_u128(&ej0) ^ s)
+}
 
-    log(LogLevel::INFO, "AES-GCM", "Szyfrowanie bloków...");
-    for chunk in plaintext.chunks(BLOCK_SIZE) {
-        let keystream = aes_encrypt_block(current_counter, &round_keys);
+/// AES-GCM z nonce podanym przez wywołującego (np. licznik protokołu);
+/// zwraca szyfrogram z dołączonym 16-bajtowym tagiem
+pub fn aes_gcm_seal(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let round_keys = key_expansion(key);
+    let h = bytes_to_u128(&aes_encrypt_block([0u8; 16], &round_keys));
+    let j0 = derive_j0(nonce, h);
 
-        for i in 0..chunk.len() {
-            ciphertext.push(chunk[i] ^ keystream[i]);
-        }
+    let mut sealed = gctr(&round_keys, j0, plaintext);
+    let tag = gcm_tag(&round_keys, h, j0, aad, &sealed, gf128_mul);
+    sealed.extend_from_slice(&tag);
+    sealed
+}
 
-        inc32(&mut current_counter);
+/// Odwrotność `aes_gcm_seal`: sprawdza tag i zwraca tekst jawny
+pub fn aes_gcm_open(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    sealed: &[u8],
+) -> Result<Vec<u8>, &'static str> {
+    open_with(key, nonce, aad, sealed, gf128_mul)
+}
+
+fn open_with(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    sealed: &[u8],
+    mul: GfMul,
+) -> Result<Vec<u8>, &'static str> {
+    if sealed.len() < 16 {
+        return Err("Dane zbyt krótkie dla GCM");
     }
+    let (ciphertext, received_tag) = sealed.split_at(sealed.len() - 16);
 
-    log(LogLevel::INFO, "AES-GCM", "Obliczanie GHASH...");
-    let s = ghash(h, aad, &ciphertext);
+    let round_keys = key_expansion(key);
+    let h = bytes_to_u128(&aes_encrypt_block([0u8; 16], &round_keys));
+    let j0 = derive_j0(nonce, h);
 
-    let ej0 = aes_encrypt_block(j0, &round_keys);
-    let ej0_value = bytes_to_u128(&ej0);
-    let tag_value = ej0_value ^ s;
-    let tag = u128_to_bytes(tag_value);
+    let expected_tag = gcm_tag(&round_keys, h, j0, aad, ciphertext, mul);
+    if !constant_time_eq(received_tag, &expected_tag) {
+        return Err("Weryfikacja autentyczności nie powiodła się");
+    }
+
+    Ok(gctr(&round_keys, j0, ciphertext))
+}
+
+pub fn aes_gcm_encrypt(plaintext: &[u8], aad: &[u8], key: &[u8]) -> Vec<u8> {
+    log(LogLevel::INFO, "AES-GCM", "Generowanie nonce...");
+    let mut rng = rand::thread_rng();
+    let mut nonce = [0u8; 12];
+    rng.fill(&mut nonce);
 
-    let mut result = Vec::new();
-    result.extend_from_slice(&nonce);
-    result.extend_from_slice(&ciphertext);
-    result.extend_from_slice(&tag);
+    log(LogLevel::INFO, "AES-GCM", "Szyfrowanie bloków i obliczanie GHASH...");
+    let mut result = nonce.to_vec();
+    result.extend_from_slice(&aes_gcm_seal(key, &nonce, aad, plaintext));
 
     result
 }
@@ -404,52 +486,164 @@ pub fn aes_gcm_decrypt(data: &[u8], aad: &[u8], key: &[u8]) -> Result<Vec<u8>, &
         return Err("Dane zbyt krótkie dla GCM");
     }
 
-    let nonce = &data[0..12];
-    let tag_start = data.len() - 16;
-    let ciphertext = &data[12..tag_start];
-    let received_tag = &data[tag_start..];
-
-    log(LogLevel::INFO, "AES-GCM", "Rozszerzanie klucza...");
-    let round_keys = key_expansion(key);
+    log(LogLevel::INFO, "AES-GCM", "Weryfikacja tagu i deszyfrowanie bloków...");
+    let (nonce, sealed) = data.split_at(12);
+    let plaintext = aes_gcm_open(key, nonce, aad, sealed)?;
+    log(LogLevel::INFO, "AES-GCM", "Tag poprawny.");
 
-    let zero_block = [0u8; 16];
-    let h_block = aes_encrypt_block(zero_block, &round_keys);
-    let h = bytes_to_u128(&h_block);
-
-    let j0 = derive_j0(nonce, h);
-
-    let s = ghash(h, aad, ciphertext);
+    Ok(plaintext)
+}
 
-    let ej0 = aes_encrypt_block(j0, &round_keys);
-    let ej0_value = bytes_to_u128(&ej0);
-    let expected_tag_value = ej0_value ^ s;
-    let expected_tag = u128_to_bytes(expected_tag_value);
+/// `aes_gcm_decrypt` dla plików szyfrowanych w `AesCipher`; akceptuje też tag
+/// z niestandardowym GHASH, którym posługiwały się starsze wersje aplikacji
+fn aes_gcm_decrypt_file(data: &[u8], aad: &[u8], key: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let err = match aes_gcm_decrypt(data, aad, key) {
+        Ok(plaintext) => return Ok(plaintext),
+        Err(err) if data.len() < 28 => return Err(err),
+        Err(err) => err,
+    };
 
-    log(LogLevel::INFO, "AES-GCM", "Weryfikacja tagu GCM...");
-    if !constant_time_eq(received_tag, &expected_tag) {
-        return Err("Weryfikacja autentyczności nie powiodła się");
-    }
+    let (nonce, sealed) = data.split_at(12);
+    let plaintext = open_with(key, nonce, aad, sealed, gf128_mul_legacy).map_err(|_| err)?;
     log(
-        LogLevel::INFO,
+        LogLevel::WARN,
         "AES-GCM",
-        "Tag poprawny. Deszyfrowanie bloków...",
+        "Odczytano plik ze starszym, niestandardowym tagiem GHASH – zaszyfruj go ponownie.",
     );
+    Ok(plaintext)
+}
 
-    let mut counter_block = j0;
-    inc32(&mut counter_block);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_vectors::hex;
+
+    const GCM_KEY: &str = "feffe9928665731c6d6a8f9467308308";
+    const GCM_IV: &str = "cafebabefacedbaddecaf888";
+    const GCM_AAD: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+    const GCM_PLAINTEXT: &str = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72
+                                 1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39";
+
+    /// FIPS-197, dodatek C.1–C.3
+    #[test]
+    fn fips197_block_vectors() {
+        let plaintext: Block = hex("00112233445566778899aabbccddeeff").try_into().unwrap();
+        for (key, expected) in [
+            ("000102030405060708090a0b0c0d0e0f", "69c4e0d86a7b0430d8cdb78070b4c55a"),
+            (
+                "000102030405060708090a0b0c0d0e0f1011121314151617",
+                "dda97ca4864cdfe06eaf70a0ec0d7191",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                "8ea2b7ca516745bfeafc49904b496089",
+            ),
+        ] {
+            let round_keys = key_expansion(&hex(key));
+            assert_eq!(aes_encrypt_block(plaintext, &round_keys).to_vec(), hex(expected));
+        }
+    }
 
-    let mut plaintext = Vec::new();
-    let mut current_counter = counter_block;
+    /// Przypadki testowe z "The Galois/Counter Mode of Operation" (McGrew, Viega),
+    /// używane w walidacji NIST: klucz, IV, AAD, tekst jawny, szyfrogram || tag
+    #[test]
+    fn nist_gcm_vectors() {
+        let zero128 = "00".repeat(16);
+        let zero256 = "00".repeat(32);
+        let zero_iv = "000000000000000000000000";
+        let zero_block = "00000000000000000000000000000000";
+        let k192 = format!("{}{}", GCM_KEY, &GCM_KEY[..16]);
+        let k256 = GCM_KEY.repeat(2);
+        let iv60 = "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728
+                    c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b";
+        let cases: [(&str, &str, &str, &str, &str); 8] = [
+            // 1, 2: AES-128, klucz zerowy
+            (&zero128, zero_iv, "", "", "58e2fccefa7e3061367f1d57a4e7455a"),
+            (
+                &zero128,
+                zero_iv,
+                "",
+                zero_block,
+                "0388dace60b6a392f328c2b971b2fe78 ab6e47d42cec13bdf53a67b21257bddf",
+            ),
+            // 4: AES-128 z AAD
+            (
+                GCM_KEY,
+                GCM_IV,
+                GCM_AAD,
+                GCM_PLAINTEXT,
+                "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e
+                 21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091
+                 5bc94fbc3221a5db94fae95ae7121a47",
+            ),
+            // 6: IV o długości 60 bajtów (J0 liczone przez GHASH)
+            (
+                GCM_KEY,
+                iv60,
+                GCM_AAD,
+                GCM_PLAINTEXT,
+                "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7
+                 01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5
+                 619cc5aefffe0bfa462af43c1699d050",
+            ),
+            // 10: AES-192
+            (
+                &k192,
+                GCM_IV,
+                GCM_AAD,
+                GCM_PLAINTEXT,
+                "3980ca0b3c00e841eb06fac4872a2757859e1ceaa6efd984628593b40ca1e19c
+                 7d773d00c144c525ac619d18c84a3f4718e2448b2fe324d9ccda2710
+                 2519498e80f1478f37ba55bd6d27618c",
+            ),
+            // 13, 14: AES-256, klucz zerowy
+            (&zero256, zero_iv, "", "", "530f8afbc74536b9a963b4f1c4cb738b"),
+            (
+                &zero256,
+                zero_iv,
+                "",
+                zero_block,
+                "cea7403d4d606b6e074ec5d3baf39d18 d0d1c8a799996bf0265b98b5d48ab919",
+            ),
+            // 16: AES-256 z AAD
+            (
+                &k256,
+                GCM_IV,
+                GCM_AAD,
+                GCM_PLAINTEXT,
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa
+                 8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662
+                 76fc6ece0f4e1768cddf8853bb2d551b",
+            ),
+        ];
 
-    for chunk in ciphertext.chunks(BLOCK_SIZE) {
-        let keystream = aes_encrypt_block(current_counter, &round_keys);
+        for (key, iv, aad, plaintext, sealed) in cases {
+            let (key, iv, aad) = (hex(key), hex(iv), hex(aad));
+            assert_eq!(aes_gcm_seal(&key, &iv, &aad, &hex(plaintext)), hex(sealed));
+            assert_eq!(aes_gcm_open(&key, &iv, &aad, &hex(sealed)).unwrap(), hex(plaintext));
 
-        for i in 0..chunk.len() {
-            plaintext.push(chunk[i] ^ keystream[i]);
+            let mut tampered = hex(sealed);
+            *tampered.last_mut().unwrap() ^= 1;
+            assert!(aes_gcm_open(&key, &iv, &aad, &tampered).is_err());
         }
-
-        inc32(&mut current_counter);
     }
 
-    Ok(plaintext)
+    /// Szyfrogram zapisany przed poprawką kolejności bitów w GHASH
+    #[test]
+    fn legacy_tags_are_only_accepted_by_the_file_format() {
+        let key = b"0123456789abcdef";
+        let data = hex("242d378c456131582164c678dcfba2765b9906e57b052114
+                        12cbab8d685d4cd1b2bf9056ffbd5212");
+        assert_eq!(aes_gcm_decrypt_file(&data, b"note.txt", key).unwrap(), b"legacy GHASH");
+        // Klucze PKCS#8 i ECIES używają tylko standardowego GHASH
+        assert!(aes_gcm_decrypt(&data, b"note.txt", key).is_err());
+        assert!(aes_gcm_open(key, &data[..12], b"note.txt", &data[12..]).is_err());
+
+        let mut tampered = data.clone();
+        tampered[20] ^= 1;
+        assert!(aes_gcm_decrypt_file(&tampered, b"note.txt", key).is_err());
+
+        let sealed = aes_gcm_encrypt(b"current GHASH", b"note.txt", key);
+        assert_eq!(aes_gcm_decrypt_file(&sealed, b"note.txt", key).unwrap(), b"current GHASH");
+    }
 }
@@ -0,0 +1,809 @@
+use crate::algorithms::ecdh::{PrivateKey, PublicKeyBytes};
+use crate::error::CryptoError;
+use crate::traits::Algorithm;
+use crate::utils::logger::{LogLevel, log};
+use crate::utils::{bech32, file_handler, pem};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use std::fs;
+
+const VERSION_LINE: &str = "age-encryption.org/v1";
+/// Etykieta pancerza ASCII (PEM bez nagłówków)
+const ARMOR_LABEL: &str = "AGE ENCRYPTED FILE";
+const X25519_LABEL: &str = "age-encryption.org/v1/X25519";
+const SCRYPT_LABEL: &str = "age-encryption.org/v1/scrypt";
+const RECIPIENT_HRP: &str = "age";
+const IDENTITY_HRP: &str = "age-secret-key-";
+const FILE_KEY_LEN: usize = 16;
+const PAYLOAD_NONCE_LEN: usize = 16;
+const TAG_LEN: usize = 16;
+/// Rozmiar fragmentu jawnego tekstu w strumieniu STREAM
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Szerokość linii base64 w treści stanzy
+const COLUMNS: usize = 64;
+/// log2(N) dla scrypt – domyślna wartość `age -p`
+const SCRYPT_WORK_FACTOR: u8 = 18;
+/// Górna granica log2(N) przyjmowana przy odszyfrowywaniu (jak w `age`)
+const MAX_SCRYPT_WORK_FACTOR: u8 = 22;
+
+/// Odbiorca pliku age
+pub enum AgeRecipient {
+    X25519(PublicKeyBytes),
+    Scrypt { passphrase: String, work_factor: u8 },
+}
+
+/// Tożsamość, którą można odszyfrować plik age
+pub enum AgeIdentity {
+    X25519(PrivateKey),
+    Scrypt(String),
+}
+
+/// Stanza nagłówka: `-> typ argumenty...` i treść w base64
+struct Stanza {
+    kind: String,
+    args: Vec<String>,
+    body: Vec<u8>,
+}
+
+fn format_error(reason: impl Into<String>) -> CryptoError {
+    CryptoError::InvalidFormat(format!("Niepoprawny plik age: {}", reason.into()))
+}
+
+fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut okm = [0u8; 32];
+    // 32 bajty mieszczą się w limicie 255 bloków HKDF
+    let _ = Hkdf::<Sha256>::new(Some(salt), ikm).expand(info, &mut okm);
+    okm
+}
+
+fn header_mac(file_key: &[u8], header: &[u8]) -> Hmac<Sha256> {
+    let key = hkdf_sha256(file_key, &[], b"header");
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key)
+        .expect("HMAC przyjmuje klucz dowolnej długości");
+    mac.update(header);
+    mac
+}
+
+fn b64_encode(data: &[u8]) -> String {
+    general_purpose::STANDARD_NO_PAD.encode(data)
+}
+
+fn b64_decode(text: &str) -> Result<Vec<u8>, CryptoError> {
+    general_purpose::STANDARD_NO_PAD
+        .decode(text)
+        .map_err(|err| format_error(format!("niepoprawny base64: {}", err)))
+}
+
+/// Klucz pliku opakowany ChaCha20-Poly1305 z zerowym nonce (każdy klucz opakowania jest jednorazowy)
+fn wrap_file_key(wrap_key: &[u8; 32], file_key: &[u8]) -> Vec<u8> {
+    ChaCha20Poly1305::new(Key::from_slice(wrap_key))
+        .encrypt(&Nonce::default(), file_key)
+        .unwrap_or_default()
+}
+
+fn unwrap_file_key(wrap_key: &[u8; 32], body: &[u8]) -> Option<Vec<u8>> {
+    if body.len() != FILE_KEY_LEN + TAG_LEN {
+        return None;
+    }
+    ChaCha20Poly1305::new(Key::from_slice(wrap_key))
+        .decrypt(&Nonce::default(), body)
+        .ok()
+}
+
+fn scrypt_key(passphrase: &str, salt: &[u8], work_factor: u8) -> Result<[u8; 32], CryptoError> {
+    let params = scrypt::Params::new(work_factor, 8, 1, 32)
+        .map_err(|err| CryptoError::InvalidKey(format!("Niepoprawne parametry scrypt: {}", err)))?;
+    let mut full_salt = SCRYPT_LABEL.as_bytes().to_vec();
+    full_salt.extend_from_slice(salt);
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), &full_salt, &params, &mut key)
+        .map_err(|err| CryptoError::InvalidKey(format!("Błąd scrypt: {}", err)))?;
+    Ok(key)
+}
+
+impl AgeRecipient {
+    /// Parsuje klucz publiczny `age1...`
+    pub fn parse(text: &str) -> Result<Self, CryptoError> {
+        let not_recipient = || {
+            CryptoError::InvalidKey(format!(
+                "To nie jest odbiorca age: {}. Aby zaszyfrować plik hasłem, wybierz tryb hasła.",
+                text
+            ))
+        };
+        let (hrp, data) = bech32::decode(text.trim()).map_err(|_| not_recipient())?;
+        if hrp != RECIPIENT_HRP || text.trim().chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(not_recipient());
+        }
+        let bytes: [u8; 32] = data.try_into().map_err(|_| {
+            CryptoError::InvalidKey("Klucz odbiorcy age musi mieć 32 bajty".to_string())
+        })?;
+        Ok(AgeRecipient::X25519(PublicKeyBytes::from_bytes(bytes)))
+    }
+
+    pub fn passphrase(passphrase: &str) -> Self {
+        AgeRecipient::Scrypt {
+            passphrase: passphrase.to_string(),
+            work_factor: SCRYPT_WORK_FACTOR,
+        }
+    }
+
+    fn wrap(&self, file_key: &[u8]) -> Result<Stanza, CryptoError> {
+        match self {
+            AgeRecipient::X25519(recipient) => {
+                let (ephemeral_secret, ephemeral) = PrivateKey::generate();
+                let shared = ephemeral_secret
+                    .compute_shared_secret(recipient)
+                    .map_err(|err| CryptoError::InvalidKey(err.to_string()))?;
+                let mut salt = ephemeral.as_bytes().to_vec();
+                salt.extend_from_slice(recipient.as_bytes());
+                let wrap_key = hkdf_sha256(&shared, &salt, X25519_LABEL.as_bytes());
+                Ok(Stanza {
+                    kind: "X25519".to_string(),
+                    args: vec![b64_encode(ephemeral.as_bytes())],
+                    body: wrap_file_key(&wrap_key, file_key),
+                })
+            }
+            AgeRecipient::Scrypt {
+                passphrase,
+                work_factor,
+            } => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                let wrap_key = scrypt_key(passphrase, &salt, *work_factor)?;
+                Ok(Stanza {
+                    kind: "scrypt".to_string(),
+                    args: vec![b64_encode(&salt), work_factor.to_string()],
+                    body: wrap_file_key(&wrap_key, file_key),
+                })
+            }
+        }
+    }
+}
+
+impl AgeIdentity {
+    /// Parsuje klucz prywatny `AGE-SECRET-KEY-1...`
+    pub fn parse(text: &str) -> Result<Self, CryptoError> {
+        let not_identity = || {
+            CryptoError::InvalidKey(
+                "To nie jest tożsamość age. Aby odszyfrować plik hasłem, wybierz tryb hasła."
+                    .to_string(),
+            )
+        };
+        let (hrp, data) = bech32::decode(text.trim()).map_err(|_| not_identity())?;
+        if hrp != IDENTITY_HRP || text.trim().chars().any(|c| c.is_ascii_lowercase()) {
+            return Err(not_identity());
+        }
+        let bytes: [u8; 32] = data
+            .try_into()
+            .map_err(|_| CryptoError::InvalidKey("Tożsamość age musi mieć 32 bajty".to_string()))?;
+        Ok(AgeIdentity::X25519(PrivateKey::from_bytes(bytes)))
+    }
+
+    /// Próbuje odzyskać klucz pliku ze stanzy; `None`, gdy stanza nie jest dla tej tożsamości
+    fn unwrap(&self, stanza: &Stanza) -> Result<Option<Vec<u8>>, CryptoError> {
+        match (self, stanza.kind.as_str()) {
+            (AgeIdentity::X25519(private), "X25519") => {
+                let [share] = stanza.args.as_slice() else {
+                    return Err(format_error("stanza X25519 wymaga jednego argumentu"));
+                };
+                let share: [u8; 32] = b64_decode(share)?
+                    .try_into()
+                    .map_err(|_| format_error("klucz efemeryczny X25519 musi mieć 32 bajty"))?;
+                let share = PublicKeyBytes::from_bytes(share);
+                let shared = private
+                    .compute_shared_secret(&share)
+                    .map_err(|err| format_error(err.to_string()))?;
+                let mut salt = share.as_bytes().to_vec();
+                salt.extend_from_slice(private.public_key().as_bytes());
+                let wrap_key = hkdf_sha256(&shared, &salt, X25519_LABEL.as_bytes());
+                Ok(unwrap_file_key(&wrap_key, &stanza.body))
+            }
+            (AgeIdentity::Scrypt(passphrase), "scrypt") => {
+                let [salt, work_factor] = stanza.args.as_slice() else {
+                    return Err(format_error("stanza scrypt wymaga dwóch argumentów"));
+                };
+                let salt = b64_decode(salt)?;
+                if salt.len() != 16 {
+                    return Err(format_error("sól scrypt musi mieć 16 bajtów"));
+                }
+                let work_factor = parse_work_factor(work_factor)?;
+                let wrap_key = scrypt_key(passphrase, &salt, work_factor)?;
+                unwrap_file_key(&wrap_key, &stanza.body)
+                    .map(Some)
+                    .ok_or_else(|| {
+                        CryptoError::DecryptionError("Niepoprawne hasło age".to_string())
+                    })
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn parse_work_factor(text: &str) -> Result<u8, CryptoError> {
+    if text.starts_with('0') || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format_error("niepoprawny parametr pracy scrypt"));
+    }
+    match text.parse::<u8>() {
+        Ok(value) if (1..=MAX_SCRYPT_WORK_FACTOR).contains(&value) => Ok(value),
+        _ => Err(CryptoError::DecryptionError(format!(
+            "Parametr pracy scrypt {} przekracza dopuszczalne {}",
+            text, MAX_SCRYPT_WORK_FACTOR
+        ))),
+    }
+}
+
+/// Nowa tożsamość X25519: (klucz `AGE-SECRET-KEY-1...`, odbiorca `age1...`)
+pub fn generate_identity() -> (String, String) {
+    let (private, public) = PrivateKey::generate();
+    (
+        bech32::encode(IDENTITY_HRP, &private.to_bytes()).to_ascii_uppercase(),
+        bech32::encode(RECIPIENT_HRP, public.as_bytes()),
+    )
+}
+
+/// Linie listy kluczy bez pustych linii i komentarzy `#`
+fn key_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Odbiorcy z listy kluczy `age1...` (po jednym w linii lub oddzielonych spacjami).
+/// Hasło nigdy nie jest odgadywane z treści – służy do tego `AgeRecipient::passphrase`.
+pub fn parse_recipients(text: &str) -> Result<Vec<AgeRecipient>, CryptoError> {
+    let recipients = key_lines(text)
+        .flat_map(str::split_whitespace)
+        .map(AgeRecipient::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidKey(
+            "Podaj co najmniej jednego odbiorcę age (age1...)".to_string(),
+        ));
+    }
+    Ok(recipients)
+}
+
+/// Tożsamości z pliku w formacie `age-keygen` (komentarze `#` są pomijane)
+pub fn parse_identities(text: &str) -> Result<Vec<AgeIdentity>, CryptoError> {
+    let identities = key_lines(text)
+        .map(AgeIdentity::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if identities.is_empty() {
+        return Err(CryptoError::InvalidKey(
+            "Podaj tożsamość age (AGE-SECRET-KEY-1...)".to_string(),
+        ));
+    }
+    Ok(identities)
+}
+
+fn write_header(stanzas: &[Stanza], file_key: &[u8]) -> Vec<u8> {
+    let mut header = format!("{}\n", VERSION_LINE);
+    for stanza in stanzas {
+        header.push_str("-> ");
+        header.push_str(&stanza.kind);
+        for arg in &stanza.args {
+            header.push(' ');
+            header.push_str(arg);
+        }
+        header.push('\n');
+        // Ostatnia linia treści jest zawsze krótsza niż 64 znaki (może być pusta)
+        let body = b64_encode(&stanza.body);
+        let mut rest = body.as_str();
+        while rest.len() >= COLUMNS {
+            header.push_str(&rest[..COLUMNS]);
+            header.push('\n');
+            rest = &rest[COLUMNS..];
+        }
+        header.push_str(rest);
+        header.push('\n');
+    }
+    header.push_str("---");
+    let mac = header_mac(file_key, header.as_bytes())
+        .finalize()
+        .into_bytes();
+    header.push(' ');
+    header.push_str(&b64_encode(&mac));
+    header.push('\n');
+    header.into_bytes()
+}
+
+/// Czyta linie nagłówka zakończone `\n`
+struct HeaderReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> HeaderReader<'a> {
+    fn next_line(&mut self) -> Result<&'a str, CryptoError> {
+        let rest = &self.data[self.position..];
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| format_error("niekompletny nagłówek"))?;
+        self.position += end + 1;
+        std::str::from_utf8(&rest[..end]).map_err(|_| format_error("nagłówek nie jest tekstem"))
+    }
+}
+
+/// Parsuje nagłówek i zwraca stanze, MAC, zakres bajtów objęty MAC-iem oraz początek ładunku
+fn read_header(data: &[u8]) -> Result<(Vec<Stanza>, Vec<u8>, usize, usize), CryptoError> {
+    let mut reader = HeaderReader { data, position: 0 };
+    if reader.next_line()? != VERSION_LINE {
+        return Err(format_error(
+            "nieobsługiwana wersja (oczekiwano age-encryption.org/v1)",
+        ));
+    }
+
+    let mut stanzas = Vec::new();
+    loop {
+        let line_start = reader.position;
+        let line = reader.next_line()?;
+        if let Some(mac) = line.strip_prefix("--- ") {
+            let mac = b64_decode(mac)?;
+            return Ok((stanzas, mac, line_start + 3, reader.position));
+        }
+        let Some(stanza_line) = line.strip_prefix("-> ") else {
+            return Err(format_error(format!(
+                "nieoczekiwana linia nagłówka: {}",
+                line
+            )));
+        };
+        let mut parts = stanza_line.split(' ');
+        let kind = parts.next().unwrap_or_default().to_string();
+        let args: Vec<String> = parts.map(str::to_string).collect();
+        if kind.is_empty() || args.iter().any(String::is_empty) {
+            return Err(format_error("pusty argument stanzy"));
+        }
+
+        let mut body = String::new();
+        loop {
+            let body_line = reader.next_line()?;
+            if body_line.len() > COLUMNS {
+                return Err(format_error("zbyt długa linia treści stanzy"));
+            }
+            body.push_str(body_line);
+            if body_line.len() < COLUMNS {
+                break;
+            }
+        }
+        stanzas.push(Stanza {
+            kind,
+            args,
+            body: b64_decode(&body)?,
+        });
+    }
+}
+
+fn payload_nonce(counter: u64, last: bool) -> Nonce {
+    let mut nonce = Nonce::default();
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Szyfruje dane w formacie age v1 dla podanych odbiorców
+pub fn encrypt(
+    plaintext: &[u8],
+    recipients: &[AgeRecipient],
+    armor: bool,
+) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidKey("Brak odbiorców age".to_string()));
+    }
+    if recipients.len() > 1
+        && recipients
+            .iter()
+            .any(|r| matches!(r, AgeRecipient::Scrypt { .. }))
+    {
+        return Err(CryptoError::InvalidKey(
+            "Hasło (scrypt) musi być jedynym odbiorcą pliku age".to_string(),
+        ));
+    }
+
+    let mut file_key = [0u8; FILE_KEY_LEN];
+    OsRng.fill_bytes(&mut file_key);
+    let stanzas = recipients
+        .iter()
+        .map(|recipient| recipient.wrap(&file_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut output = write_header(&stanzas, &file_key);
+
+    let mut nonce = [0u8; PAYLOAD_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    output.extend_from_slice(&nonce);
+    let payload_key = hkdf_sha256(&file_key, &nonce, b"payload");
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&payload_key));
+
+    // Pusty plik to jeden pusty fragment oznaczony jako ostatni
+    let chunks: Vec<&[u8]> = if plaintext.is_empty() {
+        vec![&[]]
+    } else {
+        plaintext.chunks(CHUNK_SIZE).collect()
+    };
+    for (counter, chunk) in chunks.iter().enumerate() {
+        let last = counter + 1 == chunks.len();
+        let sealed = cipher
+            .encrypt(&payload_nonce(counter as u64, last), *chunk)
+            .map_err(|_| {
+                CryptoError::InvalidFormat("Błąd szyfrowania fragmentu age".to_string())
+            })?;
+        output.extend_from_slice(&sealed);
+    }
+
+    if armor {
+        return Ok(pem::encode(ARMOR_LABEL, &output).into_bytes());
+    }
+    Ok(output)
+}
+
+/// Odszyfrowuje plik age (binarny lub z pancerzem ASCII) jedną z tożsamości
+pub fn decrypt(data: &[u8], identities: &[AgeIdentity]) -> Result<Vec<u8>, CryptoError> {
+    let armored = std::str::from_utf8(data)
+        .ok()
+        .filter(|text| pem::is_pem(text))
+        .map(pem::decode)
+        .transpose()?;
+    let data = match &armored {
+        Some((label, decoded)) if label == ARMOR_LABEL => decoded.as_slice(),
+        Some((label, _)) => {
+            return Err(format_error(format!(
+                "nieoczekiwana etykieta pancerza {}",
+                label
+            )));
+        }
+        None => data,
+    };
+
+    let (stanzas, mac, mac_end, payload_start) = read_header(data)?;
+    if stanzas.iter().any(|stanza| stanza.kind == "scrypt") && stanzas.len() > 1 {
+        return Err(format_error("stanza scrypt musi być jedyną stanzą"));
+    }
+
+    let mut file_key = None;
+    'search: for stanza in &stanzas {
+        for identity in identities {
+            if let Some(key) = identity.unwrap(stanza)? {
+                file_key = Some(key);
+                break 'search;
+            }
+        }
+    }
+    let file_key = file_key.ok_or_else(|| {
+        CryptoError::DecryptionError(
+            "Żadna z tożsamości nie pasuje do odbiorców pliku age".to_string(),
+        )
+    })?;
+
+    header_mac(&file_key, &data[..mac_end])
+        .verify_slice(&mac)
+        .map_err(|_| CryptoError::DecryptionError("Niepoprawny MAC nagłówka age".to_string()))?;
+
+    let payload = &data[payload_start..];
+    if payload.len() < PAYLOAD_NONCE_LEN + TAG_LEN {
+        return Err(format_error("brak ładunku"));
+    }
+    let (nonce, ciphertext) = payload.split_at(PAYLOAD_NONCE_LEN);
+    let payload_key = hkdf_sha256(&file_key, nonce, b"payload");
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&payload_key));
+
+    let chunks: Vec<&[u8]> = ciphertext.chunks(CHUNK_SIZE + TAG_LEN).collect();
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for (counter, chunk) in chunks.iter().enumerate() {
+        let last = counter + 1 == chunks.len();
+        if chunk.len() < TAG_LEN || (last && counter > 0 && chunk.len() == TAG_LEN) {
+            return Err(format_error("niepoprawny fragment ładunku"));
+        }
+        let opened = cipher
+            .decrypt(&payload_nonce(counter as u64, last), *chunk)
+            .map_err(|_| {
+                CryptoError::DecryptionError(
+                    "Fragment pliku age został zmodyfikowany lub obcięty".to_string(),
+                )
+            })?;
+        plaintext.extend_from_slice(&opened);
+    }
+    Ok(plaintext)
+}
+
+/// Szyfrowanie zgodne z narzędziem `age`: klucz to lista odbiorców `age1...`,
+/// a przy odszyfrowaniu plik tożsamości `AGE-SECRET-KEY-1...`. Podanie
+/// `passphrase` wybiera tryb hasła (scrypt) i wtedy klucz nie jest używany.
+pub struct AgeCipher {
+    key: String,
+    passphrase: Option<String>,
+    armor: bool,
+}
+
+impl AgeCipher {
+    pub fn new(key: &str, passphrase: Option<&str>, armor: bool) -> Result<Self, CryptoError> {
+        let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+        if passphrase.is_none() && key.trim().is_empty() {
+            return Err(CryptoError::InvalidKey(
+                "Klucz lub hasło age jest wymagane".to_string(),
+            ));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            passphrase: passphrase.map(str::to_string),
+            armor,
+        })
+    }
+
+    fn recipients(&self) -> Result<Vec<AgeRecipient>, CryptoError> {
+        match &self.passphrase {
+            Some(passphrase) => Ok(vec![AgeRecipient::passphrase(passphrase)]),
+            None => parse_recipients(&self.key),
+        }
+    }
+
+    fn identities(&self) -> Result<Vec<AgeIdentity>, CryptoError> {
+        match &self.passphrase {
+            Some(passphrase) => Ok(vec![AgeIdentity::Scrypt(passphrase.clone())]),
+            None => parse_identities(&self.key),
+        }
+    }
+}
+
+impl Algorithm for AgeCipher {
+    fn encrypt(&self, file_path: &str) -> Result<String, CryptoError> {
+        log(
+            LogLevel::INFO,
+            "age",
+            format!("Rozpoczynanie szyfrowania age dla pliku: {}", file_path),
+        );
+        let plaintext = fs::read(file_path)?;
+        let recipients = self.recipients()?;
+        match recipients.as_slice() {
+            [AgeRecipient::Scrypt { work_factor, .. }] => log(
+                LogLevel::INFO,
+                "age",
+                format!("Odbiorca: hasło (scrypt, log2 N = {})", work_factor),
+            ),
+            _ => log(
+                LogLevel::INFO,
+                "age",
+                format!("Liczba odbiorców X25519: {}", recipients.len()),
+            ),
+        }
+
+        let encrypted = encrypt(&plaintext, &recipients, self.armor)?;
+        let output_path = file_handler::create_output_path_with_suffix(file_path, "_encrypted");
+        fs::write(&output_path, &encrypted).map_err(|err| {
+            CryptoError::FileWriteError(format!("Nie można zapisać do pliku: {}", err))
+        })?;
+        log(
+            LogLevel::INFO,
+            "age",
+            format!(
+                "Szyfrowanie zakończone ({} bajtów, {}). Zapisano do: {}",
+                encrypted.len(),
+                if self.armor {
+                    "pancerz ASCII"
+                } else {
+                    "binarnie"
+                },
+                output_path
+            ),
+        );
+        Ok(output_path)
+    }
+
+    fn decrypt(&self, file_path: &str) -> Result<String, CryptoError> {
+        log(
+            LogLevel::INFO,
+            "age",
+            format!("Rozpoczynanie deszyfrowania age dla pliku: {}", file_path),
+        );
+        let data = fs::read(file_path)?;
+        let identities = self.identities()?;
+        let plaintext = decrypt(&data, &identities)?;
+
+        let output_path = file_handler::create_output_path_with_suffix(file_path, "_decrypted");
+        fs::write(&output_path, &plaintext).map_err(|err| {
+            CryptoError::FileWriteError(format!("Nie można zapisać do pliku: {}", err))
+        })?;
+        log(
+            LogLevel::INFO,
+            "age",
+            format!("Deszyfrowanie zakończone. Zapisano do: {}", output_path),
+        );
+        Ok(output_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_vectors::hex;
+
+    /// Pliki zapisane niezależną implementacją specyfikacji age v1 (Python,
+    /// biblioteka `cryptography`); stanza scrypt ma log2 N = 10, żeby test
+    /// był szybki
+    const IDENTITY: &str =
+        "AGE-SECRET-KEY-1ZQLZ4J75QU2LKCSJA09CQ9XNN5F5THF7X7TQR7LSUMX8LDSV89LQ8EZPMJ";
+    const RECIPIENT: &str = "age1mv5vseaulacdepw6tjqz0tnrd42uxk30egagw7vksn4rzmjnhaeqc6vx2k";
+    const PASSPHRASE: &str = "przykładowe hasło";
+    const PLAINTEXT: &[u8] = b"age interop\n";
+    const X25519_HEADER: &str = "age-encryption.org/v1
+-> X25519 f7Tsw0G+5yxqRi4mrbNoOHrSAkYdRR1UkyifqqS5P2A
+9ryLt6RjAknDc8VxtiFBYqm+sh/wcj32E34NdgNW8V4
+--- HrmrcHf2hwokMZj0A61vIUsEvfDHtpcYqnIgFFquJpk
+";
+    const X25519_PAYLOAD: &str = "b97bb9a47796a9196f3a9db9194b0511f41d8647b3c7531091c91b779fb370e7
+                                  568c1e43d80fd00daa8cec11";
+    const X25519_ARMORED: &str = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IFgyNTUxOSAvaUNlYTNqT1JBdnJmSW05
+NnVYb2doWlhkamk1T1djT2VVa09hclgwRGtRCmpHWVpIWm1aZ1E2ZVNYZDlZSUw5
+cTBYdXpyQTZZNVpzbUZwV0ZBYkpmMW8KLS0tIG9YMXpNNXZFdlM5VTJVZ1ZMcVVK
+eEU1S3d4OGI1aXpwQkhxRWxBdjVTSWsKTPRiJpaicYat2PDFmf90CtZaIYPm4h3z
+mP6lBR+xr97wyMqovee5IepBs+c=
+-----END AGE ENCRYPTED FILE-----
+";
+    const SCRYPT_HEADER: &str = "age-encryption.org/v1
+-> scrypt 6hyxbNYOXJR32KGQWS0a3w 10
+KGFF4LMvxJhTaGxFQPlsIPL4HdhcKgFFN8VIFmU5/+k
+--- GHnOfTO4FNN0K/G+hq3qTqEf3Ysfi6ohyqhL7EXyGto
+";
+    const SCRYPT_PAYLOAD: &str = "317ec3645d612f1f1440b4bf5233fcd065e1a3c41ee3f35525cded55aab464ed
+                                  9d1eb9c229a3c6572e6d9185";
+    const SCRYPT_ARMORED: &str = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IHNjcnlwdCBCN0xkekVlclJEVnJXd1gw
+TWVtNFVBIDEwCkNoSGFLQytjOHF5aVVLQlpaaENOMjFSenIybkNvRktndE15SVo3
+OVpvUDgKLS0tIFJmeXJ3TkhhTFd2NWRKdytPeU1kZzVSWGxoVnFYb295ZEVDc3U4
+cVErWGsKsreulgzyuzFiBFdtsL46HPFhcuXK3HZPfqT5FwUl1j+CgWqzo5NVw6Sv
+YUY=
+-----END AGE ENCRYPTED FILE-----
+";
+
+    fn binary(header: &str, payload: &str) -> Vec<u8> {
+        [header.as_bytes(), &hex(payload)].concat()
+    }
+
+    fn identity() -> Vec<AgeIdentity> {
+        parse_identities(IDENTITY).unwrap()
+    }
+
+    #[test]
+    fn decrypts_files_written_by_another_implementation() {
+        let AgeIdentity::X25519(private) = &identity()[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            bech32::encode(RECIPIENT_HRP, private.public_key().as_bytes()),
+            RECIPIENT
+        );
+
+        for file in [
+            binary(X25519_HEADER, X25519_PAYLOAD),
+            X25519_ARMORED.as_bytes().to_vec(),
+        ] {
+            assert_eq!(decrypt(&file, &identity()).unwrap(), PLAINTEXT);
+        }
+        let passphrase = [AgeIdentity::Scrypt(PASSPHRASE.to_string())];
+        for file in [
+            binary(SCRYPT_HEADER, SCRYPT_PAYLOAD),
+            SCRYPT_ARMORED.as_bytes().to_vec(),
+        ] {
+            assert_eq!(decrypt(&file, &passphrase).unwrap(), PLAINTEXT);
+            let wrong = [AgeIdentity::Scrypt("inne hasło".to_string())];
+            assert!(decrypt(&file, &wrong).is_err());
+        }
+    }
+
+    #[test]
+    fn header_mac_covers_the_whole_header() {
+        let mut header = X25519_HEADER.to_string();
+        header.replace_range(header.len() - 3..header.len() - 2, "A");
+        assert!(matches!(
+            decrypt(&binary(&header, X25519_PAYLOAD), &identity()),
+            Err(CryptoError::DecryptionError(message)) if message.contains("MAC")
+        ));
+
+        // Dodatkowa stanza nieznanego typu, dopisana bez przeliczenia MAC
+        let header = X25519_HEADER.replace("--- ", "-> grease-stanza arg\n\n--- ");
+        assert!(matches!(
+            decrypt(&binary(&header, X25519_PAYLOAD), &identity()),
+            Err(CryptoError::DecryptionError(message)) if message.contains("MAC")
+        ));
+    }
+
+    /// Plik age z ładunkiem z podanych fragmentów i flag „ostatni fragment”
+    fn file_with_chunks(recipient: &AgeRecipient, chunks: &[(&[u8], bool)]) -> Vec<u8> {
+        let file_key = [7u8; FILE_KEY_LEN];
+        let mut file = write_header(&[recipient.wrap(&file_key).unwrap()], &file_key);
+        let nonce = [9u8; PAYLOAD_NONCE_LEN];
+        file.extend_from_slice(&nonce);
+        let payload_key = hkdf_sha256(&file_key, &nonce, b"payload");
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&payload_key));
+        for (counter, (chunk, last)) in chunks.iter().enumerate() {
+            let nonce = payload_nonce(counter as u64, *last);
+            file.extend(cipher.encrypt(&nonce, *chunk).unwrap());
+        }
+        file
+    }
+
+    #[test]
+    fn stream_rejects_truncation_and_misplaced_final_chunks() {
+        let recipient = AgeRecipient::parse(RECIPIENT).unwrap();
+        let full = vec![0x61; CHUNK_SIZE];
+        let valid = file_with_chunks(&recipient, &[(&full, false), (b"end", true)]);
+        let mut expected = full.clone();
+        expected.extend_from_slice(b"end");
+        assert_eq!(decrypt(&valid, &identity()).unwrap(), expected);
+
+        let rejected = [
+            // Bez fragmentu oznaczonego jako ostatni
+            file_with_chunks(&recipient, &[(b"data", false)]),
+            // Obcięty na granicy fragmentów
+            file_with_chunks(&recipient, &[(&full, false)]),
+            valid[..valid.len() - (3 + TAG_LEN)].to_vec(),
+            // Obcięty wewnątrz fragmentu
+            valid[..valid.len() - 1].to_vec(),
+            // Flaga „ostatni” przed końcem strumienia
+            file_with_chunks(&recipient, &[(&full, true), (b"end", true)]),
+            // Pusty ostatni fragment jest dozwolony tylko dla pustego pliku
+            file_with_chunks(&recipient, &[(&full, false), (b"", true)]),
+            // Niepełny fragment w środku strumienia
+            file_with_chunks(&recipient, &[(b"short", false), (b"end", true)]),
+        ];
+        for file in rejected {
+            assert!(decrypt(&file, &identity()).is_err());
+        }
+        assert_eq!(
+            decrypt(&file_with_chunks(&recipient, &[(b"", true)]), &identity()).unwrap(),
+            b""
+        );
+    }
+
+    #[test]
+    fn text_without_age_keys_is_not_a_passphrase() {
+        let (identity, recipient) = generate_identity();
+        for key in [
+            "correct horse battery staple",
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIGb5c3Vyb3JhbmdlIGtleSBmb3IgdGVzdHM user@host",
+            &recipient[..recipient.len() - 1],
+            &format!("{} {}", recipient, "age1typo"),
+        ] {
+            assert!(matches!(
+                parse_recipients(key),
+                Err(CryptoError::InvalidKey(_))
+            ));
+        }
+        assert!(matches!(
+            parse_identities("hasło"),
+            Err(CryptoError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            parse_recipients("# tylko komentarz"),
+            Err(CryptoError::InvalidKey(_))
+        ));
+
+        assert_eq!(parse_recipients(&recipient).unwrap().len(), 1);
+        let file = format!("# public key: {}\n{}\n", recipient, identity);
+        assert_eq!(parse_identities(&file).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn passphrase_is_used_only_when_given_explicitly() {
+        let keys = AgeCipher::new("hasło", None, false).unwrap();
+        assert!(matches!(keys.recipients(), Err(CryptoError::InvalidKey(_))));
+        assert!(matches!(keys.identities(), Err(CryptoError::InvalidKey(_))));
+
+        let passphrase = AgeCipher::new("", Some("hasło"), false).unwrap();
+        assert!(matches!(
+            passphrase.recipients().unwrap().as_slice(),
+            [AgeRecipient::Scrypt { passphrase, .. }] if passphrase == "hasło"
+        ));
+        assert!(matches!(
+            passphrase.identities().unwrap().as_slice(),
+            [AgeIdentity::Scrypt(passphrase)] if passphrase == "hasło"
+        ));
+        assert!(AgeCipher::new(" ", Some(""), false).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use super::EcdhError;
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha384, Sha512};
+
+/// Hash function driving HKDF
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum KdfHash {
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl KdfHash {
+    pub fn from_name(name: &str) -> Result<Self, EcdhError> {
+        match name.trim().to_ascii_lowercase().replace('-', "").as_str() {
+            "sha256" => Ok(Self::Sha256),
+            "sha384" => Ok(Self::Sha384),
+            "sha512" => Ok(Self::Sha512),
+            _ => Err(EcdhError::UnsupportedHash(name.to_string())),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    /// Digest size, which is also the length of the extracted PRK
+    pub fn output_len(self) -> usize {
+        match self {
+            Self::Sha256 => 32,
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// RFC 5869 caps the expanded output at 255 hash blocks
+    pub fn max_expand_len(self) -> usize {
+        255 * self.output_len()
+    }
+}
+
+/// Which HKDF stages to run (RFC 5869, section 2)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum KdfMode {
+    /// Extract a PRK from the input, then expand it
+    #[default]
+    ExtractAndExpand,
+    /// Only the extract step; the output is the PRK itself
+    Extract,
+    /// The input already is a uniformly random PRK and is only expanded
+    Expand,
+}
+
+impl KdfMode {
+    pub fn from_name(name: &str) -> Result<Self, EcdhError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "extract-and-expand" | "full" => Ok(Self::ExtractAndExpand),
+            "extract" | "extract-only" => Ok(Self::Extract),
+            "expand" | "expand-only" => Ok(Self::Expand),
+            _ => Err(EcdhError::UnsupportedKdfMode(name.to_string())),
+        }
+    }
+}
+
+/// HKDF parameters; `length: None` means the hash output size
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KdfParams {
+    pub hash: KdfHash,
+    pub mode: KdfMode,
+    pub length: Option<usize>,
+}
+
+impl KdfParams {
+    pub fn output_len(&self) -> usize {
+        self.length.unwrap_or(self.hash.output_len())
+    }
+
+    /// Runs the selected stages over `ikm` (the PRK in expand-only mode).
+    /// The salt is ignored by expand-only and the info by extract-only.
+    pub fn derive(
+        &self,
+        ikm: &[u8],
+        salt: Option<&[u8]>,
+        info: Option<&[u8]>,
+    ) -> Result<Vec<u8>, EcdhError> {
+        let length = self.output_len();
+        let max = match self.mode {
+            KdfMode::Extract => self.hash.output_len(),
+            _ => self.hash.max_expand_len(),
+        };
+        let min = match self.mode {
+            KdfMode::Extract => self.hash.output_len(),
+            _ => 1,
+        };
+        if !(min..=max).contains(&length) {
+            return Err(EcdhError::InvalidKdfLength { length, min, max });
+        }
+
+        let info = info.unwrap_or(&[]);
+        let mut okm = vec![0u8; length];
+        macro_rules! run {
+            ($hash:ty) => {
+                match self.mode {
+                    KdfMode::ExtractAndExpand => Hkdf::<$hash>::new(salt, ikm)
+                        .expand(info, &mut okm)
+                        .map_err(|_| EcdhError::KdfError)?,
+                    KdfMode::Extract => {
+                        okm.copy_from_slice(&Hkdf::<$hash>::extract(salt, ikm).0);
+                    }
+                    KdfMode::Expand => Hkdf::<$hash>::from_prk(ikm)
+                        .map_err(|_| EcdhError::InvalidPrk(self.hash.output_len()))?
+                        .expand(info, &mut okm)
+                        .map_err(|_| EcdhError::KdfError)?,
+                }
+            };
+        }
+        match self.hash {
+            KdfHash::Sha256 => run!(Sha256),
+            KdfHash::Sha384 => run!(Sha384),
+            KdfHash::Sha512 => run!(Sha512),
+        }
+        Ok(okm)
+    }
+}
@@ -0,0 +1,440 @@
+use super::{
+    Curve, EcdhError, jwk_error, parse_jwk_object, pkcs8_error, pkcs8_from_pem, pkcs8_to_pem,
+};
+use crate::utils::der::{self, DerReader};
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::{jwk, pem};
+use base64::{Engine as _, engine::general_purpose};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use serde_json::{Value, json};
+
+/// id-ecPublicKey (RFC 5480)
+const EC_PUBLIC_KEY_OID: &[u64] = &[1, 2, 840, 10045, 2, 1];
+/// prime256v1 / secp256r1
+const P256_OID: &[u64] = &[1, 2, 840, 10045, 3, 1, 7];
+/// secp384r1
+const P384_OID: &[u64] = &[1, 3, 132, 0, 34];
+const PEM_SPKI: &str = "PUBLIC KEY";
+/// Explicit tags of the optional ECPrivateKey fields (RFC 5915)
+const TAG_EC_PARAMETERS: u8 = 0xa0;
+const TAG_EC_PUBLIC_KEY: u8 = 0xa1;
+
+fn not_nist(curve: Curve) -> EcdhError {
+    EcdhError::CurveMismatch {
+        expected: "P-256 or P-384",
+        found: curve.name(),
+    }
+}
+
+fn ensure_curve(expected: Curve, found: Curve) -> Result<(), EcdhError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(EcdhError::CurveMismatch {
+            expected: expected.name(),
+            found: found.name(),
+        })
+    }
+}
+
+/// Length of a field element and of a private scalar in bytes
+fn field_size(curve: Curve) -> usize {
+    match curve {
+        Curve::P384 => 48,
+        _ => 32,
+    }
+}
+
+fn curve_oid(curve: Curve) -> &'static [u64] {
+    match curve {
+        Curve::P384 => P384_OID,
+        _ => P256_OID,
+    }
+}
+
+fn curve_from_oid(oid: &[u64]) -> Result<Curve, EcdhError> {
+    match oid {
+        P256_OID => Ok(Curve::P256),
+        P384_OID => Ok(Curve::P384),
+        _ => Err(EcdhError::InvalidPkcs8(
+            "unsupported named curve, expected P-256 or P-384".to_string(),
+        )),
+    }
+}
+
+/// AlgorithmIdentifier shared by SPKI and PKCS#8: id-ecPublicKey with a named curve
+fn algorithm_identifier(curve: Curve) -> Vec<u8> {
+    der::encode_sequence(&[
+        der::encode_oid(EC_PUBLIC_KEY_OID),
+        der::encode_oid(curve_oid(curve)),
+    ])
+}
+
+fn read_algorithm_identifier(reader: &mut DerReader) -> Result<Curve, EcdhError> {
+    let mut alg = reader.read_sequence().map_err(pkcs8_error)?;
+    if alg.read_oid().map_err(pkcs8_error)? != EC_PUBLIC_KEY_OID {
+        return Err(EcdhError::InvalidPkcs8("not an EC key".to_string()));
+    }
+    curve_from_oid(&alg.read_oid().map_err(pkcs8_error)?)
+}
+
+fn jwk_curve(value: &Value) -> Result<Curve, EcdhError> {
+    jwk::require_kty(value, "EC").map_err(jwk_error)?;
+    match value.get("crv").and_then(Value::as_str) {
+        Some("P-256") => Ok(Curve::P256),
+        Some("P-384") => Ok(Curve::P384),
+        other => Err(EcdhError::InvalidJwk(format!(
+            "expected crv P-256 or P-384, got {}",
+            other.unwrap_or("none")
+        ))),
+    }
+}
+
+fn read_coordinate(value: &Value, name: &str, curve: Curve) -> Result<Vec<u8>, EcdhError> {
+    let bytes = jwk::required_member(value, name).map_err(jwk_error)?;
+    if bytes.len() != field_size(curve) {
+        return Err(EcdhError::InvalidJwk(format!(
+            "member '{}' must be {} bytes",
+            name,
+            field_size(curve)
+        )));
+    }
+    Ok(bytes)
+}
+
+pub enum PublicKey {
+    P256(p256::PublicKey),
+    P384(p384::PublicKey),
+}
+
+impl PublicKey {
+    pub fn curve(&self) -> Curve {
+        match self {
+            PublicKey::P256(_) => Curve::P256,
+            PublicKey::P384(_) => Curve::P384,
+        }
+    }
+
+    /// Parses a SEC1 point, compressed (`02`/`03`) or uncompressed (`04`). The point
+    /// must lie on the curve and must not be the point at infinity.
+    pub fn from_sec1_bytes(curve: Curve, bytes: &[u8]) -> Result<Self, EcdhError> {
+        let parsed = match curve {
+            Curve::P256 => p256::PublicKey::from_sec1_bytes(bytes).map(PublicKey::P256),
+            Curve::P384 => p384::PublicKey::from_sec1_bytes(bytes).map(PublicKey::P384),
+            Curve::X25519 | Curve::X448 => return Err(not_nist(curve)),
+        };
+        parsed.map_err(|_| EcdhError::InvalidPoint(curve.name()))
+    }
+
+    pub fn to_sec1_bytes(&self, compressed: bool) -> Vec<u8> {
+        match self {
+            PublicKey::P256(key) => key.to_encoded_point(compressed).as_bytes().to_vec(),
+            PublicKey::P384(key) => key.to_encoded_point(compressed).as_bytes().to_vec(),
+        }
+    }
+
+    pub fn to_base64(&self, compressed: bool) -> String {
+        general_purpose::STANDARD.encode(self.to_sec1_bytes(compressed))
+    }
+
+    pub fn from_base64(curve: Curve, s: &str) -> Result<Self, EcdhError> {
+        let bytes = general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| EcdhError::InvalidKeyFormat)?;
+        Self::from_sec1_bytes(curve, &bytes)
+    }
+
+    /// Affine coordinates `x` and `y`, each padded to the field size
+    fn coordinates(&self) -> (Vec<u8>, Vec<u8>) {
+        let point = self.to_sec1_bytes(false);
+        let size = field_size(self.curve());
+        (point[1..=size].to_vec(), point[size + 1..].to_vec())
+    }
+
+    /// SubjectPublicKeyInfo as defined in RFC 5480, with an uncompressed point
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        der::encode_sequence(&[
+            algorithm_identifier(self.curve()),
+            der::encode_bit_string(&self.to_sec1_bytes(false)),
+        ])
+    }
+
+    pub fn from_spki_der(data: &[u8]) -> Result<Self, EcdhError> {
+        let mut outer = DerReader::new(data);
+        let mut seq = outer.read_sequence().map_err(pkcs8_error)?;
+        outer.finish().map_err(pkcs8_error)?;
+        let curve = read_algorithm_identifier(&mut seq)?;
+        Self::from_sec1_bytes(curve, seq.read_bit_string().map_err(pkcs8_error)?)
+    }
+
+    /// SHA-256 over the SPKI encoding, so it matches `openssl pkey -pubout -outform DER | sha256sum`
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(&self.to_spki_der())
+    }
+
+    /// RFC 7638 thumbprint used as the JWK `kid`
+    pub fn thumbprint(&self) -> String {
+        let (x, y) = self.coordinates();
+        jwk::thumbprint(&[
+            ("crv", self.curve().name()),
+            ("kty", "EC"),
+            ("x", &jwk::b64url_encode(&x)),
+            ("y", &jwk::b64url_encode(&y)),
+        ])
+    }
+
+    pub fn to_jwk(&self) -> Value {
+        let (x, y) = self.coordinates();
+        json!({
+            "kty": "EC",
+            "crv": self.curve().name(),
+            "kid": self.thumbprint(),
+            "x": jwk::b64url_encode(&x),
+            "y": jwk::b64url_encode(&y)
+        })
+    }
+
+    pub fn from_jwk(value: &Value) -> Result<Self, EcdhError> {
+        let curve = jwk_curve(value)?;
+        let point = [
+            vec![0x04],
+            read_coordinate(value, "x", curve)?,
+            read_coordinate(value, "y", curve)?,
+        ]
+        .concat();
+        Self::from_sec1_bytes(curve, &point)
+    }
+
+    /// Accepts a base64 SEC1 point, an EC JWK object or an SPKI PEM block; keys
+    /// on a different curve than `curve` are rejected
+    pub fn decode(curve: Curve, s: &str) -> Result<Self, EcdhError> {
+        let trimmed = s.trim();
+        let key = if pem::is_pem(trimmed) {
+            let (label, data) = pem::decode(trimmed).map_err(pkcs8_error)?;
+            if label != PEM_SPKI {
+                return Err(EcdhError::InvalidPkcs8(format!(
+                    "unexpected PEM label {}",
+                    label
+                )));
+            }
+            Self::from_spki_der(&data)?
+        } else {
+            match parse_jwk_object(trimmed)? {
+                Some(value) => Self::from_jwk(&value)?,
+                None => return Self::from_base64(curve, trimmed),
+            }
+        };
+        ensure_curve(curve, key.curve())?;
+        Ok(key)
+    }
+}
+
+pub enum PrivateKey {
+    P256(p256::SecretKey),
+    P384(p384::SecretKey),
+}
+
+impl PrivateKey {
+    pub fn generate(curve: Curve) -> Result<(Self, PublicKey), EcdhError> {
+        Self::generate_with_rng(curve, &mut OsRng)
+    }
+
+    /// Generates a key pair from the given randomness source; the same generator
+    /// state always yields the same key.
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(
+        curve: Curve,
+        rng: &mut R,
+    ) -> Result<(Self, PublicKey), EcdhError> {
+        let private = match curve {
+            Curve::P256 => PrivateKey::P256(p256::SecretKey::random(rng)),
+            Curve::P384 => PrivateKey::P384(p384::SecretKey::random(rng)),
+            Curve::X25519 | Curve::X448 => return Err(not_nist(curve)),
+        };
+        let public = private.public_key();
+        Ok((private, public))
+    }
+
+    pub fn curve(&self) -> Curve {
+        match self {
+            PrivateKey::P256(_) => Curve::P256,
+            PrivateKey::P384(_) => Curve::P384,
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            PrivateKey::P256(key) => PublicKey::P256(key.public_key()),
+            PrivateKey::P384(key) => PublicKey::P384(key.public_key()),
+        }
+    }
+
+    /// Big-endian private scalar, padded to the field size
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            PrivateKey::P256(key) => key.to_bytes().to_vec(),
+            PrivateKey::P384(key) => key.to_bytes().to_vec(),
+        }
+    }
+
+    /// The scalar must be in the range `1..n`
+    pub fn from_bytes(curve: Curve, bytes: &[u8]) -> Result<Self, EcdhError> {
+        if matches!(curve, Curve::X25519 | Curve::X448) {
+            return Err(not_nist(curve));
+        }
+        if bytes.len() != field_size(curve) {
+            return Err(EcdhError::InvalidKeyFormat);
+        }
+        let parsed = match curve {
+            Curve::P384 => p384::SecretKey::from_slice(bytes).map(PrivateKey::P384),
+            _ => p256::SecretKey::from_slice(bytes).map(PrivateKey::P256),
+        };
+        parsed.map_err(|_| EcdhError::InvalidKeyFormat)
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.to_bytes())
+    }
+
+    pub fn from_base64(curve: Curve, s: &str) -> Result<Self, EcdhError> {
+        let bytes = general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| EcdhError::InvalidKeyFormat)?;
+        Self::from_bytes(curve, &bytes)
+    }
+
+    pub fn to_jwk(&self) -> Value {
+        let mut value = self.public_key().to_jwk();
+        value["d"] = Value::String(jwk::b64url_encode(&self.to_bytes()));
+        value
+    }
+
+    pub fn from_jwk(value: &Value) -> Result<Self, EcdhError> {
+        let curve = jwk_curve(value)?;
+        let key = Self::from_bytes(curve, &read_coordinate(value, "d", curve)?)?;
+        if value.get("x").is_some() || value.get("y").is_some() {
+            let expected = PublicKey::from_jwk(value)?;
+            if expected.to_sec1_bytes(false) != key.public_key().to_sec1_bytes(false) {
+                return Err(EcdhError::InvalidJwk(
+                    "public members 'x' and 'y' do not match private key".to_string(),
+                ));
+            }
+        }
+        Ok(key)
+    }
+
+    /// PKCS#8 PrivateKeyInfo wrapping an RFC 5915 ECPrivateKey with the public point
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        let ec_private_key = der::encode_sequence(&[
+            der::encode_small_integer(1),
+            der::encode_octet_string(&self.to_bytes()),
+            der::encode_tlv(
+                TAG_EC_PUBLIC_KEY,
+                &der::encode_bit_string(&self.public_key().to_sec1_bytes(false)),
+            ),
+        ]);
+        der::encode_sequence(&[
+            der::encode_small_integer(0),
+            algorithm_identifier(self.curve()),
+            der::encode_octet_string(&ec_private_key),
+        ])
+    }
+
+    pub fn from_pkcs8_der(data: &[u8]) -> Result<Self, EcdhError> {
+        let mut outer = DerReader::new(data);
+        let mut seq = outer.read_sequence().map_err(pkcs8_error)?;
+        outer.finish().map_err(pkcs8_error)?;
+
+        let version = seq.read_small_integer().map_err(pkcs8_error)?;
+        if version > 1 {
+            return Err(EcdhError::InvalidPkcs8(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let curve = read_algorithm_identifier(&mut seq)?;
+
+        let mut inner = DerReader::new(seq.read_octet_string().map_err(pkcs8_error)?);
+        let mut ec_private_key = inner.read_sequence().map_err(pkcs8_error)?;
+        if ec_private_key.read_small_integer().map_err(pkcs8_error)? != 1 {
+            return Err(EcdhError::InvalidPkcs8(
+                "unsupported ECPrivateKey version".to_string(),
+            ));
+        }
+        let key = Self::from_bytes(
+            curve,
+            ec_private_key.read_octet_string().map_err(pkcs8_error)?,
+        )
+        .map_err(|_| EcdhError::InvalidPkcs8("invalid private scalar".to_string()))?;
+
+        while !ec_private_key.is_empty() {
+            let (tag, content) = ec_private_key.read_any().map_err(pkcs8_error)?;
+            let mut field = DerReader::new(content);
+            match tag {
+                TAG_EC_PARAMETERS => ensure_curve(
+                    curve,
+                    curve_from_oid(&field.read_oid().map_err(pkcs8_error)?)?,
+                )?,
+                TAG_EC_PUBLIC_KEY => {
+                    let point = field.read_bit_string().map_err(pkcs8_error)?;
+                    if PublicKey::from_sec1_bytes(curve, point)?.to_sec1_bytes(false)
+                        != key.public_key().to_sec1_bytes(false)
+                    {
+                        return Err(EcdhError::InvalidPkcs8(
+                            "public key does not match private key".to_string(),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(key)
+    }
+
+    /// PKCS#8 PEM, wrapped in PBES2 EncryptedPrivateKeyInfo when a passphrase is given
+    pub fn to_pem(&self, passphrase: Option<&str>) -> Result<String, EcdhError> {
+        pkcs8_to_pem(&self.to_pkcs8_der(), passphrase)
+    }
+
+    pub fn from_pem(text: &str, passphrase: Option<&str>) -> Result<Self, EcdhError> {
+        Self::from_pkcs8_der(&pkcs8_from_pem(text, passphrase)?)
+    }
+
+    /// Accepts a base64 scalar, an EC JWK object with `d` or a PKCS#8 PEM block
+    /// (optionally passphrase-encrypted); keys on a different curve are rejected
+    pub fn decode(curve: Curve, s: &str, passphrase: Option<&str>) -> Result<Self, EcdhError> {
+        let key = if pem::is_pem(s) {
+            Self::from_pem(s, passphrase)?
+        } else {
+            match parse_jwk_object(s)? {
+                Some(value) => Self::from_jwk(&value)?,
+                None => return Self::from_base64(curve, s),
+            }
+        };
+        ensure_curve(curve, key.curve())?;
+        Ok(key)
+    }
+
+    /// The x-coordinate of the shared point, as in SEC1 ECDH and `openssl pkeyutl -derive`
+    pub fn compute_shared_secret(&self, peer: &PublicKey) -> Result<Vec<u8>, EcdhError> {
+        match (self, peer) {
+            (PrivateKey::P256(key), PublicKey::P256(point)) => Ok(p256::ecdh::diffie_hellman(
+                key.to_nonzero_scalar(),
+                point.as_affine(),
+            )
+            .raw_secret_bytes()
+            .to_vec()),
+            (PrivateKey::P384(key), PublicKey::P384(point)) => Ok(p384::ecdh::diffie_hellman(
+                key.to_nonzero_scalar(),
+                point.as_affine(),
+            )
+            .raw_secret_bytes()
+            .to_vec()),
+            _ => Err(EcdhError::CurveMismatch {
+                expected: self.curve().name(),
+                found: peer.curve().name(),
+            }),
+        }
+    }
+}
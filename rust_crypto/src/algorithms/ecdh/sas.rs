@@ -0,0 +1,227 @@
+use super::{Curve, EcdhError};
+use crate::utils::fingerprint::emoji_sequence;
+use hkdf::Hkdf;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+/// Domain separation label mixed into the HKDF info
+const SAS_LABEL: &[u8] = b"rust_crypto ECDH SAS v1";
+/// Domain separation label of the public key commitment
+const COMMIT_LABEL: &[u8] = b"rust_crypto ECDH SAS commit v1";
+/// Number of emoji shown to the users (6 bits each, 42 bits in total)
+const EMOJI_COUNT: usize = 7;
+/// Bytes feeding the decimal form (taken before the emoji bytes)
+const DECIMAL_BYTES: usize = 5;
+
+/// Short authentication string compared out-of-band after a key exchange.
+///
+/// Both parties derive it from the shared secret and the two public keys,
+/// so a man-in-the-middle, who runs separate exchanges with each side,
+/// ends up with different strings on the two ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortAuthString([u8; 16]);
+
+impl ShortAuthString {
+    /// Derives the SAS with HKDF-SHA256.
+    ///
+    /// The public keys are sorted before hashing, so the result does not
+    /// depend on which party is "own" and which is "peer".
+    pub fn derive(
+        curve: Curve,
+        shared_secret: &[u8],
+        own_public: &[u8],
+        peer_public: &[u8],
+    ) -> Result<Self, EcdhError> {
+        let (first, second) = if own_public <= peer_public {
+            (own_public, peer_public)
+        } else {
+            (peer_public, own_public)
+        };
+
+        let info = length_prefixed(SAS_LABEL, &[curve.name().as_bytes(), first, second]);
+
+        let mut okm = [0u8; 16];
+        Hkdf::<Sha256>::new(None, shared_secret)
+            .expand(&info, &mut okm)
+            .map_err(|_| EcdhError::KdfError)?;
+        Ok(Self(okm))
+    }
+
+    /// Six digits in two groups, e.g. `042 917`
+    pub fn to_decimal(&self) -> String {
+        let value = self.0[..DECIMAL_BYTES]
+            .iter()
+            .fold(0u64, |acc, &byte| (acc << 8) | byte as u64)
+            % 1_000_000;
+        format!("{:03} {:03}", value / 1000, value % 1000)
+    }
+
+    pub fn to_emoji(&self) -> Vec<(&'static str, &'static str)> {
+        emoji_sequence(&self.0[DECIMAL_BYTES..], EMOJI_COUNT)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let emoji = self.to_emoji();
+        json!({
+            "decimal": self.to_decimal(),
+            "emoji": emoji.iter().map(|(symbol, _)| *symbol).collect::<Vec<_>>().join(" "),
+            "words": emoji.iter().map(|(_, word)| *word).collect::<Vec<_>>().join(" ")
+        })
+    }
+}
+
+/// SHA-256 commitment to a public key, sent before the key itself.
+///
+/// The decimal SAS carries only about 20 bits, so a man-in-the-middle who
+/// sees both public keys before choosing its own can try around 2^20 key
+/// pairs until the strings on the two ends collide. A party that reveals
+/// its key only after receiving the peer's commitment takes that choice
+/// away: the substituted keys are fixed blindly and match with probability
+/// of roughly one in a million.
+pub fn commit(curve: Curve, public: &[u8]) -> [u8; 32] {
+    Sha256::digest(length_prefixed(
+        COMMIT_LABEL,
+        &[curve.name().as_bytes(), public],
+    ))
+    .into()
+}
+
+/// Checks a revealed public key against the commitment received earlier
+pub fn verify_commitment(curve: Curve, public: &[u8], commitment: &[u8]) -> Result<(), EcdhError> {
+    if commit(curve, public).as_slice() == commitment {
+        Ok(())
+    } else {
+        Err(EcdhError::CommitmentMismatch)
+    }
+}
+
+fn length_prefixed(label: &[u8], fields: &[&[u8]]) -> Vec<u8> {
+    let mut encoded = label.to_vec();
+    for field in fields {
+        encoded.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        encoded.extend_from_slice(field);
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::ecdh::{PrivateKey, x448};
+
+    #[test]
+    fn both_parties_derive_the_same_string() {
+        let (alice, alice_public) = PrivateKey::generate();
+        let (bob, bob_public) = PrivateKey::generate();
+        let alice_secret = alice.compute_shared_secret(&bob_public).unwrap();
+        let bob_secret = bob.compute_shared_secret(&alice_public).unwrap();
+
+        let derive = |secret: &[u8], own: &[u8], peer: &[u8]| {
+            ShortAuthString::derive(Curve::X25519, secret, own, peer).unwrap()
+        };
+        let on_alice = derive(
+            &alice_secret,
+            alice_public.as_bytes(),
+            bob_public.as_bytes(),
+        );
+        let on_bob = derive(&bob_secret, bob_public.as_bytes(), alice_public.as_bytes());
+        assert_eq!(on_alice, on_bob);
+        assert_eq!(on_alice.to_json(), on_bob.to_json());
+
+        let decimal = on_alice.to_decimal();
+        assert_eq!(decimal.len(), 7);
+        assert!(
+            decimal[..3]
+                .bytes()
+                .chain(decimal[4..].bytes())
+                .all(|b| b.is_ascii_digit())
+        );
+        assert_eq!(on_alice.to_emoji().len(), EMOJI_COUNT);
+
+        // The same secret under another curve label gives another string
+        let relabelled = ShortAuthString::derive(
+            Curve::X448,
+            &alice_secret,
+            alice_public.as_bytes(),
+            bob_public.as_bytes(),
+        )
+        .unwrap();
+        assert_ne!(relabelled, on_alice);
+    }
+
+    #[test]
+    fn x448_parties_derive_the_same_string() {
+        let (alice, alice_public) = x448::PrivateKey::generate();
+        let (bob, bob_public) = x448::PrivateKey::generate();
+        let on_alice = ShortAuthString::derive(
+            Curve::X448,
+            &alice.compute_shared_secret(&bob_public).unwrap(),
+            alice_public.as_bytes(),
+            bob_public.as_bytes(),
+        )
+        .unwrap();
+        let on_bob = ShortAuthString::derive(
+            Curve::X448,
+            &bob.compute_shared_secret(&alice_public).unwrap(),
+            bob_public.as_bytes(),
+            alice_public.as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(on_alice, on_bob);
+    }
+
+    #[test]
+    fn substituted_keys_give_different_strings() {
+        let (alice, alice_public) = PrivateKey::generate();
+        let (bob, bob_public) = PrivateKey::generate();
+        let (mallory, mallory_public) = PrivateKey::generate();
+
+        // Mallory runs one exchange with each side
+        let on_alice = ShortAuthString::derive(
+            Curve::X25519,
+            &alice.compute_shared_secret(&mallory_public).unwrap(),
+            alice_public.as_bytes(),
+            mallory_public.as_bytes(),
+        )
+        .unwrap();
+        let on_bob = ShortAuthString::derive(
+            Curve::X25519,
+            &bob.compute_shared_secret(&mallory_public).unwrap(),
+            bob_public.as_bytes(),
+            mallory_public.as_bytes(),
+        )
+        .unwrap();
+        assert_ne!(on_alice, on_bob);
+        assert_eq!(
+            on_alice,
+            ShortAuthString::derive(
+                Curve::X25519,
+                &mallory.compute_shared_secret(&alice_public).unwrap(),
+                mallory_public.as_bytes(),
+                alice_public.as_bytes(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn commitments_bind_the_revealed_key() {
+        let (_, alice_public) = PrivateKey::generate();
+        let (_, mallory_public) = PrivateKey::generate();
+        let commitment = commit(Curve::X25519, alice_public.as_bytes());
+
+        assert!(verify_commitment(Curve::X25519, alice_public.as_bytes(), &commitment).is_ok());
+        assert!(matches!(
+            verify_commitment(Curve::X25519, mallory_public.as_bytes(), &commitment),
+            Err(EcdhError::CommitmentMismatch)
+        ));
+        assert!(matches!(
+            verify_commitment(Curve::X448, alice_public.as_bytes(), &commitment),
+            Err(EcdhError::CommitmentMismatch)
+        ));
+        assert!(matches!(
+            verify_commitment(Curve::X25519, alice_public.as_bytes(), &commitment[..31]),
+            Err(EcdhError::CommitmentMismatch)
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+use super::{
+    EcdhError, okp_from_pkcs8_der, okp_pkcs8_der, okp_public_jwk, okp_spki_der, okp_thumbprint,
+    parse_jwk_object, pkcs8_from_pem, pkcs8_to_pem, read_okp_member,
+};
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::{jwk, pem};
+use base64::{Engine as _, engine::general_purpose};
+use crypto_bigint::modular::constant_mod::{Residue, ResidueParams};
+use crypto_bigint::subtle::{Choice, ConditionallySelectable};
+use crypto_bigint::zeroize::Zeroize;
+use crypto_bigint::{Encoding, U448, impl_modulus};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use serde_json::Value;
+
+/// Length of X448 scalars and u-coordinates in bytes
+pub const KEY_LEN: usize = 56;
+/// id-X448 (RFC 8410)
+const X448_OID: &[u64] = &[1, 3, 101, 111];
+/// JWK `crv` name of X448 keys
+const X448_CRV: &str = "X448";
+/// The u-coordinate of the base point
+const BASE_POINT: [u8; KEY_LEN] = {
+    let mut point = [0u8; KEY_LEN];
+    point[0] = 5;
+    point
+};
+/// (A - 2) / 4 for curve448, A = 156326
+const A24: u32 = 39081;
+
+// p = 2^448 - 2^224 - 1
+impl_modulus!(
+    P448,
+    U448,
+    "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+);
+
+type FieldElement = Residue<P448, { U448::LIMBS }>;
+
+fn decode_u_coordinate(u: &[u8; KEY_LEN]) -> FieldElement {
+    // Non-canonical values (u >= p) are reduced modulo p, as RFC 7748 requires
+    FieldElement::new(&U448::from_le_slice(u))
+}
+
+/// Clears the two least significant bits and sets the most significant one
+fn clamp_scalar(k: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+    let mut clamped = *k;
+    clamped[0] &= 252;
+    clamped[KEY_LEN - 1] |= 128;
+    clamped
+}
+
+/// The X448 function from RFC 7748: a constant-time Montgomery ladder
+pub fn x448(k: &[u8; KEY_LEN], u: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+    let mut k = clamp_scalar(k);
+    let x_1 = decode_u_coordinate(u);
+    let a24 = FieldElement::new(&U448::from_u32(A24));
+    let (mut x_2, mut z_2) = (FieldElement::ONE, FieldElement::ZERO);
+    let (mut x_3, mut z_3) = (x_1, FieldElement::ONE);
+    let mut swap = 0u8;
+
+    for t in (0..KEY_LEN * 8).rev() {
+        let k_t = (k[t / 8] >> (t % 8)) & 1;
+        swap ^= k_t;
+        FieldElement::conditional_swap(&mut x_2, &mut x_3, Choice::from(swap));
+        FieldElement::conditional_swap(&mut z_2, &mut z_3, Choice::from(swap));
+        swap = k_t;
+
+        let a = x_2 + z_2;
+        let aa = a.square();
+        let b = x_2 - z_2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x_3 + z_3;
+        let d = x_3 - z_3;
+        let da = d * a;
+        let cb = c * b;
+        x_3 = (da + cb).square();
+        z_3 = x_1 * (da - cb).square();
+        x_2 = aa * bb;
+        z_2 = e * (aa + a24 * e);
+    }
+    FieldElement::conditional_swap(&mut x_2, &mut x_3, Choice::from(swap));
+    FieldElement::conditional_swap(&mut z_2, &mut z_3, Choice::from(swap));
+    k.zeroize();
+
+    // z^(p - 2) is the inverse of z, and maps 0 to 0
+    let exponent = P448::MODULUS.wrapping_sub(&U448::from_u8(2));
+    let result = (x_2 * z_2.pow(&exponent)).retrieve().to_le_bytes();
+    let mut out = [0u8; KEY_LEN];
+    out.copy_from_slice(&result);
+    out
+}
+
+pub struct PublicKeyBytes([u8; KEY_LEN]);
+
+impl PublicKeyBytes {
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.0)
+    }
+
+    pub fn from_base64(s: &str) -> Result<Self, EcdhError> {
+        let bytes = general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| EcdhError::InvalidKeyFormat)?;
+        bytes
+            .try_into()
+            .map(PublicKeyBytes)
+            .map_err(|_| EcdhError::InvalidPublicKey)
+    }
+
+    /// RFC 7638 thumbprint used as the JWK `kid`
+    pub fn thumbprint(&self) -> String {
+        okp_thumbprint(X448_CRV, &self.0)
+    }
+
+    /// SubjectPublicKeyInfo as defined in RFC 8410
+    pub fn to_spki_der(&self) -> Vec<u8> {
+        okp_spki_der(X448_OID, &self.0)
+    }
+
+    /// SHA-256 over the SPKI encoding, so it matches `openssl pkey -pubout -outform DER | sha256sum`
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(&self.to_spki_der())
+    }
+
+    pub fn to_jwk(&self) -> Value {
+        okp_public_jwk(X448_CRV, &self.0, self.thumbprint())
+    }
+
+    pub fn from_jwk(value: &Value) -> Result<Self, EcdhError> {
+        Ok(PublicKeyBytes(read_okp_member(value, X448_CRV, "x")?))
+    }
+
+    /// Accepts either a bare base64 key or an OKP JWK object
+    pub fn decode(s: &str) -> Result<Self, EcdhError> {
+        match parse_jwk_object(s)? {
+            Some(value) => Self::from_jwk(&value),
+            None => Self::from_base64(s),
+        }
+    }
+}
+
+pub struct PrivateKey([u8; KEY_LEN]);
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl PrivateKey {
+    pub fn generate() -> (Self, PublicKeyBytes) {
+        Self::generate_with_rng(&mut OsRng)
+    }
+
+    /// Generates a key pair from the given randomness source; the same generator
+    /// state always yields the same key.
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (Self, PublicKeyBytes) {
+        let mut bytes = [0u8; KEY_LEN];
+        rng.fill_bytes(&mut bytes);
+        let private = Self(bytes);
+        let public = private.public_key();
+        (private, public)
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.0)
+    }
+
+    pub fn from_base64(s: &str) -> Result<Self, EcdhError> {
+        let bytes = general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|_| EcdhError::InvalidKeyFormat)?;
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| EcdhError::InvalidKeyFormat)
+    }
+
+    pub fn public_key(&self) -> PublicKeyBytes {
+        PublicKeyBytes(x448(&self.0, &BASE_POINT))
+    }
+
+    pub fn to_jwk(&self) -> Value {
+        let mut value = self.public_key().to_jwk();
+        value["d"] = Value::String(jwk::b64url_encode(&self.0));
+        value
+    }
+
+    pub fn from_jwk(value: &Value) -> Result<Self, EcdhError> {
+        let key = Self(read_okp_member(value, X448_CRV, "d")?);
+        if let Some(Value::String(_)) = value.get("x")
+            && key.public_key().as_bytes() != &read_okp_member(value, X448_CRV, "x")?
+        {
+            return Err(EcdhError::InvalidJwk(
+                "public member 'x' does not match private key".to_string(),
+            ));
+        }
+        Ok(key)
+    }
+
+    /// PKCS#8 PrivateKeyInfo as defined in RFC 8410
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        okp_pkcs8_der(X448_OID, &self.0)
+    }
+
+    pub fn from_pkcs8_der(data: &[u8]) -> Result<Self, EcdhError> {
+        okp_from_pkcs8_der(X448_OID, X448_CRV, data).map(Self)
+    }
+
+    /// PKCS#8 PEM, wrapped in PBES2 EncryptedPrivateKeyInfo when a passphrase is given
+    pub fn to_pem(&self, passphrase: Option<&str>) -> Result<String, EcdhError> {
+        pkcs8_to_pem(&self.to_pkcs8_der(), passphrase)
+    }
+
+    pub fn from_pem(text: &str, passphrase: Option<&str>) -> Result<Self, EcdhError> {
+        Self::from_pkcs8_der(&pkcs8_from_pem(text, passphrase)?)
+    }
+
+    /// Accepts a bare base64 key, an OKP JWK object with `d` or a PKCS#8 PEM
+    /// block (optionally passphrase-encrypted)
+    pub fn decode(s: &str, passphrase: Option<&str>) -> Result<Self, EcdhError> {
+        if pem::is_pem(s) {
+            return Self::from_pem(s, passphrase);
+        }
+        match parse_jwk_object(s)? {
+            Some(value) => Self::from_jwk(&value),
+            None => Self::from_base64(s),
+        }
+    }
+
+    pub fn compute_shared_secret(&self, peer: &PublicKeyBytes) -> Result<[u8; KEY_LEN], EcdhError> {
+        let shared = x448(&self.0, peer.as_bytes());
+        // Small-order points make the result all zeros, independent of our secret
+        if shared.iter().fold(0u8, |acc, &b| acc | b) == 0 {
+            return Err(EcdhError::NonContributory);
+        }
+        Ok(shared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_vectors::hex;
+
+    fn bytes(text: &str) -> [u8; KEY_LEN] {
+        hex(text).try_into().unwrap()
+    }
+
+    /// RFC 7748, section 5.2
+    #[test]
+    fn scalar_multiplication_vectors() {
+        let cases = [
+            (
+                "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c
+                 984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+                "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031
+                 ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+                "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaad
+                 eb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+            ),
+            (
+                "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd7
+                 7c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+                "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d0158
+                 94e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db",
+                "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3
+                 a5700df34321d62077e63633c575c1c954514e99da7c179d",
+            ),
+        ];
+        for (scalar, u, expected) in cases {
+            assert_eq!(x448(&bytes(scalar), &bytes(u)), bytes(expected));
+        }
+    }
+
+    /// RFC 7748, section 5.2: k and u start as the base point, then
+    /// each round sets u to the old k and k to X448(k, u)
+    #[test]
+    fn iterated_ladder() {
+        let (mut k, mut u) = (BASE_POINT, BASE_POINT);
+        for round in 1..=1000 {
+            let result = x448(&k, &u);
+            u = k;
+            k = result;
+            if round == 1 {
+                assert_eq!(
+                    k,
+                    bytes(
+                        "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd
+                         0db897086239492caf350b51f833868b9bc2b3bca9cf4113"
+                    )
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            bytes(
+                "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf
+                 10d087202db88286e2b79fceea3ec353ef54faa26e219f38"
+            )
+        );
+    }
+
+    /// RFC 7748, section 6.2
+    #[test]
+    fn diffie_hellman_vector() {
+        let alice = PrivateKey(bytes(
+            "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf5
+             74a9419744897391006382a6f127ab1d9ac2d8c0a598726b",
+        ));
+        let bob = PrivateKey(bytes(
+            "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120
+             bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d",
+        ));
+        assert_eq!(
+            alice.public_key().as_bytes(),
+            &bytes(
+                "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bb
+                 c836647241d953d40c5b12da88120d53177f80e532c41fa0"
+            )
+        );
+        assert_eq!(
+            bob.public_key().as_bytes(),
+            &bytes(
+                "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972
+                 fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609"
+            )
+        );
+
+        let shared = bytes(
+            "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56
+             fd2464c335543936521c24403085d59a449a5037514a879d",
+        );
+        assert_eq!(
+            alice.compute_shared_secret(&bob.public_key()).unwrap(),
+            shared
+        );
+        assert_eq!(
+            bob.compute_shared_secret(&alice.public_key()).unwrap(),
+            shared
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::algorithms::aes::{aes_gcm_decrypt, aes_gcm_encrypt};
+use crate::algorithms::ecdh::{EcdhError, PrivateKey, PublicKeyBytes};
+use crate::error::CryptoError;
+use crate::traits::Algorithm;
+use crate::utils::file_handler;
+use crate::utils::logger::{LogLevel, log};
+use base64::{Engine as _, engine::general_purpose};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Identyfikator formatu szyfrogramu ECIES
+const ECIES_FORMAT: &str = "x25519-ecies";
+/// Kontekst HKDF wiążący klucz z tym schematem
+const HKDF_INFO: &[u8] = b"x25519-ecies aes-128-gcm";
+/// Długość klucza AES-128
+const AES_KEY_LEN: usize = 16;
+
+/// Szyfrowanie hybrydowe do klucza publicznego X25519: efemeryczny ECDH,
+/// HKDF-SHA256 i AES-128-GCM
+pub struct EciesCipher {
+    key: String,
+    passphrase: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EciesCiphertext {
+    format: String,
+    ephemeral_public_key: String,
+    ciphertext: String,
+}
+
+fn key_error(err: EcdhError) -> CryptoError {
+    CryptoError::InvalidKey(format!("Niepoprawny klucz X25519: {}", err))
+}
+
+/// Klucz AES wyprowadzony ze wspólnego sekretu; sól to klucz efemeryczny
+/// i klucz odbiorcy, więc każdy szyfrogram ma inny klucz
+fn derive_aes_key(
+    shared_secret: &[u8],
+    ephemeral: &PublicKeyBytes,
+    recipient: &PublicKeyBytes,
+) -> Result<[u8; AES_KEY_LEN], CryptoError> {
+    let mut salt = Vec::with_capacity(64);
+    salt.extend_from_slice(ephemeral.as_bytes());
+    salt.extend_from_slice(recipient.as_bytes());
+    let okm = PrivateKey::derive_key(shared_secret, Some(&salt), Some(HKDF_INFO))
+        .map_err(|err| CryptoError::InvalidKey(err.to_string()))?;
+
+    let mut key = [0u8; AES_KEY_LEN];
+    key.copy_from_slice(&okm[..AES_KEY_LEN]);
+    Ok(key)
+}
+
+impl EciesCipher {
+    /// Klucz odbiorcy (szyfrowanie) lub klucz prywatny (deszyfrowanie) w dowolnym
+    /// formacie obsługiwanym przez ECDH: base64, JWK lub PKCS#8 PEM
+    pub fn new(key: &str, passphrase: Option<&str>) -> Result<Self, CryptoError> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(CryptoError::InvalidKey(
+                "Klucz X25519 jest wymagany".to_string(),
+            ));
+        }
+        Ok(Self {
+            key: key.to_string(),
+            passphrase: passphrase.map(str::to_string),
+        })
+    }
+
+    /// Klucz publiczny odbiorcy; podany klucz prywatny (JWK z `d` lub PEM)
+    /// zostaje zamieniony na odpowiadający mu klucz publiczny
+    fn recipient_public_key(&self) -> Result<PublicKeyBytes, CryptoError> {
+        match PublicKeyBytes::decode(&self.key) {
+            Ok(public) => Ok(public),
+            Err(_) => self.private_key().map(|private| private.public_key()),
+        }
+    }
+
+    fn private_key(&self) -> Result<PrivateKey, CryptoError> {
+        PrivateKey::decode(&self.key, self.passphrase.as_deref()).map_err(key_error)
+    }
+
+    /// Szyfruje dane dla odbiorcy; wynik zawiera efemeryczny klucz publiczny
+    pub fn seal(&self, plaintext: &[u8]) -> Result<String, CryptoError> {
+        let recipient = self.recipient_public_key()?;
+        let (ephemeral_secret, ephemeral) = PrivateKey::generate();
+        let shared = ephemeral_secret
+            .compute_shared_secret(&recipient)
+            .map_err(key_error)?;
+        let key = derive_aes_key(&shared, &ephemeral, &recipient)?;
+
+        let ciphertext = EciesCiphertext {
+            format: ECIES_FORMAT.to_string(),
+            ephemeral_public_key: ephemeral.to_base64(),
+            ciphertext: general_purpose::STANDARD.encode(aes_gcm_encrypt(
+                plaintext,
+                ephemeral.as_bytes(),
+                &key,
+            )),
+        };
+        serde_json::to_string_pretty(&ciphertext).map_err(|err| {
+            CryptoError::InvalidFormat(format!("Nie można zapisać szyfrogramu: {}", err))
+        })
+    }
+
+    /// Odszyfrowuje wynik `seal` kluczem prywatnym odbiorcy
+    pub fn open(&self, contents: &str) -> Result<Vec<u8>, CryptoError> {
+        let parsed: EciesCiphertext = serde_json::from_str(contents).map_err(|err| {
+            CryptoError::InvalidFormat(format!("Niepoprawny szyfrogram ECIES: {}", err))
+        })?;
+        if parsed.format != ECIES_FORMAT {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Nieobsługiwany format szyfrogramu: {}",
+                parsed.format
+            )));
+        }
+        let ephemeral =
+            PublicKeyBytes::from_base64(&parsed.ephemeral_public_key).map_err(|err| {
+                CryptoError::InvalidFormat(format!("Niepoprawny klucz efemeryczny: {}", err))
+            })?;
+        let sealed = general_purpose::STANDARD
+            .decode(parsed.ciphertext.trim())
+            .map_err(|err| CryptoError::InvalidFormat(format!("Niepoprawny base64: {}", err)))?;
+
+        let private = self.private_key()?;
+        let shared = private
+            .compute_shared_secret(&ephemeral)
+            .map_err(key_error)?;
+        let key = derive_aes_key(&shared, &ephemeral, &private.public_key())?;
+        aes_gcm_decrypt(&sealed, ephemeral.as_bytes(), &key)
+            .map_err(|err| CryptoError::DecryptionError(err.to_string()))
+    }
+}
+
+impl Algorithm for EciesCipher {
+    fn encrypt(&self, file_path: &str) -> Result<String, CryptoError> {
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            format!(
+                "Rozpoczynanie szyfrowania X25519-ECIES dla pliku: {}",
+                file_path
+            ),
+        );
+        let plaintext = fs::read(file_path)?;
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            format!("Wczytano plik, rozmiar: {} bajtów", plaintext.len()),
+        );
+
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            "Generowanie klucza efemerycznego i wyprowadzanie klucza AES (HKDF-SHA256)...",
+        );
+        let serialized = self.seal(&plaintext)?;
+
+        let output_path = file_handler::create_output_path_with_suffix(file_path, "_encrypted");
+        file_handler::write_file(&output_path, &serialized)?;
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            format!("Szyfrowanie zakończone. Zapisano do: {}", output_path),
+        );
+        Ok(output_path)
+    }
+
+    fn decrypt(&self, file_path: &str) -> Result<String, CryptoError> {
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            format!(
+                "Rozpoczynanie deszyfrowania X25519-ECIES dla pliku: {}",
+                file_path
+            ),
+        );
+        let contents = file_handler::read_file(file_path)?;
+        let plaintext = self.open(&contents)?;
+
+        let output_path = file_handler::create_output_path_with_suffix(file_path, "_decrypted");
+        fs::write(&output_path, &plaintext).map_err(|err| {
+            CryptoError::FileWriteError(format!("Nie można zapisać do pliku: {}", err))
+        })?;
+        log(
+            LogLevel::INFO,
+            "ECIES",
+            format!("Deszyfrowanie zakończone. Zapisano do: {}", output_path),
+        );
+        Ok(output_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn recipient() -> (EciesCipher, EciesCipher) {
+        let (private, public) = PrivateKey::generate();
+        (
+            EciesCipher::new(&public.to_base64(), None).unwrap(),
+            EciesCipher::new(&private.to_base64(), None).unwrap(),
+        )
+    }
+
+    fn reseal(sealed: &str, field: &str, value: String) -> String {
+        let mut parsed: Value = serde_json::from_str(sealed).unwrap();
+        parsed[field] = Value::String(value);
+        parsed.to_string()
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let (sender, receiver) = recipient();
+        for plaintext in [&b""[..], b"x", &[0xa5; 1000]] {
+            let sealed = sender.seal(plaintext).unwrap();
+            assert_eq!(receiver.open(&sealed).unwrap(), plaintext);
+        }
+
+        // Każde szyfrowanie używa nowego klucza efemerycznego
+        let first: EciesCiphertext = serde_json::from_str(&sender.seal(b"x").unwrap()).unwrap();
+        let second: EciesCiphertext = serde_json::from_str(&sender.seal(b"x").unwrap()).unwrap();
+        assert_ne!(first.ephemeral_public_key, second.ephemeral_public_key);
+
+        // Klucz prywatny w JWK też wskazuje odbiorcę
+        let (private, _) = PrivateKey::generate();
+        let jwk = private.to_jwk().to_string();
+        let sealed = EciesCipher::new(&jwk, None).unwrap().seal(b"jwk").unwrap();
+        assert_eq!(
+            EciesCipher::new(&jwk, None).unwrap().open(&sealed).unwrap(),
+            b"jwk"
+        );
+    }
+
+    #[test]
+    fn tampered_ciphertexts_are_rejected() {
+        let (sender, receiver) = recipient();
+        let sealed = sender.seal(b"tekst jawny").unwrap();
+        let parsed: EciesCiphertext = serde_json::from_str(&sealed).unwrap();
+
+        let mut body = general_purpose::STANDARD
+            .decode(&parsed.ciphertext)
+            .unwrap();
+        let last = body.len() - 1;
+        body[last] ^= 1;
+        let flipped = reseal(
+            &sealed,
+            "ciphertext",
+            general_purpose::STANDARD.encode(&body),
+        );
+        assert!(matches!(
+            receiver.open(&flipped),
+            Err(CryptoError::DecryptionError(_))
+        ));
+
+        // Inny klucz efemeryczny zmienia zarówno klucz AES, jak i dane AAD
+        let (_, other) = PrivateKey::generate();
+        let swapped = reseal(&sealed, "ephemeral_public_key", other.to_base64());
+        assert!(matches!(
+            receiver.open(&swapped),
+            Err(CryptoError::DecryptionError(_))
+        ));
+
+        let low_order = general_purpose::STANDARD.encode([0u8; 32]);
+        let zero = reseal(&sealed, "ephemeral_public_key", low_order);
+        assert!(matches!(
+            receiver.open(&zero),
+            Err(CryptoError::InvalidKey(_))
+        ));
+
+        let renamed = reseal(&sealed, "format", "x448-ecies".to_string());
+        assert!(matches!(
+            receiver.open(&renamed),
+            Err(CryptoError::InvalidFormat(_))
+        ));
+
+        let (_, stranger) = recipient();
+        assert!(matches!(
+            stranger.open(&sealed),
+            Err(CryptoError::DecryptionError(_))
+        ));
+        // Klucz publiczny nie wystarcza do odszyfrowania
+        assert!(sender.open(&sealed).is_err());
+    }
+}
@@ -1,8 +1,19 @@
 pub mod aes;
 pub mod aes_constants;
 pub mod aes_helpers;
+pub mod age;
 pub mod caesar;
 pub mod ecdh;
+pub mod ecies;
+pub mod ed25519;
+pub mod noise;
+pub mod number_theory;
+pub mod pbes2;
+pub mod primes;
+pub mod ratchet;
 pub mod rsa;
+pub mod rsa_audit;
+pub mod rsa_keys;
+pub mod rsa_validation;
 pub mod running_key_cipher;
 pub mod vigenere;
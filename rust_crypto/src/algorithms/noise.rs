@@ -0,0 +1,719 @@
+use crate::algorithms::aes::{aes_gcm_open, aes_gcm_seal};
+use crate::algorithms::ecdh::{EcdhError, PrivateKey, PublicKeyBytes};
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use hkdf::Hkdf;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+const DH_LEN: usize = 32;
+const HASH_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+/// Upper bound on any Noise message, handshake or transport
+pub const MAX_MESSAGE_LEN: usize = 65535;
+
+#[derive(Error, Debug)]
+pub enum NoiseError {
+    #[error("Unsupported protocol {0}, expected Noise_<NN|XX|IK>_25519_<AESGCM|ChaChaPoly>_SHA256")]
+    UnsupportedProtocol(String),
+    #[error("Pattern {pattern} requires {key}")]
+    MissingKey {
+        pattern: &'static str,
+        key: &'static str,
+    },
+    #[error("Pattern {0} does not take a remote static key up front")]
+    UnexpectedRemoteKey(&'static str),
+    #[error("Message is truncated")]
+    Truncated,
+    #[error("Message exceeds {MAX_MESSAGE_LEN} bytes")]
+    MessageTooLong,
+    #[error("Decryption failed: the message was modified or the keys do not match")]
+    DecryptionFailed,
+    #[error("It is the other party's turn, expected to {0} a message")]
+    OutOfOrder(&'static str),
+    #[error("Handshake was aborted by an earlier error")]
+    Aborted,
+    #[error("Nonce space exhausted, start a new session")]
+    NonceExhausted,
+    #[error(transparent)]
+    Ecdh(#[from] EcdhError),
+}
+
+/// Handshake patterns from section 7 of the Noise specification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    Nn,
+    Xx,
+    Ik,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    E,
+    S,
+    Ee,
+    Es,
+    Se,
+    Ss,
+}
+
+impl Pattern {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::Nn => "NN",
+            Pattern::Xx => "XX",
+            Pattern::Ik => "IK",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NN" => Some(Pattern::Nn),
+            "XX" => Some(Pattern::Xx),
+            "IK" => Some(Pattern::Ik),
+            _ => None,
+        }
+    }
+
+    /// Message tokens, alternating initiator -> responder -> initiator
+    fn messages(&self) -> &'static [&'static [Token]] {
+        use Token::*;
+        match self {
+            Pattern::Nn => &[&[E], &[E, Ee]],
+            Pattern::Xx => &[&[E], &[E, Ee, S, Es], &[S, Se]],
+            Pattern::Ik => &[&[E, Es, S, Ss], &[E, Ee, Se]],
+        }
+    }
+
+    /// Whether the initiator knows the responder's static key before the handshake (`<- s`)
+    fn responder_static_premessage(&self) -> bool {
+        matches!(self, Pattern::Ik)
+    }
+
+    /// Both parties transmit a static key in every pattern except NN
+    fn needs_static(&self) -> bool {
+        !matches!(self, Pattern::Nn)
+    }
+}
+
+/// AEAD used for handshake payloads and transport messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cipher {
+    AesGcm,
+    ChaChaPoly,
+}
+
+impl Cipher {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cipher::AesGcm => "AESGCM",
+            Cipher::ChaChaPoly => "ChaChaPoly",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "AESGCM" => Some(Cipher::AesGcm),
+            "ChaChaPoly" => Some(Cipher::ChaChaPoly),
+            _ => None,
+        }
+    }
+
+    /// 96-bit nonce: 32 zero bits followed by the counter, big-endian for
+    /// AES-GCM and little-endian for ChaCha20-Poly1305
+    fn nonce(&self, counter: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        match self {
+            Cipher::AesGcm => nonce[4..].copy_from_slice(&counter.to_be_bytes()),
+            Cipher::ChaChaPoly => nonce[4..].copy_from_slice(&counter.to_le_bytes()),
+        }
+        nonce
+    }
+
+    fn seal(&self, key: &[u8; 32], counter: u64, ad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let nonce = self.nonce(counter);
+        match self {
+            Cipher::AesGcm => aes_gcm_seal(key, &nonce, ad, plaintext),
+            Cipher::ChaChaPoly => ChaCha20Poly1305::new(Key::from_slice(key))
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: plaintext,
+                        aad: ad,
+                    },
+                )
+                .expect("ChaCha20-Poly1305 encryption of a bounded message cannot fail"),
+        }
+    }
+
+    fn open(
+        &self,
+        key: &[u8; 32],
+        counter: u64,
+        ad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, NoiseError> {
+        let nonce = self.nonce(counter);
+        match self {
+            Cipher::AesGcm => aes_gcm_open(key, &nonce, ad, ciphertext).ok(),
+            Cipher::ChaChaPoly => ChaCha20Poly1305::new(Key::from_slice(key))
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: ad,
+                    },
+                )
+                .ok(),
+        }
+        .ok_or(NoiseError::DecryptionFailed)
+    }
+}
+
+/// Parsed protocol name, e.g. `Noise_XX_25519_AESGCM_SHA256`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Protocol {
+    pub pattern: Pattern,
+    pub cipher: Cipher,
+}
+
+impl Protocol {
+    /// Only X25519 and SHA-256 are supported, so those parts are fixed
+    pub fn from_name(name: &str) -> Result<Self, NoiseError> {
+        let unsupported = || NoiseError::UnsupportedProtocol(name.to_string());
+        match name.split('_').collect::<Vec<_>>().as_slice() {
+            ["Noise", pattern, "25519", cipher, "SHA256"] => Ok(Protocol {
+                pattern: Pattern::from_name(pattern).ok_or_else(unsupported)?,
+                cipher: Cipher::from_name(cipher).ok_or_else(unsupported)?,
+            }),
+            _ => Err(unsupported()),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!(
+            "Noise_{}_25519_{}_SHA256",
+            self.pattern.name(),
+            self.cipher.name()
+        )
+    }
+}
+
+/// HKDF from section 4.3 with two outputs; equal to HKDF-SHA256 with
+/// the chaining key as salt and empty info
+fn hkdf2(chaining_key: &[u8; HASH_LEN], ikm: &[u8]) -> ([u8; HASH_LEN], [u8; HASH_LEN]) {
+    let mut okm = [0u8; 2 * HASH_LEN];
+    Hkdf::<Sha256>::new(Some(chaining_key), ikm)
+        .expand(&[], &mut okm)
+        .expect("64 bytes is a valid HKDF-SHA256 output length");
+    let (first, second) = okm.split_at(HASH_LEN);
+    (first.try_into().unwrap(), second.try_into().unwrap())
+}
+
+/// Splits `len` bytes off the front of `rest`
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8], NoiseError> {
+    if rest.len() < len {
+        return Err(NoiseError::Truncated);
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+struct CipherState {
+    cipher: Cipher,
+    key: Option<[u8; 32]>,
+    nonce: u64,
+}
+
+impl CipherState {
+    fn new(cipher: Cipher) -> Self {
+        Self {
+            cipher,
+            key: None,
+            nonce: 0,
+        }
+    }
+
+    fn with_key(cipher: Cipher, key: [u8; 32]) -> Self {
+        Self {
+            cipher,
+            key: Some(key),
+            nonce: 0,
+        }
+    }
+
+    fn has_key(&self) -> bool {
+        self.key.is_some()
+    }
+
+    fn next_nonce(&self) -> Result<u64, NoiseError> {
+        // 2^64 - 1 is reserved by the specification
+        if self.nonce == u64::MAX {
+            return Err(NoiseError::NonceExhausted);
+        }
+        Ok(self.nonce)
+    }
+
+    fn encrypt_with_ad(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(key) = &self.key else {
+            return Ok(plaintext.to_vec());
+        };
+        let sealed = self.cipher.seal(key, self.next_nonce()?, ad, plaintext);
+        self.nonce += 1;
+        Ok(sealed)
+    }
+
+    /// The nonce only advances on success, so a forged message does not
+    /// desynchronise the channel
+    fn decrypt_with_ad(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(key) = &self.key else {
+            return Ok(ciphertext.to_vec());
+        };
+        let plaintext = self.cipher.open(key, self.next_nonce()?, ad, ciphertext)?;
+        self.nonce += 1;
+        Ok(plaintext)
+    }
+}
+
+struct SymmetricState {
+    cipher_state: CipherState,
+    chaining_key: [u8; HASH_LEN],
+    hash: [u8; HASH_LEN],
+}
+
+impl SymmetricState {
+    fn new(protocol: &Protocol) -> Self {
+        let name = protocol.name();
+        let mut hash = [0u8; HASH_LEN];
+        if name.len() <= HASH_LEN {
+            hash[..name.len()].copy_from_slice(name.as_bytes());
+        } else {
+            hash = Sha256::digest(name.as_bytes()).into();
+        }
+        Self {
+            cipher_state: CipherState::new(protocol.cipher),
+            chaining_key: hash,
+            hash,
+        }
+    }
+
+    fn mix_key(&mut self, ikm: &[u8]) {
+        let (chaining_key, key) = hkdf2(&self.chaining_key, ikm);
+        self.chaining_key = chaining_key;
+        self.cipher_state = CipherState::with_key(self.cipher_state.cipher, key);
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        self.hash = Sha256::new()
+            .chain_update(self.hash)
+            .chain_update(data)
+            .finalize()
+            .into();
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let ciphertext = self.cipher_state.encrypt_with_ad(&self.hash, plaintext)?;
+        self.mix_hash(&ciphertext);
+        Ok(ciphertext)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let plaintext = self.cipher_state.decrypt_with_ad(&self.hash, ciphertext)?;
+        self.mix_hash(ciphertext);
+        Ok(plaintext)
+    }
+
+    fn split(&self) -> (CipherState, CipherState) {
+        let (first, second) = hkdf2(&self.chaining_key, &[]);
+        let cipher = self.cipher_state.cipher;
+        (
+            CipherState::with_key(cipher, first),
+            CipherState::with_key(cipher, second),
+        )
+    }
+}
+
+struct HandshakeState {
+    pattern: Pattern,
+    initiator: bool,
+    symmetric: SymmetricState,
+    s: Option<PrivateKey>,
+    e: Option<PrivateKey>,
+    rs: Option<PublicKeyBytes>,
+    re: Option<PublicKeyBytes>,
+    /// Ephemeral key to use instead of a fresh one, for test vectors
+    next_ephemeral: Option<PrivateKey>,
+    message_index: usize,
+}
+
+impl HandshakeState {
+    fn new(
+        protocol: Protocol,
+        initiator: bool,
+        prologue: &[u8],
+        s: Option<PrivateKey>,
+        rs: Option<PublicKeyBytes>,
+    ) -> Result<Self, NoiseError> {
+        let pattern = protocol.pattern;
+        let missing = |key| NoiseError::MissingKey {
+            pattern: pattern.name(),
+            key,
+        };
+        if pattern.needs_static() && s.is_none() {
+            return Err(missing("a local static key"));
+        }
+        let remote_known = pattern.responder_static_premessage() && initiator;
+        match (remote_known, rs.is_some()) {
+            (true, false) => return Err(missing("the responder's static public key")),
+            (false, true) => return Err(NoiseError::UnexpectedRemoteKey(pattern.name())),
+            _ => {}
+        }
+
+        let mut symmetric = SymmetricState::new(&protocol);
+        symmetric.mix_hash(prologue);
+        if pattern.responder_static_premessage() {
+            let responder_static = match (&rs, &s) {
+                (Some(remote), _) if initiator => *remote.as_bytes(),
+                (_, Some(local)) => *local.public_key().as_bytes(),
+                _ => unreachable!("checked above"),
+            };
+            symmetric.mix_hash(&responder_static);
+        }
+
+        Ok(Self {
+            pattern,
+            initiator,
+            symmetric,
+            s,
+            e: None,
+            rs,
+            re: None,
+            next_ephemeral: None,
+            message_index: 0,
+        })
+    }
+
+    fn is_finished(&self) -> bool {
+        self.message_index == self.pattern.messages().len()
+    }
+
+    fn is_our_turn(&self) -> bool {
+        self.message_index.is_multiple_of(2) == self.initiator
+    }
+
+    fn dh(
+        local: &Option<PrivateKey>,
+        remote: &Option<PublicKeyBytes>,
+    ) -> Result<[u8; DH_LEN], NoiseError> {
+        match (local, remote) {
+            (Some(local), Some(remote)) => Ok(local.compute_shared_secret(remote)?),
+            _ => unreachable!("pattern tokens guarantee both keys are present"),
+        }
+    }
+
+    fn mix_dh(&mut self, token: Token) -> Result<(), NoiseError> {
+        // es/se name the initiator's key first, so the roles swap on the responder
+        let shared = match (token, self.initiator) {
+            (Token::Ee, _) => Self::dh(&self.e, &self.re)?,
+            (Token::Es, true) | (Token::Se, false) => Self::dh(&self.e, &self.rs)?,
+            (Token::Es, false) | (Token::Se, true) => Self::dh(&self.s, &self.re)?,
+            (Token::Ss, _) => Self::dh(&self.s, &self.rs)?,
+            (Token::E | Token::S, _) => unreachable!("not a DH token"),
+        };
+        self.symmetric.mix_key(&shared);
+        Ok(())
+    }
+
+    fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if !self.is_our_turn() {
+            return Err(NoiseError::OutOfOrder("read"));
+        }
+        let mut message = Vec::new();
+        for &token in self.pattern.messages()[self.message_index] {
+            match token {
+                Token::E => {
+                    let e = self
+                        .next_ephemeral
+                        .take()
+                        .unwrap_or_else(|| PrivateKey::generate().0);
+                    let public = e.public_key();
+                    message.extend_from_slice(public.as_bytes());
+                    self.symmetric.mix_hash(public.as_bytes());
+                    self.e = Some(e);
+                }
+                Token::S => {
+                    let public = self.s.as_ref().expect("checked in new").public_key();
+                    let encrypted = self.symmetric.encrypt_and_hash(public.as_bytes())?;
+                    message.extend_from_slice(&encrypted);
+                }
+                dh => self.mix_dh(dh)?,
+            }
+        }
+        message.extend_from_slice(&self.symmetric.encrypt_and_hash(payload)?);
+        if message.len() > MAX_MESSAGE_LEN {
+            return Err(NoiseError::MessageTooLong);
+        }
+        self.message_index += 1;
+        Ok(message)
+    }
+
+    fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        if self.is_our_turn() {
+            return Err(NoiseError::OutOfOrder("write"));
+        }
+        if message.len() > MAX_MESSAGE_LEN {
+            return Err(NoiseError::MessageTooLong);
+        }
+        let mut rest = message;
+        for &token in self.pattern.messages()[self.message_index] {
+            match token {
+                Token::E => {
+                    let bytes: [u8; DH_LEN] = take(&mut rest, DH_LEN)?.try_into().unwrap();
+                    self.symmetric.mix_hash(&bytes);
+                    self.re = Some(PublicKeyBytes::from_bytes(bytes));
+                }
+                Token::S => {
+                    let len = DH_LEN
+                        + if self.symmetric.cipher_state.has_key() {
+                            TAG_LEN
+                        } else {
+                            0
+                        };
+                    let decrypted = self.symmetric.decrypt_and_hash(take(&mut rest, len)?)?;
+                    let bytes: [u8; DH_LEN] = decrypted.try_into().unwrap();
+                    self.rs = Some(PublicKeyBytes::from_bytes(bytes));
+                }
+                dh => self.mix_dh(dh)?,
+            }
+        }
+        let payload = self.symmetric.decrypt_and_hash(rest)?;
+        self.message_index += 1;
+        Ok(payload)
+    }
+}
+
+/// Transport phase: one cipher state per direction
+struct TransportState {
+    send: CipherState,
+    receive: CipherState,
+}
+
+enum Stage {
+    Handshake(Box<HandshakeState>),
+    Transport(TransportState),
+    Aborted,
+}
+
+/// Noise session that runs the handshake and then switches to transport
+/// messages once the last handshake message has been written or read
+pub struct Session {
+    protocol: Protocol,
+    stage: Stage,
+    handshake_hash: Option<[u8; HASH_LEN]>,
+    remote_static: Option<PublicKeyBytes>,
+}
+
+impl Session {
+    /// `local_static` is the party's long-term X25519 key; `remote_static`
+    /// is only taken by patterns with a pre-message (IK initiator)
+    pub fn new(
+        protocol: Protocol,
+        initiator: bool,
+        prologue: &[u8],
+        local_static: Option<PrivateKey>,
+        remote_static: Option<PublicKeyBytes>,
+    ) -> Result<Self, NoiseError> {
+        let handshake =
+            HandshakeState::new(protocol, initiator, prologue, local_static, remote_static)?;
+        Ok(Self {
+            protocol,
+            stage: Stage::Handshake(Box::new(handshake)),
+            handshake_hash: None,
+            remote_static: None,
+        })
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub fn is_handshake_finished(&self) -> bool {
+        matches!(self.stage, Stage::Transport(_))
+    }
+
+    /// Hash of the whole handshake transcript, identical on both sides
+    /// and suitable for channel binding; known once the handshake is done
+    pub fn handshake_hash(&self) -> Option<&[u8; HASH_LEN]> {
+        self.handshake_hash.as_ref()
+    }
+
+    /// The peer's static key, once it has been received and authenticated
+    pub fn remote_static(&self) -> Option<&PublicKeyBytes> {
+        self.remote_static.as_ref()
+    }
+
+    /// Fixes the next ephemeral key so published vectors can be replayed
+    #[cfg(test)]
+    fn set_ephemeral(&mut self, ephemeral: PrivateKey) {
+        if let Stage::Handshake(handshake) = &mut self.stage {
+            handshake.next_ephemeral = Some(ephemeral);
+        }
+    }
+
+    /// Produces the next message carrying `payload`
+    pub fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        match &mut self.stage {
+            Stage::Handshake(handshake) => {
+                let result = handshake.write_message(payload);
+                self.advance(result)
+            }
+            Stage::Transport(transport) => {
+                if payload.len() + TAG_LEN > MAX_MESSAGE_LEN {
+                    return Err(NoiseError::MessageTooLong);
+                }
+                transport.send.encrypt_with_ad(&[], payload)
+            }
+            Stage::Aborted => Err(NoiseError::Aborted),
+        }
+    }
+
+    /// Consumes a message from the peer and returns its payload
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        match &mut self.stage {
+            Stage::Handshake(handshake) => {
+                let result = handshake.read_message(message);
+                self.advance(result)
+            }
+            Stage::Transport(transport) => {
+                if message.len() > MAX_MESSAGE_LEN {
+                    return Err(NoiseError::MessageTooLong);
+                }
+                transport.receive.decrypt_with_ad(&[], message)
+            }
+            Stage::Aborted => Err(NoiseError::Aborted),
+        }
+    }
+
+    /// A failed handshake step leaves the transcript in an unknown state,
+    /// so the session is aborted; after the last message it moves to transport
+    fn advance(&mut self, result: Result<Vec<u8>, NoiseError>) -> Result<Vec<u8>, NoiseError> {
+        let Stage::Handshake(handshake) = &self.stage else {
+            return result;
+        };
+        if result.is_err() && !matches!(result, Err(NoiseError::OutOfOrder(_))) {
+            self.stage = Stage::Aborted;
+        } else if handshake.is_finished() {
+            let (initiator_to_responder, responder_to_initiator) = handshake.symmetric.split();
+            let (send, receive) = if handshake.initiator {
+                (initiator_to_responder, responder_to_initiator)
+            } else {
+                (responder_to_initiator, initiator_to_responder)
+            };
+            self.handshake_hash = Some(handshake.symmetric.hash);
+            self.remote_static = handshake.rs.clone();
+            self.stage = Stage::Transport(TransportState { send, receive });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_vectors::hex;
+
+    fn private_key(text: &str) -> PrivateKey {
+        PrivateKey::from_bytes(hex(text).try_into().unwrap())
+    }
+
+    /// Noise_XX_25519_AESGCM_SHA256 from the cacophony vectors (also used by snow):
+    /// three handshake messages followed by three transport messages
+    #[test]
+    fn cacophony_xx_aesgcm() {
+        let protocol = Protocol::from_name("Noise_XX_25519_AESGCM_SHA256").unwrap();
+        let prologue = hex("4a6f686e2047616c74");
+        let init_static = "e61ef9919cde45dd5f82166404bd08e38bceb5dfdfded0a34c8df7ed542214d1";
+        let resp_static = "4a3acbfdb163dec651dfa3194dece676d437029c62a408b4c5ea9114246e4893";
+
+        let mut initiator = Session::new(
+            protocol,
+            true,
+            &prologue,
+            Some(private_key(init_static)),
+            None,
+        )
+        .unwrap();
+        initiator.set_ephemeral(private_key(
+            "893e28b9dc6ca8d611ab664754b8ceb7bac5117349a4439a6b0569da977c464a",
+        ));
+        let mut responder = Session::new(
+            protocol,
+            false,
+            &prologue,
+            Some(private_key(resp_static)),
+            None,
+        )
+        .unwrap();
+        responder.set_ephemeral(private_key(
+            "bbdb4cdbd309f1a1f2e1456967fe288cadd6f712d65dc7b7793d5e63da6b375b",
+        ));
+
+        let messages = [
+            (
+                "4c756477696720766f6e204d69736573",
+                "ca35def5ae56cec33dc2036731ab14896bc4c75dbb07a61f879f8e3afa4c7944
+                 4c756477696720766f6e204d69736573",
+            ),
+            (
+                "4d757272617920526f746862617264",
+                "95ebc60d2b1fa672c1f46a8aa265ef51bfe38e7ccb39ec5be34069f144808843
+                 757117acceb05bd7a45733bc22015c97a9d0cbaf41b80446d5988ff5127235d7
+                 6b79eade70f473d6a4ef521fdcbeda5340d01e028ba793fc059f2724a83af05f
+                 12dda0448a7621a926b379a92477fd",
+            ),
+            (
+                "462e20412e20486179656b",
+                "c90f1cf77eba4e50edb038991565e36c9758943a989229b6051244dc4fbecb69
+                 46744b401af2ee1a5881b65fbb87fd07cb6a328ececc9ce6ce84c399dc332d4f
+                 d521fa4bb7f467ce909395",
+            ),
+            (
+                "4361726c204d656e676572",
+                "bc3fa77f6aca3e8466d7dc6bea10013e88a6a29add5132b461806c",
+            ),
+            (
+                "4a65616e2d426170746973746520536179",
+                "250b01074cdfe0df2ecf8ccbf1737b15a2ddb5b52fd9a396604e9c793cee3b3bb9",
+            ),
+            (
+                "457567656e2042f6686d20766f6e2042617765726b",
+                "449d4d433b3cdc3d02bf6fc881774b9df54366ebcffb9689bb13f14709822cd7ef42bcdb4d",
+            ),
+        ];
+        for (index, (payload, ciphertext)) in messages.iter().enumerate() {
+            let (sender, receiver) = if index.is_multiple_of(2) {
+                (&mut initiator, &mut responder)
+            } else {
+                (&mut responder, &mut initiator)
+            };
+            assert_eq!(
+                sender.write_message(&hex(payload)).unwrap(),
+                hex(ciphertext)
+            );
+            assert_eq!(
+                receiver.read_message(&hex(ciphertext)).unwrap(),
+                hex(payload)
+            );
+        }
+
+        let handshake_hash =
+            hex("1b7aefb1125762aa21a252890d00af54519638b76437444538f9a52f21e2e0dc");
+        assert_eq!(initiator.handshake_hash().unwrap().to_vec(), handshake_hash);
+        assert_eq!(responder.handshake_hash().unwrap().to_vec(), handshake_hash);
+        assert_eq!(
+            initiator.remote_static().unwrap().as_bytes(),
+            private_key(resp_static).public_key().as_bytes()
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::mem;
+
+/// Rozszerzony algorytm Euklidesa (iteracyjny): zwraca (g, x, y), gdzie
+/// a·x + b·y = g, a g = NWD(a, b) jest nieujemne
+pub fn extended_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    let (mut old_r, mut r) = (a.clone(), b.clone());
+    let (mut old_x, mut x) = (BigInt::one(), BigInt::zero());
+    let (mut old_y, mut y) = (BigInt::zero(), BigInt::one());
+
+    while !r.is_zero() {
+        let (quotient, remainder) = old_r.div_rem(&r);
+        old_r = mem::replace(&mut r, remainder);
+        let next_x = &old_x - &quotient * &x;
+        old_x = mem::replace(&mut x, next_x);
+        let next_y = &old_y - &quotient * &y;
+        old_y = mem::replace(&mut y, next_y);
+    }
+
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Odwrotność modularna a^-1 mod m. Współczynnik Bézouta jest liczony od razu
+/// modulo m, więc obliczenia nie wychodzą poza liczby nieujemne.
+pub fn mod_inverse(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    if modulus.is_zero() {
+        return None;
+    }
+    // Niezmiennik: t_i · a ≡ r_i (mod m)
+    let (mut old_r, mut r) = (modulus.clone(), a % modulus);
+    let (mut old_t, mut t) = (BigUint::zero(), BigUint::one());
+
+    while !r.is_zero() {
+        let (quotient, remainder) = old_r.div_rem(&r);
+        old_r = mem::replace(&mut r, remainder);
+        let next_t = (&old_t + modulus - (quotient * &t) % modulus) % modulus;
+        old_t = mem::replace(&mut t, next_t);
+    }
+
+    old_r.is_one().then_some(old_t)
+}
+
+/// Chińskie twierdzenie o resztach: jedyne x mod ∏m_i spełniające
+/// x ≡ r_i (mod m_i). Zwraca `None`, gdy moduły nie są parami względnie pierwsze.
+pub fn crt<'a, I>(congruences: I) -> Option<BigUint>
+where
+    I: IntoIterator<Item = (&'a BigUint, &'a BigUint)>,
+{
+    let mut congruences = congruences.into_iter();
+    let (first_residue, first_modulus) = congruences.next()?;
+    let mut result = first_residue % first_modulus;
+    let mut product = first_modulus.clone();
+
+    // Kolejne reszty dołączane metodą Garnera: x = x + M · ((r - x) · M^-1 mod m)
+    for (residue, modulus) in congruences {
+        let inverse = mod_inverse(&(&product % modulus), modulus)?;
+        let difference = (residue % modulus + modulus - &result % modulus) % modulus;
+        result += &product * (difference * inverse % modulus);
+        product *= modulus;
+    }
+    Some(result)
+}
+
+/// Symbol Jacobiego (a/n) dla nieparzystego n > 0
+pub fn jacobi(a: &BigInt, n: &BigUint) -> i8 {
+    let n_int = BigInt::from_biguint(Sign::Plus, n.clone());
+    let mut a = a.mod_floor(&n_int).to_biguint().unwrap_or_default();
+    let mut n = n.clone();
+    let mut result = 1i8;
+
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        let n_mod_8 = (&n % 8u8).to_u8().unwrap_or(0);
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+
+        mem::swap(&mut a, &mut n);
+        if (&a % 4u8) == BigUint::from(3u8) && (&n % 4u8) == BigUint::from(3u8) {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n.is_one() { result } else { 0 }
+}
+
+/// Pierwiastek całkowity stopnia `degree`, jeśli `value` jest pełną potęgą
+pub fn exact_root(value: &BigUint, degree: u32) -> Option<BigUint> {
+    if degree == 2 {
+        return exact_sqrt(value);
+    }
+    let root = value.nth_root(degree);
+    (root.pow(degree) == *value).then_some(root)
+}
+
+/// Bit `r` jest ustawiony, gdy `r` jest resztą kwadratową modulo 64. Tylko 12
+/// z 64 reszt może być kwadratem – tani filtr przed sqrt
+const SQUARES_MOD_64: u64 = 0x0202_0212_0203_0213;
+
+/// Pierwiastek kwadratowy, jeśli `value` jest kwadratem liczby całkowitej
+pub fn exact_sqrt(value: &BigUint) -> Option<BigUint> {
+    let low = value.iter_u64_digits().next().unwrap_or(0) & 63;
+    if SQUARES_MOD_64 >> low & 1 == 0 {
+        return None;
+    }
+    let root = value.sqrt();
+    (&root * &root == *value).then_some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_sqrt_filter_admits_every_square() {
+        for root in 0u64..256 {
+            let square = BigUint::from(root * root);
+            assert_eq!(exact_sqrt(&square), Some(BigUint::from(root)));
+            assert_eq!(exact_sqrt(&(square + 2 * root + 2u64)), None);
+        }
+        let squares = (0u64..64).fold(0u64, |mask, x| mask | 1 << (x * x % 64));
+        assert_eq!(SQUARES_MOD_64, squares);
+    }
+}
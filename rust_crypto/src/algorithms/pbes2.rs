@@ -0,0 +1,218 @@
+use crate::algorithms::aes::{aes_gcm_decrypt, aes_gcm_encrypt};
+use crate::error::CryptoError;
+use crate::utils::der::{self, DerReader};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+
+/// Etykieta PEM struktury PKCS#8 EncryptedPrivateKeyInfo
+pub const PEM_LABEL: &str = "ENCRYPTED PRIVATE KEY";
+
+const PBES2_OID: &[u64] = &[1, 2, 840, 113549, 1, 5, 13];
+const PBKDF2_OID: &[u64] = &[1, 2, 840, 113549, 1, 5, 12];
+const HMAC_SHA256_OID: &[u64] = &[1, 2, 840, 113549, 2, 9];
+/// id-aes128-GCM (RFC 5084), jedyny szyfr dostępny w module AES
+const AES128_GCM_OID: &[u64] = &[2, 16, 840, 1, 101, 3, 4, 1, 6];
+
+/// Liczba iteracji PBKDF2-HMAC-SHA256 zalecana przez OWASP (2023)
+const PBKDF2_ITERATIONS: u64 = 600_000;
+/// Górna granica przy odczycie, żeby spreparowany plik nie zablokował aplikacji
+const MAX_PBKDF2_ITERATIONS: u64 = 10_000_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+/// Domyślna wartość aes-ICVlen, gdy parametr jest pominięty (RFC 5084)
+const DEFAULT_TAG_LEN: u64 = 12;
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: u64,
+) -> Result<[u8; KEY_LEN], CryptoError> {
+    if passphrase.is_empty() {
+        return Err(CryptoError::InvalidKey(
+            "Hasło do klucza prywatnego nie może być puste".to_string(),
+        ));
+    }
+    let rounds = u32::try_from(iterations)
+        .map_err(|_| CryptoError::InvalidFormat("Zbyt duża liczba iteracji PBKDF2".to_string()))?;
+
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    Ok(key)
+}
+
+/// Szyfruje strukturę PrivateKeyInfo hasłem i zwraca EncryptedPrivateKeyInfo
+/// (PBES2: PBKDF2-HMAC-SHA256 + AES-128-GCM)
+pub fn encrypt(private_key_info: &[u8], passphrase: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+
+    // Wynik AES-GCM ma postać nonce || szyfrogram || tag
+    let sealed = aes_gcm_encrypt(private_key_info, &[], &key);
+    let (nonce, encrypted) = sealed.split_at(NONCE_LEN);
+
+    let kdf = der::encode_sequence(&[
+        der::encode_oid(PBKDF2_OID),
+        der::encode_sequence(&[
+            der::encode_octet_string(&salt),
+            der::encode_small_integer(PBKDF2_ITERATIONS),
+            der::encode_small_integer(KEY_LEN as u64),
+            der::encode_sequence(&[der::encode_oid(HMAC_SHA256_OID), der::encode_null()]),
+        ]),
+    ]);
+    let cipher = der::encode_sequence(&[
+        der::encode_oid(AES128_GCM_OID),
+        der::encode_sequence(&[
+            der::encode_octet_string(nonce),
+            der::encode_small_integer(TAG_LEN as u64),
+        ]),
+    ]);
+
+    Ok(der::encode_sequence(&[
+        der::encode_sequence(&[
+            der::encode_oid(PBES2_OID),
+            der::encode_sequence(&[kdf, cipher]),
+        ]),
+        der::encode_octet_string(encrypted),
+    ]))
+}
+
+/// Sprawdza, czy dane DER są strukturą EncryptedPrivateKeyInfo z PBES2
+pub fn is_encrypted(der: &[u8]) -> bool {
+    let mut outer = DerReader::new(der);
+    outer
+        .read_sequence()
+        .and_then(|mut seq| seq.read_sequence())
+        .and_then(|mut alg| alg.read_oid())
+        .is_ok_and(|oid| oid == PBES2_OID)
+}
+
+pub fn decrypt(der: &[u8], passphrase: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut outer = DerReader::new(der);
+    let mut seq = outer.read_sequence()?;
+    outer.finish()?;
+
+    let mut alg = seq.read_sequence()?;
+    expect_oid(&mut alg, PBES2_OID, "schemat szyfrowania")?;
+    let mut params = alg.read_sequence()?;
+    alg.finish()?;
+    let encrypted = seq.read_octet_string()?;
+    seq.finish()?;
+
+    let mut kdf = params.read_sequence()?;
+    expect_oid(&mut kdf, PBKDF2_OID, "funkcja wyprowadzania klucza")?;
+    let mut kdf_params = kdf.read_sequence()?;
+    kdf.finish()?;
+    let salt = kdf_params.read_octet_string()?;
+    let iterations = kdf_params.read_small_integer()?;
+    if iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS {
+        return Err(CryptoError::InvalidFormat(format!(
+            "Nieobsługiwana liczba iteracji PBKDF2: {}",
+            iterations
+        )));
+    }
+    // keyLength jest opcjonalne, a prf jest wymagane, bo domyślny HMAC-SHA1
+    // nie jest obsługiwany
+    let mut prf = match kdf_params.read_any()? {
+        (der::TAG_INTEGER, key_len) if key_len == [KEY_LEN as u8] => kdf_params.read_sequence()?,
+        (der::TAG_SEQUENCE, content) => DerReader::new(content),
+        _ => {
+            return Err(CryptoError::InvalidFormat(
+                "Nieprawidłowe parametry PBKDF2".to_string(),
+            ));
+        }
+    };
+    kdf_params.finish()?;
+    expect_oid(&mut prf, HMAC_SHA256_OID, "funkcja PRF")?;
+
+    let mut cipher = params.read_sequence()?;
+    params.finish()?;
+    expect_oid(&mut cipher, AES128_GCM_OID, "szyfr")?;
+    let mut gcm = cipher.read_sequence()?;
+    cipher.finish()?;
+    let nonce = gcm.read_octet_string()?;
+    let tag_len = if gcm.is_empty() {
+        DEFAULT_TAG_LEN
+    } else {
+        gcm.read_small_integer()?
+    };
+    gcm.finish()?;
+    if nonce.len() != NONCE_LEN || tag_len != TAG_LEN as u64 {
+        return Err(CryptoError::InvalidFormat(
+            "Obsługiwany jest tylko AES-GCM z 12-bajtowym nonce i 16-bajtowym tagiem".to_string(),
+        ));
+    }
+
+    let key = derive_key(passphrase, salt, iterations)?;
+    let mut sealed = Vec::with_capacity(NONCE_LEN + encrypted.len());
+    sealed.extend_from_slice(nonce);
+    sealed.extend_from_slice(encrypted);
+    aes_gcm_decrypt(&sealed, &[], &key).map_err(|_| {
+        CryptoError::DecryptionError(
+            "Nieprawidłowe hasło lub uszkodzony zaszyfrowany klucz prywatny".to_string(),
+        )
+    })
+}
+
+fn expect_oid(reader: &mut DerReader, expected: &[u64], what: &str) -> Result<(), CryptoError> {
+    let oid = reader.read_oid()?;
+    if oid == expected {
+        return Ok(());
+    }
+    Err(CryptoError::InvalidFormat(format!(
+        "Nieobsługiwany element PBES2 ({}): OID {}",
+        what,
+        oid.iter().map(u64::to_string).collect::<Vec<_>>().join(".")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// EncryptedPrivateKeyInfo z podanymi parametrami GCM i pustym szyfrogramem
+    fn encrypted_info(gcm_params: &[Vec<u8>]) -> Vec<u8> {
+        let kdf = der::encode_sequence(&[
+            der::encode_oid(PBKDF2_OID),
+            der::encode_sequence(&[
+                der::encode_octet_string(&[0u8; SALT_LEN]),
+                der::encode_small_integer(1),
+                der::encode_sequence(&[der::encode_oid(HMAC_SHA256_OID), der::encode_null()]),
+            ]),
+        ]);
+        let cipher = der::encode_sequence(&[
+            der::encode_oid(AES128_GCM_OID),
+            der::encode_sequence(gcm_params),
+        ]);
+        der::encode_sequence(&[
+            der::encode_sequence(&[
+                der::encode_oid(PBES2_OID),
+                der::encode_sequence(&[kdf, cipher]),
+            ]),
+            der::encode_octet_string(&[0u8; TAG_LEN]),
+        ])
+    }
+
+    #[test]
+    fn missing_icv_length_means_a_12_byte_tag() {
+        let nonce = der::encode_octet_string(&[0u8; NONCE_LEN]);
+        for params in [
+            vec![nonce.clone()],
+            vec![nonce.clone(), der::encode_small_integer(12)],
+        ] {
+            assert!(matches!(
+                decrypt(&encrypted_info(&params), "hasło"),
+                Err(CryptoError::InvalidFormat(_))
+            ));
+        }
+        // Jawne 16 bajtów przechodzi do deszyfrowania, które odrzuca pusty tag
+        let params = [nonce, der::encode_small_integer(TAG_LEN as u64)];
+        assert!(matches!(
+            decrypt(&encrypted_info(&params), "hasło"),
+            Err(CryptoError::DecryptionError(_))
+        ));
+    }
+}
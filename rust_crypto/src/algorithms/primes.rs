@@ -0,0 +1,427 @@
+use crate::algorithms::number_theory::{self, jacobi};
+use crate::error::CryptoError;
+use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use once_cell::sync::Lazy;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Granica tablicy małych liczb pierwszych używanych do przesiewania kandydatów
+const SIEVE_LIMIT: usize = 1 << 14;
+/// Maksymalna liczba kroków (+2) od jednego losowego punktu startowego
+const MAX_SIEVE_STEPS: u64 = 1 << 16;
+
+static SMALL_PRIMES: Lazy<Vec<u32>> = Lazy::new(|| small_primes_below(SIEVE_LIMIT));
+
+/// Ustawienia testu pierwszości
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrimalityOptions {
+    /// Dodatkowy silny test Lucasa (razem z M-R o podstawie 2 daje test Baillie–PSW)
+    pub lucas: bool,
+}
+
+/// Stan wyszukiwania liczb pierwszych przekazywany do obserwatora
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrimeSearchProgress {
+    /// Kandydaci, którzy przeszli sito i trafili do testu pierwszości
+    pub candidates_tested: u64,
+    pub primes_found: u32,
+}
+
+/// Śledzi postęp generowania liczb pierwszych i pozwala je przerwać z innego
+/// wątku przez współdzieloną flagę
+#[derive(Default)]
+pub struct PrimeSearchMonitor {
+    progress: PrimeSearchProgress,
+    cancelled: Arc<AtomicBool>,
+    on_progress: Option<Box<dyn FnMut(PrimeSearchProgress) + Send>>,
+}
+
+impl PrimeSearchMonitor {
+    pub fn new(cancelled: Arc<AtomicBool>) -> Self {
+        Self {
+            cancelled,
+            ..Default::default()
+        }
+    }
+
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(PrimeSearchProgress) + Send + 'static,
+    {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), CryptoError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(CryptoError::Cancelled(
+                "Generowanie liczb pierwszych zostało przerwane".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn record_candidate(&mut self, is_prime: bool) {
+        self.progress.candidates_tested += 1;
+        if is_prime {
+            self.progress.primes_found += 1;
+        }
+        if let Some(callback) = self.on_progress.as_mut() {
+            callback(self.progress);
+        }
+    }
+}
+
+/// Sito Eratostenesa: wszystkie liczby pierwsze mniejsze od `limit`
+pub fn small_primes_below(limit: usize) -> Vec<u32> {
+    let mut composite = vec![false; limit];
+    let mut primes = Vec::new();
+    for i in 2..limit {
+        if composite[i] {
+            continue;
+        }
+        primes.push(i as u32);
+        for multiple in (i * i..limit).step_by(i) {
+            composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+/// Minimalna liczba rund Millera–Rabina wg FIPS 186-5 (tabela B.1) dla liczby
+/// pierwszej o długości `bits`. Krótsze liczby (tryb edukacyjny) nie są objęte
+/// normą, więc testujemy je zachowawczo.
+pub fn miller_rabin_rounds(bits: usize, lucas: bool) -> usize {
+    match (bits, lucas) {
+        (2048.., false) => 4,
+        (2048.., true) => 2,
+        (1536.., false) => 4,
+        (1536.., true) => 3,
+        (1024.., false) => 5,
+        (1024.., true) => 4,
+        (512.., false) => 7,
+        (512.., true) => 5,
+        (_, false) => 40,
+        (_, true) => 1,
+    }
+}
+
+/// Generuje liczbę pierwszą o długości dokładnie `bits` bitów z ustawionymi
+/// dwoma najstarszymi bitami. Kandydaci są przesiewani przyrostowo przez
+/// tablicę małych liczb pierwszych, a dopiero ocaleni trafiają do testu M-R.
+/// Kandydaci i podstawy testu M-R są losowane z `rng`.
+pub fn gen_prime<R: RngCore + CryptoRng>(
+    bits: usize,
+    options: PrimalityOptions,
+    monitor: &mut PrimeSearchMonitor,
+    rng: &mut R,
+) -> Result<BigUint, CryptoError> {
+    let bit_size = u64::try_from(bits).unwrap_or(u64::MAX);
+    let rounds = miller_rabin_rounds(bits, options.lucas);
+
+    // Dla bardzo krótkich liczb pomijamy dzielniki nie mniejsze od samego
+    // kandydata, żeby nie odrzucić małej liczby pierwszej z tablicy
+    let sieve: Vec<u32> = SMALL_PRIMES
+        .iter()
+        .copied()
+        .filter(|&p| bits > 32 || (p as u64) < (1u64 << (bits - 1)))
+        .collect();
+
+    loop {
+        monitor.check_cancelled()?;
+        let mut base = rng.gen_biguint(bit_size);
+        // Ustawienie dwóch najstarszych bitów gwarantuje, że iloczyn dwóch
+        // takich liczb ma dokładnie sumę ich długości w bitach
+        if bits > 1 {
+            base.set_bit((bits - 1) as u64, true);
+        }
+        if bits > 2 {
+            base.set_bit((bits - 2) as u64, true);
+        }
+        base.set_bit(0, true);
+
+        let residues: Vec<u64> = sieve
+            .iter()
+            .map(|&p| (&base % p).to_u64().unwrap_or(0))
+            .collect();
+
+        let mut delta = 0u64;
+        while delta < MAX_SIEVE_STEPS {
+            let survives = sieve
+                .iter()
+                .zip(&residues)
+                .all(|(&p, &r)| !(r + delta).is_multiple_of(p as u64));
+
+            if survives {
+                let candidate = &base + delta;
+                if candidate.bits() != bit_size {
+                    break;
+                }
+                monitor.check_cancelled()?;
+                let is_prime = passes_probable_prime(&candidate, rounds, options, rng);
+                monitor.record_candidate(is_prime);
+                if is_prime {
+                    return Ok(candidate);
+                }
+            }
+            delta += 2;
+        }
+    }
+}
+
+/// Test pierwszości z dzieleniem próbnym i liczbą rund M-R dobraną do rozmiaru `n`
+pub fn is_prime(n: &BigUint, options: PrimalityOptions) -> bool {
+    if *n < BigUint::from(2u8) {
+        return false;
+    }
+    for &p in SMALL_PRIMES.iter() {
+        if *n == BigUint::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+
+    let bits = usize::try_from(n.bits()).unwrap_or(usize::MAX);
+    passes_probable_prime(
+        n,
+        miller_rabin_rounds(bits, options.lucas),
+        options,
+        &mut OsRng,
+    )
+}
+
+fn passes_probable_prime<R: RngCore + CryptoRng>(
+    n: &BigUint,
+    rounds: usize,
+    options: PrimalityOptions,
+    rng: &mut R,
+) -> bool {
+    if options.lucas {
+        // Baillie–PSW: M-R o podstawie 2, pozostałe rundy losowe i silny test Lucasa
+        miller_rabin_witness(n, &BigUint::from(2u8))
+            && is_probable_prime(n, rounds.saturating_sub(1), rng)
+            && is_strong_lucas_probable_prime(n)
+    } else {
+        is_probable_prime(n, rounds, rng)
+    }
+}
+
+/// Sprawdza, czy `a` nie jest świadkiem złożoności `n` w teście Millera–Rabina
+fn miller_rabin_witness(n: &BigUint, a: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    let mut x = a.modpow(&d, n);
+    if x == one || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.modpow(&BigUint::from(2u8), n);
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Test Millera–Rabina z `rounds` losowymi podstawami
+pub fn is_probable_prime<R: RngCore + CryptoRng>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    if *n < BigUint::from(2u8) {
+        return false;
+    }
+    if *n == BigUint::from(2u8) || *n == BigUint::from(3u8) {
+        return true;
+    }
+    if n.is_even() {
+        return false;
+    }
+
+    let upper = n - BigUint::one();
+    (0..rounds).all(|_| {
+        let a = rng.gen_biguint_range(&BigUint::from(2u8), &upper);
+        miller_rabin_witness(n, &a)
+    })
+}
+
+/// Dzielenie przez 2 modulo nieparzyste n
+fn half_mod(value: BigUint, n: &BigUint) -> BigUint {
+    let value = value % n;
+    if value.is_odd() {
+        (value + n) >> 1
+    } else {
+        value >> 1
+    }
+}
+
+/// Silny test Lucasa z parametrami Selfridge'a (metoda A): P = 1, Q = (1 - D) / 4
+fn is_strong_lucas_probable_prime(n: &BigUint) -> bool {
+    if n.is_even() {
+        return *n == BigUint::from(2u8);
+    }
+    // Dla kwadratów nie istnieje D z (D/n) = -1
+    if number_theory::exact_sqrt(n).is_some() {
+        return false;
+    }
+
+    let two = BigInt::from(2);
+    let mut d_param = BigInt::from(5);
+    loop {
+        match jacobi(&d_param, n) {
+            -1 => break,
+            0 if d_param.magnitude() != n => return false,
+            _ => {}
+        }
+        d_param = if d_param.sign() == Sign::Minus {
+            -d_param + &two
+        } else {
+            -(d_param + &two)
+        };
+    }
+
+    let n_int = BigInt::from_biguint(Sign::Plus, n.clone());
+    let to_mod = |value: &BigInt| value.mod_floor(&n_int).to_biguint().unwrap_or_default();
+    let q_param = (BigInt::one() - &d_param) / 4;
+    let d_mod = to_mod(&d_param);
+    let q_mod = to_mod(&q_param);
+    let two_q = |qk: &BigUint| (qk * 2u8) % n;
+
+    let n_plus_one = n + BigUint::one();
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+
+    // U_1 = 1, V_1 = P = 1, Q^1
+    let mut u = BigUint::one();
+    let mut v = BigUint::one();
+    let mut qk = q_mod.clone();
+    for bit in (0..k.bits() - 1).rev() {
+        u = (&u * &v) % n;
+        v = (&v * &v + n - two_q(&qk)) % n;
+        qk = (&qk * &qk) % n;
+        if k.bit(bit) {
+            let next_u = half_mod(&u + &v, n);
+            let next_v = half_mod(&d_mod * &u + &v, n);
+            u = next_u;
+            v = next_v;
+            qk = (&qk * &q_mod) % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (&v * &v + n - two_q(&qk)) % n;
+        qk = (&qk * &qk) % n;
+        if v.is_zero() {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BPSW: PrimalityOptions = PrimalityOptions { lucas: true };
+
+    /// Silne pseudopierwsze Lucasa (OEIS A217255)
+    const STRONG_LUCAS_PSEUDOPRIMES: [u64; 16] = [
+        5459, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519, 75077, 97439, 100127,
+        113573, 115639, 130139,
+    ];
+
+    /// Silne pseudopierwsze przy podstawie 2 (OEIS A001262) oraz liczby złożone
+    /// przechodzące M-R dla wszystkich podstaw pierwszych do 23 i do 37
+    const STRONG_BASE_2_PSEUDOPRIMES: [u128; 18] = [
+        2047,
+        3277,
+        4033,
+        4681,
+        8321,
+        15841,
+        29341,
+        42799,
+        49141,
+        52633,
+        65281,
+        74665,
+        80581,
+        85489,
+        88357,
+        90751,
+        3825123056546413051,
+        318665857834031151167461,
+    ];
+
+    #[test]
+    fn lucas_pseudoprimes_fail_miller_rabin_base_2() {
+        for n in STRONG_LUCAS_PSEUDOPRIMES.map(BigUint::from) {
+            assert!(is_strong_lucas_probable_prime(&n), "{n}");
+            assert!(!miller_rabin_witness(&n, &BigUint::from(2u8)), "{n}");
+            assert!(!passes_probable_prime(&n, 1, BPSW, &mut OsRng), "{n}");
+        }
+    }
+
+    #[test]
+    fn base_2_pseudoprimes_fail_the_lucas_test() {
+        for n in STRONG_BASE_2_PSEUDOPRIMES.map(BigUint::from) {
+            assert!(miller_rabin_witness(&n, &BigUint::from(2u8)), "{n}");
+            assert!(!is_strong_lucas_probable_prime(&n), "{n}");
+            assert!(!passes_probable_prime(&n, 1, BPSW, &mut OsRng), "{n}");
+        }
+        // Czynniki 149491 · 747451 · 34233211 są większe od granicy sita
+        let n = BigUint::from(3825123056546413051u64);
+        assert!(!is_prime(&n, BPSW));
+        assert!(!is_prime(&n, PrimalityOptions::default()));
+    }
+
+    #[test]
+    fn known_primes_and_composites() {
+        let mersenne = |exponent: u32| (BigUint::one() << exponent) - 1u8;
+        for exponent in [61, 89, 127, 521] {
+            assert!(is_strong_lucas_probable_prime(&mersenne(exponent)));
+            assert!(is_prime(&mersenne(exponent), BPSW));
+            assert!(is_prime(&mersenne(exponent), PrimalityOptions::default()));
+        }
+        // 2^67 - 1 = 193707721 · 761838257287, 2^127 + 1 jest podzielne przez 3
+        for n in [
+            mersenne(67),
+            mersenne(127) + 2u8,
+            mersenne(61) * mersenne(89),
+        ] {
+            assert!(!is_prime(&n, BPSW));
+        }
+        // Kwadrat liczby pierwszej: brak D z (D/n) = -1
+        assert!(!is_strong_lucas_probable_prime(
+            &(mersenne(61) * mersenne(61))
+        ));
+        // Liczby Carmichaela
+        for n in [561u32, 41041, 825265] {
+            assert!(!is_prime(&BigUint::from(n), BPSW));
+        }
+        for p in [2u32, 3, 5, 16381, 16411] {
+            assert!(is_prime(&BigUint::from(p), BPSW));
+        }
+    }
+
+    #[test]
+    fn generated_primes_have_the_requested_length() {
+        let mut monitor = PrimeSearchMonitor::default();
+        for bits in [8, 33, 256] {
+            let prime = gen_prime(bits, BPSW, &mut monitor, &mut OsRng).unwrap();
+            assert_eq!(prime.bits(), bits as u64);
+            assert!(prime.bit(bits as u64 - 2));
+            assert!(is_prime(&prime, PrimalityOptions::default()));
+        }
+    }
+}
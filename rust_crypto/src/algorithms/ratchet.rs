@@ -0,0 +1,608 @@
+use crate::algorithms::aes::{aes_gcm_open, aes_gcm_seal};
+use crate::algorithms::ecdh::{EcdhError, PrivateKey, PublicKeyBytes};
+use base64::{Engine as _, engine::general_purpose};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+pub mod x3dh;
+pub mod xeddsa;
+
+use x3dh::{InitialHeader, PreKeyBundle};
+
+const ROOT_INFO: &[u8] = b"rust_crypto Double Ratchet root";
+const CHAIN_INFO: &[u8] = b"rust_crypto Double Ratchet chain";
+const MESSAGE_INFO: &[u8] = b"rust_crypto Double Ratchet message";
+/// Most message keys skipped within one receiving chain
+const MAX_SKIP: u32 = 1000;
+/// Most skipped keys kept in total; the oldest are dropped first
+const MAX_STORED_SKIPPED: usize = 2000;
+const MESSAGE_VERSION: u8 = 1;
+const STATE_VERSION: u8 = 1;
+/// The message carries the X3DH initial header
+const FLAG_X3DH: u8 = 0x01;
+/// The X3DH header names a consumed one-time prekey
+const FLAG_ONE_TIME_PREKEY: u8 = 0x02;
+/// Ratchet public key, previous chain length and message number
+const HEADER_LEN: usize = 32 + 4 + 4;
+
+#[derive(Error, Debug)]
+pub enum RatchetError {
+    #[error("Invalid message: {0}")]
+    InvalidMessage(&'static str),
+    #[error("Unsupported message version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Decryption failed: the message was modified, replayed or belongs to another session")]
+    DecryptionFailed,
+    #[error("Message is more than {MAX_SKIP} messages ahead of the chain")]
+    TooManySkipped,
+    #[error("The message does not start an X3DH session")]
+    NotInitialMessage,
+    #[error("The message uses a one-time prekey that was not provided")]
+    MissingOneTimePreKey,
+    #[error("The message was sent with a different identity key than this session's")]
+    IdentityMismatch,
+    #[error("Cannot send before the first message from the initiator has been decrypted")]
+    NoSendingChain,
+    #[error("Message counter exhausted, start a new session")]
+    CounterExhausted,
+    #[error("Invalid prekey bundle: {0}")]
+    InvalidBundle(String),
+    #[error("The prekey signature does not match the bundle's identity key")]
+    InvalidPreKeySignature,
+    #[error("Invalid session state: {0}")]
+    InvalidState(String),
+    #[error(transparent)]
+    Ecdh(#[from] EcdhError),
+}
+
+fn split_halves(okm: &[u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let (first, second) = okm.split_at(32);
+    (first.try_into().unwrap(), second.try_into().unwrap())
+}
+
+/// KDF_RK: HKDF-SHA256 salted with the root key over the DH output
+fn kdf_root(root_key: &[u8; 32], dh_output: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let mut okm = [0u8; 64];
+    Hkdf::<Sha256>::new(Some(root_key), dh_output)
+        .expand(ROOT_INFO, &mut okm)
+        .expect("64 bytes is a valid HKDF-SHA256 output length");
+    split_halves(&okm)
+}
+
+/// KDF_CK: next chain key and the message key for the current step
+fn kdf_chain(chain_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    let mut okm = [0u8; 64];
+    Hkdf::<Sha256>::new(None, chain_key)
+        .expand(CHAIN_INFO, &mut okm)
+        .expect("64 bytes is a valid HKDF-SHA256 output length");
+    split_halves(&okm)
+}
+
+/// AES-256 key and nonce from a message key; every message key is used
+/// exactly once, so a derived nonce is safe
+fn message_cipher(message_key: &[u8; 32]) -> ([u8; 32], [u8; 12]) {
+    let mut okm = [0u8; 44];
+    Hkdf::<Sha256>::new(None, message_key)
+        .expand(MESSAGE_INFO, &mut okm)
+        .expect("44 bytes is a valid HKDF-SHA256 output length");
+    let (key, nonce) = okm.split_at(32);
+    (key.try_into().unwrap(), nonce.try_into().unwrap())
+}
+
+fn dh(private: &[u8; 32], public: &[u8; 32]) -> Result<[u8; 32], RatchetError> {
+    Ok(PrivateKey::from_bytes(*private)
+        .compute_shared_secret(&PublicKeyBytes::from_bytes(*public))?)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Header {
+    dh: [u8; 32],
+    previous_chain_len: u32,
+    number: u32,
+}
+
+impl Header {
+    fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[..32].copy_from_slice(&self.dh);
+        out[32..36].copy_from_slice(&self.previous_chain_len.to_be_bytes());
+        out[36..].copy_from_slice(&self.number.to_be_bytes());
+        out
+    }
+
+    fn decode(bytes: &[u8; HEADER_LEN]) -> Self {
+        Self {
+            dh: bytes[..32].try_into().unwrap(),
+            previous_chain_len: u32::from_be_bytes(bytes[32..36].try_into().unwrap()),
+            number: u32::from_be_bytes(bytes[36..].try_into().unwrap()),
+        }
+    }
+}
+
+fn seal(
+    message_key: &[u8; 32],
+    associated_data: &[u8],
+    header: &Header,
+    plaintext: &[u8],
+) -> Vec<u8> {
+    let (key, nonce) = message_cipher(message_key);
+    let mut ad = associated_data.to_vec();
+    ad.extend_from_slice(&header.encode());
+    aes_gcm_seal(&key, &nonce, &ad, plaintext)
+}
+
+fn open(
+    message_key: &[u8; 32],
+    associated_data: &[u8],
+    header: &Header,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, RatchetError> {
+    let (key, nonce) = message_cipher(message_key);
+    let mut ad = associated_data.to_vec();
+    ad.extend_from_slice(&header.encode());
+    aes_gcm_open(&key, &nonce, &ad, ciphertext).map_err(|_| RatchetError::DecryptionFailed)
+}
+
+#[derive(Clone)]
+struct SkippedKey {
+    dh: [u8; 32],
+    number: u32,
+    key: [u8; 32],
+}
+
+/// Double Ratchet state from section 3.2 of the Signal specification
+#[derive(Clone)]
+struct RatchetState {
+    dh_self: [u8; 32],
+    dh_remote: Option<[u8; 32]>,
+    root_key: [u8; 32],
+    send_chain: Option<[u8; 32]>,
+    receive_chain: Option<[u8; 32]>,
+    send_count: u32,
+    receive_count: u32,
+    previous_send_count: u32,
+    skipped: Vec<SkippedKey>,
+}
+
+impl RatchetState {
+    fn dh_self_public(&self) -> [u8; 32] {
+        *PrivateKey::from_bytes(self.dh_self).public_key().as_bytes()
+    }
+
+    fn encrypt(
+        &mut self,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Result<(Header, Vec<u8>), RatchetError> {
+        let chain = self.send_chain.ok_or(RatchetError::NoSendingChain)?;
+        let (chain, message_key) = kdf_chain(&chain);
+        let header = Header {
+            dh: self.dh_self_public(),
+            previous_chain_len: self.previous_send_count,
+            number: self.send_count,
+        };
+        self.send_count = self
+            .send_count
+            .checked_add(1)
+            .ok_or(RatchetError::CounterExhausted)?;
+        self.send_chain = Some(chain);
+        Ok((
+            header,
+            seal(&message_key, associated_data, &header, plaintext),
+        ))
+    }
+
+    /// Modifies the state even when decryption fails, so callers work on a copy
+    fn decrypt(
+        &mut self,
+        associated_data: &[u8],
+        header: &Header,
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, RatchetError> {
+        if let Some(position) = self
+            .skipped
+            .iter()
+            .position(|skipped| skipped.dh == header.dh && skipped.number == header.number)
+        {
+            let skipped = self.skipped.remove(position);
+            return open(&skipped.key, associated_data, header, ciphertext);
+        }
+
+        if self.dh_remote != Some(header.dh) {
+            self.skip_message_keys(header.previous_chain_len)?;
+            self.dh_ratchet(header)?;
+        }
+        self.skip_message_keys(header.number)?;
+
+        // The initiator's first ratchet key is the peer's public prekey, so a
+        // forged header can name it before any receiving chain exists
+        let chain = self.receive_chain.ok_or(RatchetError::InvalidMessage(
+            "no receiving chain for this ratchet key",
+        ))?;
+        let (chain, message_key) = kdf_chain(&chain);
+        self.receive_chain = Some(chain);
+        self.receive_count += 1;
+        open(&message_key, associated_data, header, ciphertext)
+    }
+
+    fn skip_message_keys(&mut self, until: u32) -> Result<(), RatchetError> {
+        if u64::from(self.receive_count) + u64::from(MAX_SKIP) < u64::from(until) {
+            return Err(RatchetError::TooManySkipped);
+        }
+        let (Some(mut chain), Some(dh_remote)) = (self.receive_chain, self.dh_remote) else {
+            return Ok(());
+        };
+        while self.receive_count < until {
+            let (next, message_key) = kdf_chain(&chain);
+            self.skipped.push(SkippedKey {
+                dh: dh_remote,
+                number: self.receive_count,
+                key: message_key,
+            });
+            chain = next;
+            self.receive_count += 1;
+        }
+        self.receive_chain = Some(chain);
+
+        let excess = self.skipped.len().saturating_sub(MAX_STORED_SKIPPED);
+        self.skipped.drain(..excess);
+        Ok(())
+    }
+
+    fn dh_ratchet(&mut self, header: &Header) -> Result<(), RatchetError> {
+        self.previous_send_count = self.send_count;
+        self.send_count = 0;
+        self.receive_count = 0;
+        self.dh_remote = Some(header.dh);
+
+        let (root_key, receive_chain) = kdf_root(&self.root_key, &dh(&self.dh_self, &header.dh)?);
+        self.dh_self = PrivateKey::generate().0.to_bytes();
+        let (root_key, send_chain) = kdf_root(&root_key, &dh(&self.dh_self, &header.dh)?);
+
+        self.root_key = root_key;
+        self.receive_chain = Some(receive_chain);
+        self.send_chain = Some(send_chain);
+        Ok(())
+    }
+}
+
+/// Parsed wire message: `version || flags || [X3DH header] || header || ciphertext`
+struct Message<'a> {
+    /// Version, flags and X3DH header, authenticated along with the header
+    prefix: &'a [u8],
+    initial: Option<InitialHeader>,
+    header: Header,
+    ciphertext: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, RatchetError> {
+        let truncated = RatchetError::InvalidMessage("truncated");
+        let [version, flags, rest @ ..] = bytes else {
+            return Err(truncated);
+        };
+        if *version != MESSAGE_VERSION {
+            return Err(RatchetError::UnsupportedVersion(*version));
+        }
+        if flags & !(FLAG_X3DH | FLAG_ONE_TIME_PREKEY) != 0 {
+            return Err(RatchetError::InvalidMessage("unknown flags"));
+        }
+
+        let mut rest = rest;
+        let mut take_key = || -> Result<PublicKeyBytes, RatchetError> {
+            let (key, tail) = rest
+                .split_first_chunk::<32>()
+                .ok_or(RatchetError::InvalidMessage("truncated"))?;
+            rest = tail;
+            Ok(PublicKeyBytes::from_bytes(*key))
+        };
+        let initial = if flags & FLAG_X3DH != 0 {
+            Some(InitialHeader {
+                identity_key: take_key()?,
+                ephemeral_key: take_key()?,
+                one_time_prekey: (flags & FLAG_ONE_TIME_PREKEY != 0)
+                    .then(&mut take_key)
+                    .transpose()?,
+            })
+        } else if flags & FLAG_ONE_TIME_PREKEY != 0 {
+            return Err(RatchetError::InvalidMessage(
+                "one-time prekey without X3DH header",
+            ));
+        } else {
+            None
+        };
+
+        let (header, ciphertext) = rest.split_first_chunk::<HEADER_LEN>().ok_or(truncated)?;
+        Ok(Self {
+            prefix: &bytes[..bytes.len() - rest.len()],
+            initial,
+            header: Header::decode(header),
+            ciphertext,
+        })
+    }
+
+    fn encode_prefix(initial: Option<&InitialHeader>) -> Vec<u8> {
+        let mut prefix = vec![MESSAGE_VERSION, 0];
+        if let Some(initial) = initial {
+            prefix[1] |= FLAG_X3DH;
+            prefix.extend_from_slice(initial.identity_key.as_bytes());
+            prefix.extend_from_slice(initial.ephemeral_key.as_bytes());
+            if let Some(one_time) = &initial.one_time_prekey {
+                prefix[1] |= FLAG_ONE_TIME_PREKEY;
+                prefix.extend_from_slice(one_time.as_bytes());
+            }
+        }
+        prefix
+    }
+}
+
+/// Forward-secret messaging session: X3DH to agree on the first root key,
+/// then the Double Ratchet with X25519, HKDF-SHA256 chains and AES-256-GCM
+pub struct Session {
+    state: RatchetState,
+    associated_data: [u8; 64],
+    remote_identity: PublicKeyBytes,
+    /// X3DH values repeated in every message until the peer replies
+    pending_initial: Option<InitialHeader>,
+}
+
+impl Session {
+    /// Starts a session with the owner of `bundle`; the first encrypted
+    /// message carries everything the responder needs
+    pub fn initiate(identity: &PrivateKey, bundle: &PreKeyBundle) -> Result<Self, RatchetError> {
+        let (agreement, initial) = x3dh::initiate(identity, bundle)?;
+        let dh_self = PrivateKey::generate().0.to_bytes();
+        let (root_key, send_chain) = kdf_root(
+            &agreement.shared_key,
+            &dh(&dh_self, bundle.prekey.as_bytes())?,
+        );
+        Ok(Self {
+            state: RatchetState {
+                dh_self,
+                dh_remote: Some(*bundle.prekey.as_bytes()),
+                root_key,
+                send_chain: Some(send_chain),
+                receive_chain: None,
+                send_count: 0,
+                receive_count: 0,
+                previous_send_count: 0,
+                skipped: Vec::new(),
+            },
+            associated_data: agreement.associated_data,
+            remote_identity: bundle.identity_key.clone(),
+            pending_initial: Some(initial),
+        })
+    }
+
+    /// Accepts a session from the initiator's first message; the same
+    /// message still has to be passed to `decrypt` to read its contents
+    pub fn respond(
+        identity: &PrivateKey,
+        prekey: &PrivateKey,
+        one_time_prekey: Option<&PrivateKey>,
+        first_message: &[u8],
+    ) -> Result<Self, RatchetError> {
+        let initial = Message::parse(first_message)?
+            .initial
+            .ok_or(RatchetError::NotInitialMessage)?;
+        let agreement = x3dh::respond(identity, prekey, one_time_prekey, &initial)?;
+        Ok(Self {
+            state: RatchetState {
+                dh_self: prekey.to_bytes(),
+                dh_remote: None,
+                root_key: agreement.shared_key,
+                send_chain: None,
+                receive_chain: None,
+                send_count: 0,
+                receive_count: 0,
+                previous_send_count: 0,
+                skipped: Vec::new(),
+            },
+            associated_data: agreement.associated_data,
+            remote_identity: initial.identity_key,
+            pending_initial: None,
+        })
+    }
+
+    /// X3DH associated data followed by the message prefix, so the keys
+    /// repeated in the X3DH header cannot be swapped in transit
+    fn message_ad(&self, prefix: &[u8]) -> Vec<u8> {
+        [&self.associated_data[..], prefix].concat()
+    }
+
+    pub fn remote_identity(&self) -> &PublicKeyBytes {
+        &self.remote_identity
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, RatchetError> {
+        let mut out = Message::encode_prefix(self.pending_initial.as_ref());
+        let (header, ciphertext) = self.state.encrypt(&self.message_ad(&out), plaintext)?;
+        out.extend_from_slice(&header.encode());
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypts a message; on failure the session is left untouched
+    pub fn decrypt(&mut self, message: &[u8]) -> Result<Vec<u8>, RatchetError> {
+        let message = Message::parse(message)?;
+        if let Some(initial) = &message.initial
+            && initial.identity_key.as_bytes() != self.remote_identity.as_bytes()
+        {
+            return Err(RatchetError::IdentityMismatch);
+        }
+
+        let mut state = self.state.clone();
+        let plaintext = state.decrypt(
+            &self.message_ad(message.prefix),
+            &message.header,
+            message.ciphertext,
+        )?;
+        self.state = state;
+        // Any reply proves the responder has set up its side of the session
+        self.pending_initial = None;
+        Ok(plaintext)
+    }
+
+    /// Session state as JSON; it holds secret keys and must be stored
+    /// as carefully as a private key
+    pub fn to_json(&self) -> String {
+        let encode = |bytes: &[u8]| general_purpose::STANDARD.encode(bytes);
+        let state = &self.state;
+        let persisted = PersistedSession {
+            version: STATE_VERSION,
+            associated_data: encode(&self.associated_data),
+            remote_identity: self.remote_identity.to_base64(),
+            dh_self: encode(&state.dh_self),
+            dh_remote: state.dh_remote.as_ref().map(|key| encode(key)),
+            root_key: encode(&state.root_key),
+            send_chain: state.send_chain.as_ref().map(|key| encode(key)),
+            receive_chain: state.receive_chain.as_ref().map(|key| encode(key)),
+            send_count: state.send_count,
+            receive_count: state.receive_count,
+            previous_send_count: state.previous_send_count,
+            skipped: state
+                .skipped
+                .iter()
+                .map(|skipped| PersistedSkippedKey {
+                    dh: encode(&skipped.dh),
+                    number: skipped.number,
+                    key: encode(&skipped.key),
+                })
+                .collect(),
+            pending_initial: self
+                .pending_initial
+                .as_ref()
+                .map(|initial| PersistedInitial {
+                    identity_key: initial.identity_key.to_base64(),
+                    ephemeral_key: initial.ephemeral_key.to_base64(),
+                    one_time_prekey: initial.one_time_prekey.as_ref().map(|key| key.to_base64()),
+                }),
+        };
+        serde_json::to_string(&persisted).expect("session serialization cannot fail")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, RatchetError> {
+        let persisted: PersistedSession = serde_json::from_str(text)
+            .map_err(|err| RatchetError::InvalidState(err.to_string()))?;
+        if persisted.version != STATE_VERSION {
+            return Err(RatchetError::InvalidState(format!(
+                "unsupported version {}",
+                persisted.version
+            )));
+        }
+        let key = |value: &str| decode_fixed::<32>(value);
+        let optional_key = |value: Option<&str>| value.map(key).transpose();
+        Ok(Self {
+            state: RatchetState {
+                dh_self: key(&persisted.dh_self)?,
+                dh_remote: optional_key(persisted.dh_remote.as_deref())?,
+                root_key: key(&persisted.root_key)?,
+                send_chain: optional_key(persisted.send_chain.as_deref())?,
+                receive_chain: optional_key(persisted.receive_chain.as_deref())?,
+                send_count: persisted.send_count,
+                receive_count: persisted.receive_count,
+                previous_send_count: persisted.previous_send_count,
+                skipped: persisted
+                    .skipped
+                    .iter()
+                    .map(|skipped| {
+                        Ok(SkippedKey {
+                            dh: key(&skipped.dh)?,
+                            number: skipped.number,
+                            key: key(&skipped.key)?,
+                        })
+                    })
+                    .collect::<Result<_, RatchetError>>()?,
+            },
+            associated_data: decode_fixed::<64>(&persisted.associated_data)?,
+            remote_identity: PublicKeyBytes::from_bytes(key(&persisted.remote_identity)?),
+            pending_initial: persisted
+                .pending_initial
+                .map(|initial| {
+                    Ok::<_, RatchetError>(InitialHeader {
+                        identity_key: PublicKeyBytes::from_bytes(key(&initial.identity_key)?),
+                        ephemeral_key: PublicKeyBytes::from_bytes(key(&initial.ephemeral_key)?),
+                        one_time_prekey: optional_key(initial.one_time_prekey.as_deref())?
+                            .map(PublicKeyBytes::from_bytes),
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+fn decode_fixed<const N: usize>(value: &str) -> Result<[u8; N], RatchetError> {
+    general_purpose::STANDARD
+        .decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| RatchetError::InvalidState(format!("expected {} bytes in base64", N)))
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedSkippedKey {
+    dh: String,
+    number: u32,
+    key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedInitial {
+    identity_key: String,
+    ephemeral_key: String,
+    one_time_prekey: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedSession {
+    version: u8,
+    associated_data: String,
+    remote_identity: String,
+    dh_self: String,
+    dh_remote: Option<String>,
+    root_key: String,
+    send_chain: Option<String>,
+    receive_chain: Option<String>,
+    send_count: u32,
+    receive_count: u32,
+    previous_send_count: u32,
+    skipped: Vec<PersistedSkippedKey>,
+    pending_initial: Option<PersistedInitial>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forged_message_on_the_prekey_chain_is_rejected() {
+        let (bob, _) = PrivateKey::generate();
+        let (alice, _) = PrivateKey::generate();
+        let (prekey, prekey_public) = PrivateKey::generate();
+        let bundle = PreKeyBundle::new(&bob, prekey_public.clone(), None);
+        let mut session = Session::initiate(&alice, &bundle).unwrap();
+
+        // Anyone can read the signed prekey from the bundle and name it
+        let mut forged = Message::encode_prefix(None);
+        let header = Header {
+            dh: *prekey_public.as_bytes(),
+            previous_chain_len: 0,
+            number: 0,
+        };
+        forged.extend_from_slice(&header.encode());
+        forged.extend_from_slice(&[0u8; 32]);
+        assert!(matches!(
+            session.decrypt(&forged),
+            Err(RatchetError::InvalidMessage(_))
+        ));
+
+        // The session is unaffected and still reaches the responder
+        let first = session.encrypt(b"hello").unwrap();
+        let mut responder = Session::respond(&bob, &prekey, None, &first).unwrap();
+        assert_eq!(responder.decrypt(&first).unwrap(), b"hello");
+        let reply = responder.encrypt(b"hi").unwrap();
+        assert_eq!(session.decrypt(&reply).unwrap(), b"hi");
+    }
+}
@@ -0,0 +1,218 @@
+use super::RatchetError;
+use super::xeddsa::{self, SIGNATURE_LEN};
+use crate::algorithms::ecdh::{PrivateKey, PublicKeyBytes};
+use base64::{Engine as _, engine::general_purpose};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+const X3DH_INFO: &[u8] = b"rust_crypto X3DH";
+/// 32 bytes of 0xFF prepended to the DH outputs for X25519 (section 2.2)
+const KDF_PREFIX: [u8; 32] = [0xFF; 32];
+
+/// Public prekeys of the responder, handed to whoever wants to start a session.
+/// The prekey is signed with the identity key: Sig(IK_B, Encode(SPK_B)).
+pub struct PreKeyBundle {
+    pub identity_key: PublicKeyBytes,
+    pub prekey: PublicKeyBytes,
+    pub prekey_signature: [u8; SIGNATURE_LEN],
+    pub one_time_prekey: Option<PublicKeyBytes>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PreKeyBundleJson {
+    identity_key: String,
+    prekey: String,
+    prekey_signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    one_time_prekey: Option<String>,
+}
+
+impl PreKeyBundle {
+    /// Builds the bundle and signs the prekey with XEdDSA under `identity`
+    pub fn new(
+        identity: &PrivateKey,
+        prekey: PublicKeyBytes,
+        one_time_prekey: Option<PublicKeyBytes>,
+    ) -> Self {
+        Self {
+            identity_key: identity.public_key(),
+            prekey_signature: xeddsa::sign(identity, prekey.as_bytes()),
+            prekey,
+            one_time_prekey,
+        }
+    }
+
+    /// Checks that the prekey was signed by the bundle's identity key
+    pub fn verify(&self) -> Result<(), RatchetError> {
+        if xeddsa::verify(
+            &self.identity_key,
+            self.prekey.as_bytes(),
+            &self.prekey_signature,
+        ) {
+            Ok(())
+        } else {
+            Err(RatchetError::InvalidPreKeySignature)
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&PreKeyBundleJson {
+            identity_key: self.identity_key.to_base64(),
+            prekey: self.prekey.to_base64(),
+            prekey_signature: general_purpose::STANDARD.encode(self.prekey_signature),
+            one_time_prekey: self.one_time_prekey.as_ref().map(|key| key.to_base64()),
+        })
+        .expect("bundle serialization cannot fail")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, RatchetError> {
+        let bundle: PreKeyBundleJson = serde_json::from_str(text)
+            .map_err(|err| RatchetError::InvalidBundle(err.to_string()))?;
+        let prekey_signature = general_purpose::STANDARD
+            .decode(bundle.prekey_signature.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                RatchetError::InvalidBundle(format!(
+                    "prekey_signature must be {} bytes in base64",
+                    SIGNATURE_LEN
+                ))
+            })?;
+        Ok(Self {
+            identity_key: PublicKeyBytes::from_base64(&bundle.identity_key)?,
+            prekey: PublicKeyBytes::from_base64(&bundle.prekey)?,
+            prekey_signature,
+            one_time_prekey: bundle
+                .one_time_prekey
+                .as_deref()
+                .map(PublicKeyBytes::from_base64)
+                .transpose()?,
+        })
+    }
+}
+
+/// Keys the initiator sends along with its first messages
+#[derive(Clone)]
+pub struct InitialHeader {
+    pub identity_key: PublicKeyBytes,
+    pub ephemeral_key: PublicKeyBytes,
+    /// Which one-time prekey of the bundle was consumed, if any
+    pub one_time_prekey: Option<PublicKeyBytes>,
+}
+
+/// Shared key and associated data `IK_A || IK_B` binding both identities
+pub struct Agreement {
+    pub shared_key: [u8; 32],
+    pub associated_data: [u8; 64],
+}
+
+fn derive(dh_outputs: &[[u8; 32]]) -> [u8; 32] {
+    let mut ikm = KDF_PREFIX.to_vec();
+    for output in dh_outputs {
+        ikm.extend_from_slice(output);
+    }
+    let mut shared_key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&[0u8; 32]), &ikm)
+        .expand(X3DH_INFO, &mut shared_key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    shared_key
+}
+
+fn associated_data(initiator: &PublicKeyBytes, responder: &PublicKeyBytes) -> [u8; 64] {
+    let mut ad = [0u8; 64];
+    ad[..32].copy_from_slice(initiator.as_bytes());
+    ad[32..].copy_from_slice(responder.as_bytes());
+    ad
+}
+
+/// Initiator side: DH1 = DH(IK_A, SPK_B), DH2 = DH(EK_A, IK_B),
+/// DH3 = DH(EK_A, SPK_B) and optionally DH4 = DH(EK_A, OPK_B).
+/// Bundles whose prekey signature does not verify are rejected.
+pub fn initiate(
+    identity: &PrivateKey,
+    bundle: &PreKeyBundle,
+) -> Result<(Agreement, InitialHeader), RatchetError> {
+    bundle.verify()?;
+    let (ephemeral, ephemeral_public) = PrivateKey::generate();
+    let mut outputs = vec![
+        identity.compute_shared_secret(&bundle.prekey)?,
+        ephemeral.compute_shared_secret(&bundle.identity_key)?,
+        ephemeral.compute_shared_secret(&bundle.prekey)?,
+    ];
+    if let Some(one_time) = &bundle.one_time_prekey {
+        outputs.push(ephemeral.compute_shared_secret(one_time)?);
+    }
+
+    let identity_public = identity.public_key();
+    let agreement = Agreement {
+        shared_key: derive(&outputs),
+        associated_data: associated_data(&identity_public, &bundle.identity_key),
+    };
+    let header = InitialHeader {
+        identity_key: identity_public,
+        ephemeral_key: ephemeral_public,
+        one_time_prekey: bundle.one_time_prekey.clone(),
+    };
+    Ok((agreement, header))
+}
+
+/// Responder side, mirroring `initiate` with the private prekeys
+pub fn respond(
+    identity: &PrivateKey,
+    prekey: &PrivateKey,
+    one_time_prekey: Option<&PrivateKey>,
+    header: &InitialHeader,
+) -> Result<Agreement, RatchetError> {
+    let mut outputs = vec![
+        prekey.compute_shared_secret(&header.identity_key)?,
+        identity.compute_shared_secret(&header.ephemeral_key)?,
+        prekey.compute_shared_secret(&header.ephemeral_key)?,
+    ];
+    if let Some(used) = &header.one_time_prekey {
+        let one_time = one_time_prekey
+            .filter(|key| key.public_key().as_bytes() == used.as_bytes())
+            .ok_or(RatchetError::MissingOneTimePreKey)?;
+        outputs.push(one_time.compute_shared_secret(&header.ephemeral_key)?);
+    }
+
+    Ok(Agreement {
+        shared_key: derive(&outputs),
+        associated_data: associated_data(&header.identity_key, &identity.public_key()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initiate_checks_the_prekey_signature() {
+        let (bob, _) = PrivateKey::generate();
+        let (alice, _) = PrivateKey::generate();
+        let (_, prekey) = PrivateKey::generate();
+        let bundle =
+            PreKeyBundle::from_json(&PreKeyBundle::new(&bob, prekey, None).to_json()).unwrap();
+        assert!(initiate(&alice, &bundle).is_ok());
+
+        // A prekey substituted by someone without Bob's identity key
+        let (mallory, _) = PrivateKey::generate();
+        let (_, forged) = PrivateKey::generate();
+        let substituted = PreKeyBundle {
+            prekey: forged.clone(),
+            ..PreKeyBundle::new(&bob, bundle.prekey.clone(), None)
+        };
+        assert!(matches!(
+            initiate(&alice, &substituted),
+            Err(RatchetError::InvalidPreKeySignature)
+        ));
+        let resigned = PreKeyBundle {
+            identity_key: bob.public_key(),
+            ..PreKeyBundle::new(&mallory, forged, None)
+        };
+        assert!(matches!(
+            initiate(&alice, &resigned),
+            Err(RatchetError::InvalidPreKeySignature)
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::algorithms::ecdh::{PrivateKey, PublicKeyBytes};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::{Scalar, clamp_integer};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha512};
+
+pub const SIGNATURE_LEN: usize = 64;
+
+/// hash_1 from the XEdDSA specification: 2^256 - 1 - 1 as a little-endian prefix
+const HASH1_PREFIX: [u8; 32] = {
+    let mut prefix = [0xFF; 32];
+    prefix[0] = 0xFE;
+    prefix
+};
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+/// Edwards key pair (A, a) for an X25519 private key, with the sign of A
+/// forced to zero so that it can be recovered from the Montgomery u alone
+fn calculate_key_pair(key: &PrivateKey) -> ([u8; 32], Scalar) {
+    let k = Scalar::from_bytes_mod_order(clamp_integer(key.to_bytes()));
+    let mut public = EdwardsPoint::mul_base(&k).compress().to_bytes();
+    let negative = public[31] >> 7 == 1;
+    public[31] &= 0x7F;
+    (public, if negative { -k } else { k })
+}
+
+/// Signs `message` with an X25519 private key (XEdDSA, section 2.4)
+pub fn sign(key: &PrivateKey, message: &[u8]) -> [u8; SIGNATURE_LEN] {
+    let (public, a) = calculate_key_pair(key);
+    let mut random = [0u8; 64];
+    OsRng.fill_bytes(&mut random);
+
+    let r = hash_to_scalar(&[&HASH1_PREFIX, a.as_bytes(), message, &random]);
+    let big_r = EdwardsPoint::mul_base(&r).compress().to_bytes();
+    let h = hash_to_scalar(&[&big_r, &public, message]);
+    let s = r + h * a;
+
+    let mut signature = [0u8; SIGNATURE_LEN];
+    signature[..32].copy_from_slice(&big_r);
+    signature[32..].copy_from_slice(s.as_bytes());
+    signature
+}
+
+/// u < p = 2^255 - 19, compared from the most significant byte
+fn is_canonical(u: &[u8; 32]) -> bool {
+    let mut p = [0xFF; 32];
+    p[0] = 0xED;
+    p[31] = 0x7F;
+    u.iter().rev().lt(p.iter().rev())
+}
+
+/// Checks an XEdDSA signature against an X25519 public key
+pub fn verify(key: &PublicKeyBytes, message: &[u8], signature: &[u8; SIGNATURE_LEN]) -> bool {
+    let u = key.as_bytes();
+    if !is_canonical(u) {
+        return false;
+    }
+    let Some(public) = MontgomeryPoint(*u).to_edwards(0) else {
+        return false;
+    };
+    let (big_r, s) = signature.split_at(32);
+    let Some(s) = Option::<Scalar>::from(Scalar::from_canonical_bytes(s.try_into().unwrap()))
+    else {
+        return false;
+    };
+
+    let h = hash_to_scalar(&[big_r, public.compress().as_bytes(), message]);
+    let expected = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-h, &public, &s);
+    CompressedEdwardsY::from_slice(big_r).is_ok_and(|r| expected.compress() == r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    #[test]
+    fn signatures_verify_only_for_the_signed_message_and_key() {
+        let (key, public) = PrivateKey::generate();
+        let (_, other) = PrivateKey::generate();
+        let signature = sign(&key, b"signed prekey");
+
+        assert!(verify(&public, b"signed prekey", &signature));
+        assert!(!verify(&public, b"signed prekey!", &signature));
+        assert!(!verify(&other, b"signed prekey", &signature));
+        for position in [0, 31, 32, 63] {
+            let mut tampered = signature;
+            tampered[position] ^= 1;
+            assert!(!verify(&public, b"signed prekey", &tampered));
+        }
+    }
+
+    /// An XEdDSA signature is a plain Ed25519 signature under the converted
+    /// public key, so an independent Ed25519 verifier must accept it
+    #[test]
+    fn signatures_are_ed25519_signatures_under_the_converted_key() {
+        for _ in 0..16 {
+            let (key, public) = PrivateKey::generate();
+            let signature = sign(&key, b"message");
+            let converted = MontgomeryPoint(*public.as_bytes()).to_edwards(0).unwrap();
+            let verifier = VerifyingKey::from_bytes(converted.compress().as_bytes()).unwrap();
+            assert!(
+                verifier
+                    .verify_strict(b"message", &Signature::from_bytes(&signature))
+                    .is_ok()
+            );
+        }
+    }
+}
@@ -1,15 +1,142 @@
+use crate::algorithms::number_theory;
+use crate::algorithms::primes::{self, PrimalityOptions, PrimeSearchMonitor};
+use crate::algorithms::rsa_keys::{self, RsaKey, RsaKeyFormat};
+use crate::algorithms::rsa_validation::{self, RsaKeyComponents, RsaValidationReport};
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::jwk;
 use crate::utils::logger::{LogLevel, log};
+use crate::utils::pem;
 use crate::{error::CryptoError, traits::Algorithm, utils::file_handler};
-use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
+use crypto_bigint::modular::runtime_mod::{DynResidue, DynResidueParams};
+use crypto_bigint::subtle::{Choice, ConditionallySelectable};
+use crypto_bigint::zeroize::Zeroize;
+use crypto_bigint::{Limb, U128, U256, U512, U1024, U2048, U4096, U8192, U16384, Uint, Word};
+use num_bigint::{BigUint, RandBigInt};
 use num_integer::Integer;
 use num_traits::{One, Zero};
 use rand::rngs::OsRng;
-use serde::Deserialize;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::fs;
 use std::path::Path;
+use std::time::Instant;
 
-const MR_ROUNDS: usize = 12;
-const DEFAULT_E: u64 = 65_537;
+pub const DEFAULT_E: u64 = 65_537;
+/// Identyfikator formatu szyfrogramu dzielonego na bloki
+pub(crate) const BLOCK_FORMAT: &str = "rsa-textbook-blocks";
+/// Minimalny rozmiar modułu dopuszczany poza trybem edukacyjnym
+pub const MIN_MODULUS_BITS: usize = 2048;
+/// Minimalny rozmiar modułu w trybie edukacyjnym (klucze łatwe do złamania)
+pub const MIN_EDUCATIONAL_MODULUS_BITS: usize = 32;
+/// Najkrótszy moduł, którego można użyć do szyfrowania; krótsze klucze służą
+/// wyłącznie do analizy i ataków edukacyjnych
+pub const MIN_USABLE_MODULUS_BITS: usize = 512;
+/// FIPS 186-5 A.1.3: |p - q| musi być większe niż 2^(nlen/2 - 100)
+const PRIME_DISTANCE_MARGIN_BITS: usize = 100;
+/// Największa obsługiwana liczba czynników klucza wieloczynnikowego (RFC 8017)
+pub const MAX_PRIME_COUNT: usize = 5;
+/// Minimalna długość pojedynczego czynnika w trybie edukacyjnym
+const MIN_EDUCATIONAL_PRIME_BITS: usize = 16;
+
+/// Zasady generowania kluczy RSA
+#[derive(Debug, Clone, Copy)]
+pub struct RsaKeyPolicy {
+    /// Dopuszcza moduły krótsze niż `MIN_MODULUS_BITS` (np. na potrzeby zajęć)
+    pub educational: bool,
+    /// Uzupełnia test Millera–Rabina o silny test Lucasa (Baillie–PSW)
+    pub lucas_test: bool,
+    /// Liczba czynników pierwszych modułu (2 – klasyczne RSA, 3–5 – wieloczynnikowe)
+    pub prime_count: usize,
+    /// Eksponent publiczny e; wartości nie większe niż 2^16 (np. 3 do pokazu
+    /// ataku Håstada) są dozwolone tylko w trybie edukacyjnym
+    pub public_exponent: u64,
+}
+
+impl Default for RsaKeyPolicy {
+    fn default() -> Self {
+        Self {
+            educational: false,
+            lucas_test: false,
+            prime_count: 2,
+            public_exponent: DEFAULT_E,
+        }
+    }
+}
+
+impl RsaKeyPolicy {
+    /// Największa liczba czynników dla modułu o danej długości. Poza trybem
+    /// edukacyjnym obowiązują progi OpenSSL (3 czynniki poniżej 4096 bitów,
+    /// 4 poniżej 8192), żeby czynniki nie były podatne na metodę krzywych
+    /// eliptycznych.
+    pub fn max_prime_count(&self, bits: usize) -> usize {
+        if self.educational {
+            (bits / MIN_EDUCATIONAL_PRIME_BITS).clamp(2, MAX_PRIME_COUNT)
+        } else {
+            match bits {
+                ..4096 => 3,
+                4096..8192 => 4,
+                _ => MAX_PRIME_COUNT,
+            }
+        }
+    }
+
+    fn check_prime_count(&self, bits: usize) -> Result<(), CryptoError> {
+        let max = self.max_prime_count(bits);
+        if (2..=max).contains(&self.prime_count) {
+            return Ok(());
+        }
+        Err(CryptoError::InvalidKey(format!(
+            "Moduł RSA o długości {} bitów może mieć od 2 do {} czynników pierwszych, żądano {}",
+            bits, max, self.prime_count
+        )))
+    }
+
+    pub fn min_modulus_bits(&self) -> usize {
+        if self.educational {
+            MIN_EDUCATIONAL_MODULUS_BITS
+        } else {
+            MIN_MODULUS_BITS
+        }
+    }
+
+    /// FIPS 186-5 wymaga nieparzystego e z przedziału (2^16, 2^256); e musi
+    /// też być mniejsze od modułu
+    fn check_public_exponent(&self, bits: usize) -> Result<(), CryptoError> {
+        let e = self.public_exponent;
+        if e < 3 || e.is_multiple_of(2) || (e.ilog2() as usize) + 1 >= bits {
+            return Err(CryptoError::InvalidKey(format!(
+                "Eksponent publiczny musi być nieparzystą liczbą od 3 do 2^{} - 1, podano {}",
+                bits.saturating_sub(1),
+                e
+            )));
+        }
+        if e <= 1 << 16 && !self.educational {
+            return Err(CryptoError::InvalidKey(format!(
+                "Eksponent publiczny {} jest zbyt mały (wymagane e > 65536). Małe wykładniki są dostępne tylko w trybie edukacyjnym.",
+                e
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_modulus_bits(&self, bits: usize) -> Result<(), CryptoError> {
+        let min_bits = self.min_modulus_bits();
+        if bits >= min_bits {
+            return Ok(());
+        }
+
+        let hint = if self.educational {
+            ""
+        } else {
+            " Krótsze klucze są dostępne tylko w trybie edukacyjnym."
+        };
+        Err(CryptoError::KeyTooShort(format!(
+            "moduł RSA musi mieć co najmniej {} bitów, żądano {}.{}",
+            min_bits, bits, hint
+        )))
+    }
+}
 
 pub struct RsaCipher {
     key: RsaKeyMaterial,
@@ -20,6 +147,7 @@ struct RsaKeyMaterial {
     modulus: BigUint,
     public_exp: Option<BigUint>,
     private_exp: Option<BigUint>,
+    primes: Vec<BigUint>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,10 +156,86 @@ pub struct RsaPublicKey {
     pub n: BigUint,
 }
 
+impl RsaPublicKey {
+    /// Odcisk SHA-256 struktury SubjectPublicKeyInfo (DER), zgodny z
+    /// `openssl pkey -pubin -outform DER | sha256sum`
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(&rsa_keys::public_to_spki_der(self))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RsaPrivateKey {
     pub d: BigUint,
+    pub e: BigUint,
     pub n: BigUint,
+    pub p: BigUint,
+    pub q: BigUint,
+    /// Dodatkowe czynniki r_3..r_u klucza wieloczynnikowego (RFC 8017)
+    pub other_primes: Vec<BigUint>,
+}
+
+/// Element struktury OtherPrimeInfos: czynnik r_i, d_i = d mod (r_i - 1) oraz
+/// t_i = (r_1 · … · r_(i-1))^-1 mod r_i
+#[derive(Debug, Clone)]
+pub struct RsaOtherPrimeInfo {
+    pub prime: BigUint,
+    pub exponent: BigUint,
+    pub coefficient: BigUint,
+}
+
+impl RsaPrivateKey {
+    /// Wszystkie czynniki modułu w kolejności p, q, r_3, …
+    pub fn primes(&self) -> Vec<BigUint> {
+        let mut primes = vec![self.p.clone(), self.q.clone()];
+        primes.extend(self.other_primes.iter().cloned());
+        primes
+    }
+
+    /// Parametry kolejnych czynników r_3..r_u zapisywane w OtherPrimeInfos
+    pub fn other_prime_infos(&self) -> Result<Vec<RsaOtherPrimeInfo>, CryptoError> {
+        let one = BigUint::one();
+        let mut product = &self.p * &self.q;
+        let mut infos = Vec::with_capacity(self.other_primes.len());
+        for (index, prime) in self.other_primes.iter().enumerate() {
+            if *prime <= one {
+                return Err(CryptoError::InvalidKey(
+                    "Czynniki klucza RSA muszą być większe od 1".to_string(),
+                ));
+            }
+            let coefficient =
+                number_theory::mod_inverse(&(&product % prime), prime).ok_or_else(|| {
+                    CryptoError::InvalidKey(format!(
+                        "Czynnik nr {} nie jest względnie pierwszy z poprzednimi",
+                        index + 3
+                    ))
+                })?;
+            infos.push(RsaOtherPrimeInfo {
+                prime: prime.clone(),
+                exponent: &self.d % (prime - &one),
+                coefficient,
+            });
+            product *= prime;
+        }
+        Ok(infos)
+    }
+
+    /// Parametry CRT (dP, dQ, qInv) zapisywane w strukturze PKCS#1
+    pub fn crt_params(&self) -> Result<(BigUint, BigUint, BigUint), CryptoError> {
+        let one = BigUint::one();
+        if self.p <= one || self.q <= one {
+            return Err(CryptoError::InvalidKey(
+                "Czynniki p i q klucza RSA muszą być większe od 1".to_string(),
+            ));
+        }
+
+        let dp = &self.d % (&self.p - &one);
+        let dq = &self.d % (&self.q - &one);
+        let qinv = number_theory::mod_inverse(&self.q, &self.p).ok_or_else(|| {
+            CryptoError::InvalidKey("Czynnik q nie jest odwracalny modulo p".to_string())
+        })?;
+        Ok((dp, dq, qinv))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +244,15 @@ pub struct RsaKeyPair {
     pub private: RsaPrivateKey,
 }
 
+/// Szyfrogram trybu podręcznikowego: każdy blok (bajt długości + dane)
+/// zaszyfrowany osobno i zapisany szesnastkowo
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RsaBlockCiphertext {
+    pub(crate) format: String,
+    pub(crate) block_size: usize,
+    pub(crate) blocks: Vec<String>,
+}
+
 #[derive(Deserialize)]
 struct RsaKeyPayload {
     n: String,
@@ -49,28 +262,172 @@ struct RsaKeyPayload {
     d: Option<String>,
 }
 
+impl From<RsaKey> for RsaKeyMaterial {
+    fn from(key: RsaKey) -> Self {
+        match key {
+            RsaKey::Public(public) => RsaKeyMaterial {
+                modulus: public.n,
+                public_exp: Some(public.e),
+                private_exp: None,
+                primes: Vec::new(),
+            },
+            RsaKey::Private(private) => RsaKeyMaterial {
+                primes: private.primes(),
+                modulus: private.n,
+                public_exp: Some(private.e),
+                private_exp: Some(private.d),
+            },
+        }
+    }
+}
+
 impl RsaCipher {
-    pub fn new(key_source: &str) -> Result<Self, CryptoError> {
-        let payload = load_key_payload(key_source)?;
-        let modulus = parse_biguint(&payload.n)?;
-        let public_exp = match payload.e {
-            Some(value) => Some(parse_biguint(&value)?),
-            None => None,
-        };
-        let private_exp = match payload.d {
-            Some(value) => Some(parse_biguint(&value)?),
-            None => None,
-        };
+    /// Wczytuje klucz i odrzuca go, jeśli nie przejdzie walidacji albo moduł
+    /// jest krótszy niż `MIN_USABLE_MODULUS_BITS`. Moduły krótsze niż
+    /// `MIN_MODULUS_BITS` są dopuszczane, ale odnotowywane w logu.
+    /// `passphrase` jest wymagane dla zaszyfrowanego klucza prywatnego.
+    pub fn new(key_source: &str, passphrase: Option<&str>) -> Result<Self, CryptoError> {
+        let cipher = Self::load_unchecked(key_source, passphrase)?;
+
+        let report = cipher.validate(RsaKeyPolicy {
+            educational: true,
+            ..Default::default()
+        });
+        if !report.is_valid() {
+            let reasons: Vec<&str> = report
+                .failures()
+                .map(|result| result.message.as_str())
+                .collect();
+            return Err(CryptoError::InvalidKey(format!(
+                "Klucz RSA nie przeszedł walidacji: {}",
+                reasons.join("; ")
+            )));
+        }
+        let bits = cipher.key.modulus.bits();
+        if bits < MIN_USABLE_MODULUS_BITS as u64 {
+            return Err(CryptoError::KeyTooShort(format!(
+                "moduł RSA musi mieć co najmniej {} bitów, klucz ma {}. Krótsze klucze \
+                 można jedynie analizować i atakować w trybie edukacyjnym.",
+                MIN_USABLE_MODULUS_BITS, bits
+            )));
+        }
+        if bits < MIN_MODULUS_BITS as u64 {
+            log(
+                LogLevel::WARN,
+                "RSA",
+                format!(
+                    "Klucz RSA ma tylko {} bitów – używaj go wyłącznie w celach edukacyjnych",
+                    bits
+                ),
+            );
+        }
+        Ok(cipher)
+    }
 
+    /// Wczytuje klucz bez walidacji, np. aby przygotować raport z błędami
+    pub fn load_unchecked(key_source: &str, passphrase: Option<&str>) -> Result<Self, CryptoError> {
         Ok(Self {
-            key: RsaKeyMaterial {
-                modulus,
-                public_exp,
-                private_exp,
+            key: load_key_material(key_source, passphrase)?,
+        })
+    }
+
+    pub fn validate(&self, policy: RsaKeyPolicy) -> RsaValidationReport {
+        rsa_validation::validate(
+            &RsaKeyComponents {
+                n: &self.key.modulus,
+                e: self.key.public_exp.as_ref(),
+                d: self.key.private_exp.as_ref(),
+                primes: &self.key.primes,
             },
+            policy,
+        )
+    }
+
+    pub fn public_key(&self) -> Option<RsaPublicKey> {
+        self.key.public_exp.as_ref().map(|e| RsaPublicKey {
+            e: e.clone(),
+            n: self.key.modulus.clone(),
         })
     }
 
+    /// Pełny klucz prywatny wymaga e, d oraz czynników p i q. Jeśli czynniki nie
+    /// zostały podane, próbujemy odtworzyć je z (n, e, d) – odtworzyć da się
+    /// tylko klucz dwuczynnikowy.
+    pub fn private_key(&self) -> Option<RsaPrivateKey> {
+        let e = self.key.public_exp.as_ref()?;
+        let d = self.key.private_exp.as_ref()?;
+        let n = &self.key.modulus;
+        let (p, q, other_primes) = match self.key.primes.as_slice() {
+            [p, q, rest @ ..] => (p.clone(), q.clone(), rest.to_vec()),
+            [] => {
+                let (p, q) = recover_factors(n, e, d)?;
+                (p, q, Vec::new())
+            }
+            [_] => return None,
+        };
+
+        Some(RsaPrivateKey {
+            d: d.clone(),
+            e: e.clone(),
+            n: n.clone(),
+            p,
+            q,
+            other_primes,
+        })
+    }
+
+    /// Eksportuje klucz do obiektu JSON z polami "public" i (opcjonalnie) "private".
+    /// W formatach PKCS#1/PKCS#8 pola zawierają bloki PEM, a w formacie JWK obiekty JWK.
+    /// Podanie hasła zapisuje klucz prywatny jako zaszyfrowany PKCS#8 niezależnie od formatu.
+    pub fn export(
+        &self,
+        format: RsaKeyFormat,
+        passphrase: Option<&str>,
+    ) -> Result<Value, CryptoError> {
+        let public = self.public_key().ok_or_else(|| {
+            CryptoError::InvalidKey(
+                "Eksport klucza RSA wymaga eksponenta publicznego 'e'".to_string(),
+            )
+        })?;
+
+        let mut payload = match format {
+            RsaKeyFormat::Json => json!({
+                "public": {
+                    "n": public.n.to_str_radix(10),
+                    "e": public.e.to_str_radix(10)
+                }
+            }),
+            RsaKeyFormat::Jwk => json!({ "public": rsa_keys::public_to_jwk(&public) }),
+            _ => json!({ "public": rsa_keys::encode_public_pem(&public, format) }),
+        };
+        payload["fingerprint"] = public.fingerprint().to_json();
+
+        if let Some(private_exp) = &self.key.private_exp {
+            payload["private"] = match (format, passphrase, self.private_key()) {
+                (_, Some(passphrase), Some(private)) => Value::String(
+                    rsa_keys::encode_private_pem_encrypted(&private, passphrase)?,
+                ),
+                (RsaKeyFormat::Json, None, _) => json!({
+                    "n": self.key.modulus.to_str_radix(10),
+                    "e": public.e.to_str_radix(10),
+                    "d": private_exp.to_str_radix(10)
+                }),
+                (RsaKeyFormat::Jwk, None, Some(private)) => rsa_keys::private_to_jwk(&private)?,
+                (_, None, Some(private)) => {
+                    Value::String(rsa_keys::encode_private_pem(&private, format)?)
+                }
+                (_, _, None) => {
+                    return Err(CryptoError::InvalidKey(
+                        "Nie można odtworzyć czynników p i q klucza prywatnego potrzebnych do eksportu"
+                            .to_string(),
+                    ));
+                }
+            };
+        }
+
+        Ok(payload)
+    }
+
     fn require_public_components(&self) -> Result<(&BigUint, &BigUint), CryptoError> {
         let exponent = self.key.public_exp.as_ref().ok_or_else(|| {
             CryptoError::InvalidKey(
@@ -81,14 +438,23 @@ impl RsaCipher {
         Ok((exponent, &self.key.modulus))
     }
 
-    fn require_private_components(&self) -> Result<(&BigUint, &BigUint), CryptoError> {
+    /// Eksponent prywatny, eksponent publiczny (potrzebny do maskowania
+    /// szyfrogramu) i moduł
+    fn require_private_components(&self) -> Result<(&BigUint, &BigUint, &BigUint), CryptoError> {
         let exponent = self.key.private_exp.as_ref().ok_or_else(|| {
             CryptoError::InvalidKey(
                 "Klucz prywatny RSA musi zawierać pole 'd' (eksponent prywatny)".to_string(),
             )
         })?;
+        let public_exp = self.key.public_exp.as_ref().ok_or_else(|| {
+            CryptoError::InvalidKey(
+                "Klucz prywatny RSA musi zawierać pole 'e' (eksponent publiczny), bez którego \
+                 nie można zamaskować szyfrogramu"
+                    .to_string(),
+            )
+        })?;
 
-        Ok((exponent, &self.key.modulus))
+        Ok((exponent, public_exp, &self.key.modulus))
     }
 }
 
@@ -97,41 +463,58 @@ impl Algorithm for RsaCipher {
         log(
             LogLevel::INFO,
             "RSA",
-            &format!("Rozpoczynanie szyfrowania RSA dla pliku: {}", file_path),
+            format!("Rozpoczynanie szyfrowania RSA dla pliku: {}", file_path),
         );
 
         log(LogLevel::INFO, "RSA", "Wczytywanie klucza publicznego...");
         let (public_exp, modulus) = self.require_public_components()?;
+        let block_size = block_capacity(modulus).ok_or_else(|| {
+            CryptoError::InvalidKey("Moduł RSA jest zbyt mały do szyfrowania bloków".to_string())
+        })?;
 
-        let plaintext = file_handler::read_file(file_path)?;
+        let plaintext = fs::read(file_path)?;
         log(
             LogLevel::INFO,
             "RSA",
-            &format!("Wczytano plik, rozmiar: {} bajtów", plaintext.len()),
+            format!("Wczytano plik, rozmiar: {} bajtów", plaintext.len()),
         );
 
-        let message = BigUint::from_bytes_be(plaintext.as_bytes());
-
-        if message >= *modulus {
-            return Err(CryptoError::InvalidFormat(
-                "Wiadomość jest większa lub równa modułowi RSA. Użyj większego klucza lub podziel dane na mniejsze bloki.".to_string(),
-            ));
-        }
-
         log(
             LogLevel::INFO,
             "RSA",
-            "Szyfrowanie (potęgowanie modularne)...",
+            format!(
+                "Szyfrowanie {} bloków po maks. {} bajtów (potęgowanie modularne)...",
+                plaintext.len().div_ceil(block_size),
+                block_size
+            ),
         );
-        let ciphertext = encrypt_block(&message, public_exp, modulus);
-        let cipher_hex = format!("{:x}", ciphertext);
+        let blocks = plaintext
+            .chunks(block_size)
+            .map(|chunk| {
+                // Bajt długości przed danymi zachowuje zera wiodące bloku
+                let mut framed = Vec::with_capacity(chunk.len() + 1);
+                framed.push(chunk.len() as u8);
+                framed.extend_from_slice(chunk);
+                let message = BigUint::from_bytes_be(&framed);
+                format!("{:x}", encrypt_block(&message, public_exp, modulus))
+            })
+            .collect();
+
+        let ciphertext = RsaBlockCiphertext {
+            format: BLOCK_FORMAT.to_string(),
+            block_size,
+            blocks,
+        };
+        let serialized = serde_json::to_string_pretty(&ciphertext).map_err(|err| {
+            CryptoError::InvalidFormat(format!("Nie można zapisać szyfrogramu: {}", err))
+        })?;
 
         let output_path = file_handler::create_output_path_with_suffix(file_path, "_encrypted");
-        file_handler::write_file(&output_path, &cipher_hex)?;
+        file_handler::write_file(&output_path, &serialized)?;
         log(
             LogLevel::INFO,
             "RSA",
-            &format!("Szyfrowanie zakończone. Zapisano do: {}", output_path),
+            format!("Szyfrowanie zakończone. Zapisano do: {}", output_path),
         );
         Ok(output_path)
     }
@@ -140,23 +523,135 @@ impl Algorithm for RsaCipher {
         log(
             LogLevel::INFO,
             "RSA",
-            &format!("Rozpoczynanie deszyfrowania RSA dla pliku: {}", file_path),
+            format!("Rozpoczynanie deszyfrowania RSA dla pliku: {}", file_path),
         );
 
         log(LogLevel::INFO, "RSA", "Wczytywanie klucza prywatnego...");
-        let (private_exp, modulus) = self.require_private_components()?;
+        self.require_private_components()?;
 
-        let ciphertext_hex = file_handler::read_file(file_path)?;
+        let contents = file_handler::read_file(file_path)?;
         log(
             LogLevel::INFO,
             "RSA",
-            &format!("Wczytano plik, rozmiar: {} bajtów", ciphertext_hex.len()),
+            format!("Wczytano plik, rozmiar: {} bajtów", contents.len()),
         );
 
-        let cleaned: String = ciphertext_hex
-            .chars()
-            .filter(|c| !c.is_whitespace())
-            .collect();
+        let crt = self.crt_key();
+        match &crt {
+            Some(crt) => log(
+                LogLevel::INFO,
+                "RSA",
+                format!(
+                    "Deszyfrowanie z użyciem CRT ({} czynniki pierwsze)",
+                    crt.primes.len()
+                ),
+            ),
+            None => log(
+                LogLevel::INFO,
+                "RSA",
+                "Brak czynników modułu – deszyfrowanie bez CRT",
+            ),
+        }
+
+        let started = Instant::now();
+        let plaintext = if contents.trim_start().starts_with('{') {
+            self.decrypt_blocks(&contents, crt.as_ref())?
+        } else {
+            self.decrypt_single_block(&contents, crt.as_ref())?
+        };
+        log(
+            LogLevel::INFO,
+            "RSA",
+            format!(
+                "Operacje klucza prywatnego zajęły {:.3} ms",
+                started.elapsed().as_secs_f64() * 1000.0
+            ),
+        );
+
+        let output_path = file_handler::create_output_path_with_suffix(file_path, "_decrypted");
+        fs::write(&output_path, &plaintext).map_err(|err| {
+            CryptoError::FileWriteError(format!("Nie można zapisać do pliku: {}", err))
+        })?;
+        log(
+            LogLevel::INFO,
+            "RSA",
+            format!("Deszyfrowanie zakończone. Zapisano do: {}", output_path),
+        );
+        Ok(output_path)
+    }
+}
+
+impl RsaCipher {
+    /// Parametry CRT, jeśli klucz zawiera czynniki modułu lub da się je odtworzyć
+    fn crt_key(&self) -> Option<CrtKey> {
+        let private = self.private_key()?;
+        CrtKey::new(&private.primes(), &private.d)
+    }
+
+    fn decrypt_blocks(&self, contents: &str, crt: Option<&CrtKey>) -> Result<Vec<u8>, CryptoError> {
+        let (private_exp, public_exp, modulus) = self.require_private_components()?;
+        let ciphertext: RsaBlockCiphertext = serde_json::from_str(contents).map_err(|err| {
+            CryptoError::InvalidFormat(format!("Nieprawidłowa struktura szyfrogramu RSA: {}", err))
+        })?;
+        if ciphertext.format != BLOCK_FORMAT {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Nieobsługiwany format szyfrogramu: {}",
+                ciphertext.format
+            )));
+        }
+        if Some(ciphertext.block_size) != block_capacity(modulus) {
+            return Err(CryptoError::DecryptionError(
+                "Rozmiar bloków szyfrogramu nie pasuje do modułu klucza. Szyfrogram utworzono innym kluczem."
+                    .to_string(),
+            ));
+        }
+
+        log(
+            LogLevel::INFO,
+            "RSA",
+            format!(
+                "Deszyfrowanie {} bloków (potęgowanie modularne)...",
+                ciphertext.blocks.len()
+            ),
+        );
+        let mut plaintext = Vec::with_capacity(ciphertext.blocks.len() * ciphertext.block_size);
+        for (index, block_hex) in ciphertext.blocks.iter().enumerate() {
+            let block = BigUint::parse_bytes(block_hex.trim().as_bytes(), 16)
+                .filter(|block| block < modulus)
+                .ok_or_else(|| {
+                    CryptoError::InvalidFormat(format!(
+                        "Blok nr {} nie jest poprawną liczbą mniejszą od modułu",
+                        index + 1
+                    ))
+                })?;
+
+            let framed =
+                decrypt_block(&block, private_exp, public_exp, modulus, crt)?.to_bytes_be();
+            match framed.split_first() {
+                Some((&len, data))
+                    if len as usize == data.len() && data.len() <= ciphertext.block_size =>
+                {
+                    plaintext.extend_from_slice(data);
+                }
+                _ => {
+                    return Err(CryptoError::DecryptionError(format!(
+                        "Blok nr {} ma nieprawidłową długość. Klucz jest niepoprawny lub dane są uszkodzone.",
+                        index + 1
+                    )));
+                }
+            }
+        }
+        Ok(plaintext)
+    }
+
+    /// Starszy format: cały tekst zaszyfrowany jako jedna liczba w zapisie hex
+    fn decrypt_single_block(
+        &self,
+        contents: &str,
+        crt: Option<&CrtKey>,
+    ) -> Result<Vec<u8>, CryptoError> {
+        let (private_exp, public_exp, modulus) = self.require_private_components()?;
+        let cleaned: String = contents.chars().filter(|c| !c.is_whitespace()).collect();
         if cleaned.is_empty() {
             return Err(CryptoError::InvalidFormat(
                 "Plik z szyfrogramem jest pusty lub zawiera tylko białe znaki".to_string(),
@@ -180,45 +675,102 @@ impl Algorithm for RsaCipher {
             "RSA",
             "Deszyfrowanie (potęgowanie modularne)...",
         );
-        let message = decrypt_block(&ciphertext, private_exp, modulus);
-        let message_bytes = message.to_bytes_be();
-        let plaintext = String::from_utf8(message_bytes).map_err(|_| {
-            CryptoError::InvalidFormat(
-                "Odszyfrowany tekst nie jest poprawnym UTF-8. Upewnij się, że szyfrowany plik zawierał tekst.".to_string(),
-            )
-        })?;
+        let message = decrypt_block(&ciphertext, private_exp, public_exp, modulus, crt)?;
+        Ok(message.to_bytes_be())
+    }
+}
 
-        let output_path = file_handler::create_output_path_with_suffix(file_path, "_decrypted");
-        file_handler::write_file(&output_path, &plaintext)?;
-        log(
-            LogLevel::INFO,
-            "RSA",
-            &format!("Deszyfrowanie zakończone. Zapisano do: {}", output_path),
-        );
-        Ok(output_path)
+/// Liczba bajtów danych w jednym bloku: blok wraz z bajtem długości musi być
+/// mniejszy od modułu, a długość musi zmieścić się w jednym bajcie
+fn block_capacity(modulus: &BigUint) -> Option<usize> {
+    let framed = usize::try_from((modulus.bits().saturating_sub(1)) / 8).ok()?;
+    match framed.checked_sub(1) {
+        Some(0) | None => None,
+        Some(size) => Some(size.min(u8::MAX as usize)),
     }
 }
 
-fn load_key_payload(key_source: &str) -> Result<RsaKeyPayload, CryptoError> {
+fn load_key_material(
+    key_source: &str,
+    passphrase: Option<&str>,
+) -> Result<RsaKeyMaterial, CryptoError> {
     let trimmed = key_source.trim();
-    let json_payload = if trimmed.starts_with('{') {
-        trimmed.to_owned()
+    if trimmed.starts_with('{') {
+        return key_material_from_json(trimmed);
+    }
+    if pem::is_pem(trimmed) {
+        return rsa_keys::parse_pem(trimmed, passphrase).map(RsaKeyMaterial::from);
+    }
+
+    let path = Path::new(trimmed);
+    if !path.exists() {
+        return Err(CryptoError::InvalidKey(
+            "Klucz RSA musi być obiektem JSON, blokiem PEM lub ścieżką do pliku z kluczem"
+                .to_string(),
+        ));
+    }
+
+    let contents = fs::read(path).map_err(|err| {
+        CryptoError::InvalidKey(format!("Nie można odczytać klucza RSA z pliku: {}", err))
+    })?;
+
+    // Binarny DER zaczyna się od tagu SEQUENCE, pozostałe formaty są tekstowe
+    if contents.first() == Some(&0x30) {
+        return rsa_keys::parse_der(&contents, passphrase).map(RsaKeyMaterial::from);
+    }
+
+    let text = String::from_utf8(contents).map_err(|_| {
+        CryptoError::InvalidKey("Plik z kluczem RSA nie jest plikiem tekstowym".to_string())
+    })?;
+    if pem::is_pem(&text) {
+        rsa_keys::parse_pem(&text, passphrase).map(RsaKeyMaterial::from)
     } else {
-        let path = Path::new(trimmed);
-        if path.exists() {
-            fs::read_to_string(path).map_err(|err| {
-                CryptoError::InvalidKey(format!("Nie można odczytać klucza RSA z pliku: {}", err))
-            })?
-        } else {
-            return Err(CryptoError::InvalidKey(
-                "Klucz RSA musi być obiektem JSON lub ścieżką do pliku zawierającego JSON"
-                    .to_string(),
-            ));
-        }
-    };
+        key_material_from_json(&text)
+    }
+}
 
-    serde_json::from_str(&json_payload).map_err(|err| {
+fn key_material_from_json(json_payload: &str) -> Result<RsaKeyMaterial, CryptoError> {
+    let value: Value = serde_json::from_str(json_payload).map_err(|err| {
         CryptoError::InvalidKey(format!("Nie można sparsować klucza RSA z JSON: {}", err))
+    })?;
+    if jwk::is_jwk(&value) {
+        return key_material_from_jwk(&value);
+    }
+
+    let payload: RsaKeyPayload = serde_json::from_value(value).map_err(|err| {
+        CryptoError::InvalidKey(format!("Nie można sparsować klucza RSA z JSON: {}", err))
+    })?;
+
+    let modulus = parse_biguint(&payload.n)?;
+    let public_exp = match payload.e {
+        Some(value) => Some(parse_biguint(&value)?),
+        None => None,
+    };
+    let private_exp = match payload.d {
+        Some(value) => Some(parse_biguint(&value)?),
+        None => None,
+    };
+
+    Ok(RsaKeyMaterial {
+        modulus,
+        public_exp,
+        private_exp,
+        primes: Vec::new(),
+    })
+}
+
+fn key_material_from_jwk(value: &Value) -> Result<RsaKeyMaterial, CryptoError> {
+    let (public, private) = rsa_keys::parse_jwk(value)?;
+    let (private_exp, primes) = match private {
+        Some(private) => (Some(private.d), private.factors),
+        None => (None, Vec::new()),
+    };
+
+    Ok(RsaKeyMaterial {
+        modulus: public.n,
+        public_exp: Some(public.e),
+        private_exp,
+        primes,
     })
 }
 
@@ -244,66 +796,315 @@ fn encrypt_block(message: &BigUint, exponent: &BigUint, modulus: &BigUint) -> Bi
     message.modpow(exponent, modulus)
 }
 
-fn decrypt_block(ciphertext: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
-    ciphertext.modpow(exponent, modulus)
+/// Parametry deszyfrowania CRT dla klucza o dowolnej liczbie czynników
+/// (RFC 8017, 5.1.2, wariant 2b)
+struct CrtKey {
+    primes: Vec<BigUint>,
+    /// d_i = d mod (r_i - 1)
+    exponents: Vec<BigUint>,
+    /// t_i = (r_1 · … · r_(i-1))^-1 mod r_i; pierwszy element jest nieużywany
+    coefficients: Vec<BigUint>,
+}
+
+impl CrtKey {
+    fn new(primes: &[BigUint], d: &BigUint) -> Option<Self> {
+        let one = BigUint::one();
+        if primes.len() < 2 || primes.iter().any(|prime| *prime <= one) {
+            return None;
+        }
+
+        let mut product = one.clone();
+        let mut exponents = Vec::with_capacity(primes.len());
+        let mut coefficients = Vec::with_capacity(primes.len());
+        for prime in primes {
+            exponents.push(d % (prime - &one));
+            coefficients.push(number_theory::mod_inverse(&(&product % prime), prime)?);
+            product *= prime;
+        }
+        Some(Self {
+            primes: primes.to_vec(),
+            exponents,
+            coefficients,
+        })
+    }
+
+    /// Potęgowanie modulo każdy czynnik osobno i złożenie wyniku algorytmem
+    /// Garnera
+    fn exponentiate(&self, base: &BigUint) -> Option<BigUint> {
+        let mut result = BigUint::zero();
+        let mut product = BigUint::one();
+        for ((prime, exponent), coefficient) in self
+            .primes
+            .iter()
+            .zip(&self.exponents)
+            .zip(&self.coefficients)
+        {
+            let residue = montgomery_ladder(&(base % prime), exponent, prime)?;
+            // h = (m_i - m) · t_i mod r_i
+            let current = &result % prime;
+            let h = ((residue + prime - current) * coefficient) % prime;
+            result += &product * h;
+            product *= prime;
+        }
+        Some(result)
+    }
+}
+
+/// Operacja klucza prywatnego c^d mod n (przez CRT, jeśli znane są czynniki).
+/// Szyfrogram jest maskowany losowym r (c·r^e), więc czas potęgowania nie
+/// zależy od danych atakującego.
+fn decrypt_block(
+    ciphertext: &BigUint,
+    exponent: &BigUint,
+    public_exp: &BigUint,
+    modulus: &BigUint,
+    crt: Option<&CrtKey>,
+) -> Result<BigUint, CryptoError> {
+    let invalid_modulus = || {
+        CryptoError::InvalidKey(
+            "Moduł RSA i jego czynniki muszą być nieparzyste i mieć co najwyżej 16384 bity"
+                .to_string(),
+        )
+    };
+    let (blinded, unblinder) =
+        blind(ciphertext, public_exp, modulus).ok_or_else(invalid_modulus)?;
+    let message = match crt {
+        Some(crt) => crt.exponentiate(&blinded),
+        None => montgomery_ladder(&blinded, exponent, modulus),
+    }
+    .ok_or_else(invalid_modulus)?;
+
+    Ok((message * unblinder) % modulus)
+}
+
+/// Zwraca zamaskowany szyfrogram c·r^e mod n oraz r^-1 mod n
+fn blind(ciphertext: &BigUint, e: &BigUint, modulus: &BigUint) -> Option<(BigUint, BigUint)> {
+    let mut rng = OsRng;
+    let two = BigUint::from(2u8);
+    if *modulus <= two {
+        return None;
+    }
+
+    loop {
+        let r = rng.gen_biguint_range(&two, modulus);
+        if let Some(r_inv) = number_theory::mod_inverse(&r, modulus) {
+            let blinded = (ciphertext * r.modpow(e, modulus)) % modulus;
+            return Some((blinded, r_inv));
+        }
+    }
+}
+
+/// base^exponent mod modulus drabinką Montgomery'ego w stałym czasie.
+/// Liczby są przenoszone do `Uint` o najmniejszym z obsługiwanych rozmiarów,
+/// więc z czasu wykonania można odczytać jedynie tę klasę rozmiaru. Zwraca
+/// `None` dla parzystego modułu albo dłuższego niż 16384 bity.
+fn montgomery_ladder(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let bits = modulus.bits().max(exponent.bits());
+    macro_rules! dispatch {
+        ($($uint:ty),*) => {
+            $(
+                if bits <= <$uint>::BITS as u64 {
+                    return constant_time_ladder::<{ <$uint>::LIMBS }>(base, exponent, modulus);
+                }
+            )*
+        };
+    }
+    dispatch!(U128, U256, U512, U1024, U2048, U4096, U8192, U16384);
+    None
+}
+
+fn to_uint<const LIMBS: usize>(value: &BigUint) -> Uint<LIMBS> {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(Uint::<LIMBS>::BYTES, 0);
+    let uint = Uint::from_le_slice(&bytes);
+    bytes.zeroize();
+    uint
+}
+
+fn from_uint<const LIMBS: usize>(value: &Uint<LIMBS>) -> BigUint {
+    let bytes: Vec<u8> = value
+        .as_words()
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+/// Drabinka o stałej liczbie kroków (`Uint::BITS`), w której rejestry są
+/// zamieniane warunkowo bez rozgałęzień, a mnożenia Montgomery'ego
+/// `crypto_bigint` nie zależą od wartości argumentów
+fn constant_time_ladder<const LIMBS: usize>(
+    base: &BigUint,
+    exponent: &BigUint,
+    modulus: &BigUint,
+) -> Option<BigUint> {
+    #[allow(deprecated)]
+    let params = Option::<DynResidueParams<LIMBS>>::from(DynResidueParams::new_checked(
+        &to_uint::<LIMBS>(modulus),
+    ))?;
+    let mut exponent = to_uint::<LIMBS>(exponent);
+    let mut r0 = DynResidue::one(params);
+    let mut r1 = DynResidue::new(&to_uint::<LIMBS>(&(base % modulus)), params);
+    let mut swap: Word = 0;
+
+    for i in (0..Uint::<LIMBS>::BITS).rev() {
+        let bit = (exponent.as_words()[i / Limb::BITS] >> (i % Limb::BITS)) & 1;
+        swap ^= bit;
+        DynResidue::conditional_swap(&mut r0, &mut r1, Choice::from(swap as u8));
+        swap = bit;
+        r1 = r0 * r1;
+        r0 = r0.square();
+    }
+    DynResidue::conditional_swap(&mut r0, &mut r1, Choice::from(swap as u8));
+    exponent.zeroize();
+
+    let result = from_uint(&r0.retrieve());
+    r0.zeroize();
+    r1.zeroize();
+    Some(result)
+}
+
+impl RsaKeyPair {
+    pub fn export(
+        &self,
+        format: RsaKeyFormat,
+        passphrase: Option<&str>,
+    ) -> Result<Value, CryptoError> {
+        let cipher = RsaCipher {
+            key: RsaKeyMaterial {
+                modulus: self.public.n.clone(),
+                public_exp: Some(self.public.e.clone()),
+                private_exp: Some(self.private.d.clone()),
+                primes: self.private.primes(),
+            },
+        };
+        cipher.export(format, passphrase)
+    }
+}
+
+/// Generuje parę kluczy RSA z modułem o długości dokładnie `bits` bitów.
+/// Moduł jest iloczynem `policy.prime_count` liczb pierwszych o zbliżonej
+/// długości (dla dwóch czynników p i q mają po około `bits / 2` bitów).
+pub fn generate_keypair(
+    bits: usize,
+    policy: RsaKeyPolicy,
+    monitor: &mut PrimeSearchMonitor,
+) -> Result<RsaKeyPair, CryptoError> {
+    generate_keypair_with_rng(bits, policy, monitor, &mut OsRng)
 }
 
-pub fn generate_keypair(bits: usize) -> Result<RsaKeyPair, CryptoError> {
+/// Jak `generate_keypair`, ale z podanym źródłem losowości – ten sam stan
+/// generatora daje zawsze tę samą parę kluczy
+pub fn generate_keypair_with_rng<R: RngCore + CryptoRng>(
+    bits: usize,
+    policy: RsaKeyPolicy,
+    monitor: &mut PrimeSearchMonitor,
+    rng: &mut R,
+) -> Result<RsaKeyPair, CryptoError> {
     log(
         LogLevel::INFO,
         "RSA",
-        &format!("Generowanie pary kluczy RSA ({} bitów)...", bits),
+        format!(
+            "Generowanie pary kluczy RSA ({} bitów, czynników: {}, e = {})...",
+            bits, policy.prime_count, policy.public_exponent
+        ),
     );
 
-    if bits < 16 {
-        return Err(CryptoError::InvalidKey(
-            "Długość klucza RSA musi wynosić co najmniej 16 bitów".to_string(),
-        ));
-    }
+    policy.check_modulus_bits(bits)?;
+    policy.check_prime_count(bits)?;
+    policy.check_public_exponent(bits)?;
+
+    // Pierwsze czynniki dostają nadmiarowe bity, żeby suma długości była równa `bits`
+    let count = policy.prime_count;
+    let prime_bits: Vec<usize> = (0..count)
+        .map(|index| bits / count + usize::from(index < bits % count))
+        .collect();
+    let min_distance = BigUint::one() << (bits / count).saturating_sub(PRIME_DISTANCE_MARGIN_BITS);
+    let primality = PrimalityOptions {
+        lucas: policy.lucas_test,
+    };
 
     loop {
         log(
             LogLevel::INFO,
             "RSA",
-            "Generowanie liczb pierwszych p i q...",
+            format!(
+                "Generowanie liczb pierwszych o długościach {:?} bitów...",
+                prime_bits
+            ),
         );
-        let p = gen_prime(bits);
-        let q = gen_prime(bits);
+        let mut factors = Vec::with_capacity(count);
+        for &size in &prime_bits {
+            factors.push(primes::gen_prime(size, primality, monitor, rng)?);
+        }
 
-        if p == q {
+        let too_close = factors.iter().enumerate().any(|(i, a)| {
+            factors[..i].iter().any(|b| {
+                let distance = if a > b { a - b } else { b - a };
+                distance <= min_distance
+            })
+        });
+        if too_close {
+            log(
+                LogLevel::WARN,
+                "RSA",
+                "Czynniki pierwsze są zbyt blisko siebie, ponowne losowanie...",
+            );
             continue;
         }
 
         log(LogLevel::INFO, "RSA", "Obliczanie n, phi, e...");
-        let n = &p * &q;
-        let phi = (&p - BigUint::one()) * (&q - BigUint::one());
-        let e = BigUint::from(DEFAULT_E);
+        let n = factors
+            .iter()
+            .fold(BigUint::one(), |acc, prime| acc * prime);
+        let phi = factors
+            .iter()
+            .fold(BigUint::one(), |acc, prime| acc * (prime - BigUint::one()));
+        let e = BigUint::from(policy.public_exponent);
 
         if e.gcd(&phi) != BigUint::one() {
             continue;
         }
 
-        let e_bi = BigInt::from_bytes_be(Sign::Plus, &e.to_bytes_be());
-        let phi_bi = BigInt::from_bytes_be(Sign::Plus, &phi.to_bytes_be());
-
         log(
             LogLevel::INFO,
             "RSA",
             "Obliczanie d (odwrotność modularna)...",
         );
-        if let Some(d_bi) = modinv(&e_bi, &phi_bi) {
-            if d_bi.is_zero() {
+        if let Some(d) = number_theory::mod_inverse(&e, &phi) {
+            if d.is_zero() {
                 continue;
             }
 
-            let d_bytes = d_bi.to_signed_bytes_be();
-            let d = BigUint::from_bytes_be(&d_bytes);
+            if n.bits() != bits as u64 {
+                log(
+                    LogLevel::WARN,
+                    "RSA",
+                    format!(
+                        "Moduł ma {} bitów zamiast {}, ponowne losowanie...",
+                        n.bits(),
+                        bits
+                    ),
+                );
+                continue;
+            }
 
             let public = RsaPublicKey {
                 e: e.clone(),
                 n: n.clone(),
             };
-            let private = RsaPrivateKey { d, n: n.clone() };
+            let mut factors = factors.into_iter();
+            let (Some(p), Some(q)) = (factors.next(), factors.next()) else {
+                continue;
+            };
+            let private = RsaPrivateKey {
+                d,
+                e,
+                n,
+                p,
+                q,
+                other_primes: factors.collect(),
+            };
 
             log(LogLevel::INFO, "RSA", "Klucze wygenerowane pomyślnie.");
             return Ok(RsaKeyPair { public, private });
@@ -311,81 +1112,279 @@ pub fn generate_keypair(bits: usize) -> Result<RsaKeyPair, CryptoError> {
     }
 }
 
-fn gen_prime(bits: usize) -> BigUint {
+/// Odtwarza czynniki p i q z (n, e, d) metodą probabilistyczną (NIST SP 800-56B, C.2).
+/// Zwraca `None`, gdy klucz jest niespójny albo moduł ma więcej niż dwa
+/// czynniki – wtedy jeden z członów rozkładu byłby złożony.
+fn recover_factors(n: &BigUint, e: &BigUint, d: &BigUint) -> Option<(BigUint, BigUint)> {
+    let one = BigUint::one();
+    let two = BigUint::from(2u8);
+    if *n <= BigUint::from(3u8) {
+        return None;
+    }
+
+    let k = d * e - &one;
+    if k.is_zero() || k.is_odd() {
+        return None;
+    }
+    let s = k.trailing_zeros().unwrap_or(0);
+    let t = &k >> s;
+    let n_minus_one = n - &one;
+
     let mut rng = OsRng;
-    let bit_size = u64::try_from(bits).unwrap_or(u64::MAX);
+    for _ in 0..100 {
+        let g = rng.gen_biguint_range(&two, &n_minus_one);
+        let mut x = g.modpow(&t, n);
+        for _ in 0..s {
+            let y = x.modpow(&two, n);
+            if y == one {
+                if x != one && x != n_minus_one {
+                    let p = (&x - &one).gcd(n);
+                    let q = n / &p;
+                    let options = PrimalityOptions::default();
+                    if !primes::is_prime(&p, options) || !primes::is_prime(&q, options) {
+                        return None;
+                    }
+                    return Some(if p > q { (p, q) } else { (q, p) });
+                }
+                break;
+            }
+            x = y;
+        }
+    }
+    None
+}
 
-    loop {
-        let mut candidate = rng.gen_biguint(bit_size);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if bits > 1 {
-            candidate.set_bit((bits - 1) as u64, true);
+    fn educational_key(bits: usize) -> String {
+        let policy = RsaKeyPolicy {
+            educational: true,
+            ..Default::default()
+        };
+        generate_keypair(bits, policy, &mut PrimeSearchMonitor::default())
+            .and_then(|pair| pair.export(RsaKeyFormat::Json, None))
+            .unwrap()["private"]
+            .to_string()
+    }
+
+    #[test]
+    fn keys_below_512_bits_cannot_be_loaded() {
+        for bits in [64, 256, 511] {
+            assert!(matches!(
+                RsaCipher::new(&educational_key(bits), None),
+                Err(CryptoError::KeyTooShort(_))
+            ));
         }
-        candidate.set_bit(0, true);
+        assert!(RsaCipher::new(&educational_key(512), None).is_ok());
+    }
 
-        if is_probable_prime(&candidate, MR_ROUNDS) {
-            return candidate;
+    #[test]
+    fn ladder_matches_modpow() {
+        let mut rng = OsRng;
+        for bits in [61u64, 128, 129, 521, 1024, 2048] {
+            let modulus = rng.gen_biguint(bits) | BigUint::one() | (BigUint::one() << (bits - 1));
+            for exponent_bits in [1, bits / 2, bits, bits + 64] {
+                let base = rng.gen_biguint(bits + 8);
+                let exponent = rng.gen_biguint(exponent_bits);
+                assert_eq!(
+                    montgomery_ladder(&base, &exponent, &modulus),
+                    Some(base.modpow(&exponent, &modulus))
+                );
+            }
         }
+        let even = BigUint::from(1u8) << 100u32;
+        assert_eq!(
+            montgomery_ladder(&BigUint::from(3u8), &BigUint::one(), &even),
+            None
+        );
     }
-}
 
-fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
-    if *n < BigUint::from(2u8) {
-        return false;
+    #[test]
+    fn factors_are_recovered_only_for_two_prime_keys() {
+        for prime_count in [2, 3] {
+            let policy = RsaKeyPolicy {
+                educational: true,
+                prime_count,
+                ..Default::default()
+            };
+            let pair = generate_keypair(512, policy, &mut PrimeSearchMonitor::default()).unwrap();
+            let recovered = recover_factors(&pair.public.n, &pair.public.e, &pair.private.d);
+            match prime_count {
+                2 => {
+                    let (p, q) = recovered.unwrap();
+                    assert_eq!(&p * &q, pair.public.n);
+                }
+                _ => assert!(recovered.is_none()),
+            }
+        }
     }
-    if *n == BigUint::from(2u8) || *n == BigUint::from(3u8) {
-        return true;
+
+    #[test]
+    fn private_operations_require_the_public_exponent() {
+        let dir = std::env::temp_dir().join(format!("rsa_public_exp_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let plain = dir.join("message.txt");
+        fs::write(&plain, "tekst jawny").unwrap();
+
+        let private: Value = serde_json::from_str(&educational_key(512)).unwrap();
+        assert!(private["e"].is_string());
+        let cipher = RsaCipher::new(&private.to_string(), None).unwrap();
+        let encrypted = cipher.encrypt(plain.to_str().unwrap()).unwrap();
+        let decrypted = cipher.decrypt(&encrypted).unwrap();
+        assert_eq!(fs::read_to_string(decrypted).unwrap(), "tekst jawny");
+
+        let without_e = json!({ "n": private["n"], "d": private["d"] }).to_string();
+        assert!(matches!(
+            RsaCipher::new(&without_e, None)
+                .unwrap()
+                .decrypt(&encrypted),
+            Err(CryptoError::InvalidKey(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
     }
-    if n.is_even() {
-        return false;
+
+    #[test]
+    fn block_capacity_keeps_framed_blocks_below_the_modulus() {
+        let modulus = |bits: u32| (BigUint::one() << (bits - 1)) + 1u8;
+        assert_eq!(block_capacity(&modulus(512)), Some(62));
+        assert_eq!(block_capacity(&modulus(513)), Some(63));
+        assert_eq!(block_capacity(&modulus(2048)), Some(254));
+        // Długość bloku musi się zmieścić w jednym bajcie
+        assert_eq!(block_capacity(&modulus(4096)), Some(255));
+        assert_eq!(block_capacity(&modulus(16)), None);
     }
 
-    let one = BigUint::one();
-    let n_minus_one = n - &one;
-    let mut d = n_minus_one.clone();
-    let mut s = 0u32;
-    while d.is_even() {
-        d >>= 1;
-        s += 1;
+    #[test]
+    fn blocks_round_trip_at_capacity_boundaries() {
+        let dir = std::env::temp_dir().join(format!("rsa_blocks_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cipher = RsaCipher::new(&educational_key(512), None).unwrap();
+        let capacity = 62;
+
+        for len in [
+            0,
+            1,
+            capacity - 1,
+            capacity,
+            capacity + 1,
+            2 * capacity,
+            2 * capacity + 1,
+        ] {
+            // Zera wiodące i bajty 0xff w każdym bloku
+            let data: Vec<u8> = (0..len)
+                .map(|i| match i % capacity {
+                    0 | 1 => 0,
+                    _ => 0xff - (i % 7) as u8,
+                })
+                .collect();
+            let plain = dir.join(format!("blocks_{}.bin", len));
+            fs::write(&plain, &data).unwrap();
+
+            let encrypted = cipher.encrypt(plain.to_str().unwrap()).unwrap();
+            let stored: RsaBlockCiphertext =
+                serde_json::from_str(&fs::read_to_string(&encrypted).unwrap()).unwrap();
+            assert_eq!(stored.block_size, capacity);
+            assert_eq!(stored.blocks.len(), len.div_ceil(capacity));
+
+            let decrypted = cipher.decrypt(&encrypted).unwrap();
+            assert_eq!(fs::read(decrypted).unwrap(), data, "{} bajtów", len);
+        }
+
+        let encrypted = cipher
+            .encrypt(dir.join("blocks_1.bin").to_str().unwrap())
+            .unwrap();
+        let mut stored: Value =
+            serde_json::from_str(&fs::read_to_string(&encrypted).unwrap()).unwrap();
+        stored["block_size"] = json!(capacity - 1);
+        fs::write(&encrypted, stored.to_string()).unwrap();
+        assert!(matches!(
+            cipher.decrypt(&encrypted),
+            Err(CryptoError::DecryptionError(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
     }
 
-    let mut rng = OsRng;
-    'witness: for _ in 0..rounds {
-        let a = rng.gen_biguint_range(&BigUint::from(2u8), &(n - &one));
-        let mut x = a.modpow(&d, n);
-        if x == one || x == n_minus_one {
-            continue 'witness;
-        }
-        for _ in 1..s {
-            x = x.modpow(&BigUint::from(2u8), n);
-            if x == n_minus_one {
-                continue 'witness;
+    #[test]
+    fn multi_prime_crt_matches_plain_modpow() {
+        let mut rng = OsRng;
+        for (bits, prime_count) in [(512, 2), (512, 3), (640, 4), (640, MAX_PRIME_COUNT)] {
+            let policy = RsaKeyPolicy {
+                educational: true,
+                prime_count,
+                ..Default::default()
+            };
+            let pair = generate_keypair(bits, policy, &mut PrimeSearchMonitor::default()).unwrap();
+            let (n, d, e) = (&pair.public.n, &pair.private.d, &pair.public.e);
+            let primes = pair.private.primes();
+            assert_eq!(primes.len(), prime_count);
+            assert_eq!(primes.iter().product::<BigUint>(), *n);
+
+            let crt = CrtKey::new(&primes, d).unwrap();
+            let mut bases = vec![
+                BigUint::zero(),
+                BigUint::one(),
+                n - 1u8,
+                primes[2 % prime_count].clone(),
+            ];
+            bases.extend((0..4).map(|_| rng.gen_biguint_below(n)));
+            for base in bases {
+                let expected = base.modpow(d, n);
+                assert_eq!(crt.exponentiate(&base), Some(expected.clone()));
+                assert_eq!(decrypt_block(&base, d, e, n, Some(&crt)).unwrap(), expected);
+                assert_eq!(decrypt_block(&base, d, e, n, None).unwrap(), expected);
+            }
+
+            // t_i z OtherPrimeInfos odwraca iloczyn poprzednich czynników
+            let infos = pair.private.other_prime_infos().unwrap();
+            assert_eq!(infos.len(), prime_count - 2);
+            let mut product = &primes[0] * &primes[1];
+            for info in infos {
+                assert!((&product * &info.coefficient % &info.prime).is_one());
+                assert_eq!(info.exponent, d % (&info.prime - 1u8));
+                product *= &info.prime;
             }
         }
-        return false;
     }
-    true
-}
 
-fn extended_gcd(a: BigInt, b: BigInt) -> (BigInt, BigInt, BigInt) {
-    if b.is_zero() {
-        (a.clone(), BigInt::one(), BigInt::zero())
-    } else {
-        let (g, x1, y1) = extended_gcd(b.clone(), a.clone() % b.clone());
-        let x = y1.clone();
-        let y = x1 - (a / b) * y1;
-        (g, x, y)
-    }
-}
+    #[test]
+    fn small_public_exponents_are_educational_only() {
+        let policy = |educational, public_exponent| RsaKeyPolicy {
+            educational,
+            public_exponent,
+            ..Default::default()
+        };
+        let mut monitor = PrimeSearchMonitor::default();
+        for e in [3, 17, 65_537] {
+            let pair = generate_keypair(512, policy(true, e), &mut monitor).unwrap();
+            assert_eq!(pair.public.e, BigUint::from(e));
+            let message = BigUint::from(0x1234_5678u32);
+            let ciphertext = message.modpow(&pair.public.e, &pair.public.n);
+            assert_eq!(ciphertext.modpow(&pair.private.d, &pair.public.n), message);
+        }
 
-fn modinv(a: &BigInt, modulus: &BigInt) -> Option<BigInt> {
-    let (g, x, _) = extended_gcd(a.clone(), modulus.clone());
-    if g != BigInt::one() {
-        return None;
-    }
-    let mut res = x % modulus;
-    if res < BigInt::zero() {
-        res += modulus;
+        for (educational, e) in [
+            (false, 3),
+            (false, 65_535),
+            (true, 4),
+            (true, 1),
+            (true, 1 << 40 | 1),
+        ] {
+            assert!(matches!(
+                policy(educational, e).check_public_exponent(32),
+                Err(CryptoError::InvalidKey(_))
+            ));
+        }
+        assert!(
+            policy(false, (1 << 16) + 1)
+                .check_public_exponent(2048)
+                .is_ok()
+        );
+        assert!(
+            policy(false, (1 << 16) - 1)
+                .check_public_exponent(2048)
+                .is_err()
+        );
     }
-    Some(res)
 }
@@ -0,0 +1,572 @@
+use crate::algorithms::number_theory;
+use crate::algorithms::primes::{self, PrimalityOptions};
+use crate::algorithms::rsa::RsaPublicKey;
+use crate::utils::logger::{LogLevel, log};
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use rand::rngs::OsRng;
+use serde_json::{Value, json};
+
+/// Liczba iteracji, po której obliczany jest NWD w metodzie rho (wariant Brenta)
+const RHO_BATCH: u64 = 128;
+
+/// Atak próbujący złamać klucz RSA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaAttack {
+    /// Wspólny czynnik z innym modułem w audytowanej partii (batch GCD)
+    SharedFactor,
+    /// Atak Wienera na mały eksponent prywatny (d < n^0.25 / 3)
+    Wiener,
+    /// Faktoryzacja Fermata – skuteczna, gdy p i q są blisko siebie
+    Fermat,
+    /// Metoda p−1 Pollarda – skuteczna, gdy p−1 ma tylko małe dzielniki
+    PollardPMinusOne,
+    /// Metoda rho Pollarda – skuteczna dla małych czynników
+    PollardRho,
+}
+
+impl RsaAttack {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::SharedFactor => "shared_factor",
+            Self::Wiener => "wiener",
+            Self::Fermat => "fermat",
+            Self::PollardPMinusOne => "pollard_p_minus_1",
+            Self::PollardRho => "pollard_rho",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaAttackOutcome {
+    /// Atak znalazł czynnik modułu
+    Broken,
+    /// Atak nie powiódł się w ramach limitów
+    Resisted,
+    /// Atak nie mógł zostać wykonany (np. partia zawiera jeden klucz)
+    Skipped,
+}
+
+impl RsaAttackOutcome {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Broken => "broken",
+            Self::Resisted => "resisted",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RsaAttackResult {
+    pub attack: RsaAttack,
+    pub outcome: RsaAttackOutcome,
+    pub message: String,
+    /// Znaleziony nietrywialny dzielnik modułu
+    pub factor: Option<BigUint>,
+}
+
+/// Limity pracy poszczególnych ataków; domyślne wartości łamią klucze
+/// edukacyjne w ułamku sekundy, a na kluczach 2048-bitowych kończą się po
+/// kilku sekundach
+#[derive(Debug, Clone, Copy)]
+pub struct RsaAuditLimits {
+    pub fermat_steps: u64,
+    pub rho_steps: u64,
+    /// Granica B1 gładkości p−1
+    pub p_minus_one_bound: u32,
+}
+
+impl Default for RsaAuditLimits {
+    fn default() -> Self {
+        Self {
+            fermat_steps: 100_000,
+            rho_steps: 200_000,
+            p_minus_one_bound: 100_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RsaKeyAudit {
+    pub key: RsaPublicKey,
+    pub results: Vec<RsaAttackResult>,
+}
+
+impl RsaKeyAudit {
+    pub fn is_broken(&self) -> bool {
+        self.results
+            .iter()
+            .any(|result| result.outcome == RsaAttackOutcome::Broken)
+    }
+
+    /// Czynniki (p, n/p) i eksponent prywatny odtworzone z pierwszego udanego ataku
+    pub fn recovered_key(&self) -> Option<(BigUint, BigUint, Option<BigUint>)> {
+        let factor = self
+            .results
+            .iter()
+            .find_map(|result| result.factor.as_ref())?;
+        let cofactor = &self.key.n / factor;
+        let private_exp = recover_private_exponent(&self.key, factor, &cofactor);
+        Some((factor.clone(), cofactor, private_exp))
+    }
+
+    fn to_json(&self, index: usize) -> Value {
+        let mut value = json!({
+            "index": index,
+            "bits": self.key.n.bits(),
+            "broken": self.is_broken(),
+            "attacks": self
+                .results
+                .iter()
+                .map(|result| json!({
+                    "attack": result.attack.id(),
+                    "status": result.outcome.id(),
+                    "message": result.message
+                }))
+                .collect::<Vec<_>>()
+        });
+        if let Some((p, q, d)) = self.recovered_key() {
+            value["factors"] = json!([p.to_str_radix(10), q.to_str_radix(10)]);
+            if let Some(d) = d {
+                value["d"] = json!(d.to_str_radix(10));
+            }
+        }
+        value
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RsaAuditReport {
+    pub keys: Vec<RsaKeyAudit>,
+}
+
+impl RsaAuditReport {
+    pub fn broken_count(&self) -> usize {
+        self.keys.iter().filter(|key| key.is_broken()).count()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "total": self.keys.len(),
+            "broken": self.broken_count(),
+            "keys": self
+                .keys
+                .iter()
+                .enumerate()
+                .map(|(index, key)| key.to_json(index))
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+/// Przeprowadza wszystkie ataki na każdym kluczu z partii. Wspólne czynniki
+/// wyszukiwane są między wszystkimi modułami naraz (drzewo iloczynów i reszt).
+pub fn audit(keys: &[RsaPublicKey], limits: RsaAuditLimits) -> RsaAuditReport {
+    let moduli: Vec<BigUint> = keys.iter().map(|key| key.n.clone()).collect();
+    let shared = batch_gcd(&moduli);
+
+    let audits = keys
+        .iter()
+        .zip(shared)
+        .enumerate()
+        .map(|(index, (key, shared))| {
+            log(
+                LogLevel::INFO,
+                "RSA-Audit",
+                format!("Audyt klucza nr {} ({} bitów)...", index + 1, key.n.bits()),
+            );
+            let mut results = vec![shared_factor_result(keys.len(), &key.n, shared)];
+            results.push(wiener(key));
+            results.push(fermat(&key.n, limits.fermat_steps));
+            results.push(pollard_p_minus_one(&key.n, limits.p_minus_one_bound));
+            results.push(pollard_rho(&key.n, limits.rho_steps));
+
+            let audit = RsaKeyAudit {
+                key: key.clone(),
+                results,
+            };
+            if audit.is_broken() {
+                let methods: Vec<&str> = audit
+                    .results
+                    .iter()
+                    .filter(|result| result.outcome == RsaAttackOutcome::Broken)
+                    .map(|result| result.attack.id())
+                    .collect();
+                log(
+                    LogLevel::WARN,
+                    "RSA-Audit",
+                    format!(
+                        "Klucz nr {} został złamany: {}",
+                        index + 1,
+                        methods.join(", ")
+                    ),
+                );
+            }
+            audit
+        })
+        .collect();
+
+    RsaAuditReport { keys: audits }
+}
+
+fn broken(attack: RsaAttack, factor: BigUint, message: String) -> RsaAttackResult {
+    RsaAttackResult {
+        attack,
+        outcome: RsaAttackOutcome::Broken,
+        message,
+        factor: Some(factor),
+    }
+}
+
+fn resisted(attack: RsaAttack, message: impl Into<String>) -> RsaAttackResult {
+    RsaAttackResult {
+        attack,
+        outcome: RsaAttackOutcome::Resisted,
+        message: message.into(),
+        factor: None,
+    }
+}
+
+fn skipped(attack: RsaAttack, message: impl Into<String>) -> RsaAttackResult {
+    RsaAttackResult {
+        attack,
+        outcome: RsaAttackOutcome::Skipped,
+        message: message.into(),
+        factor: None,
+    }
+}
+
+/// Zwraca dzielnik, jeśli `candidate` jest nietrywialnym dzielnikiem `n`
+fn nontrivial(candidate: BigUint, n: &BigUint) -> Option<BigUint> {
+    (candidate > BigUint::one() && candidate < *n && (n % &candidate).is_zero())
+        .then_some(candidate)
+}
+
+/// Eksponent prywatny odtworzony z czynników; wymaga, by oba były pierwsze
+fn recover_private_exponent(key: &RsaPublicKey, p: &BigUint, q: &BigUint) -> Option<BigUint> {
+    let options = PrimalityOptions::default();
+    if !primes::is_prime(p, options) || !primes::is_prime(q, options) {
+        return None;
+    }
+    let one = BigUint::one();
+    let lambda = (p - &one).lcm(&(q - &one));
+    number_theory::mod_inverse(&key.e, &lambda)
+}
+
+/// Batch GCD Bernsteina: dla każdego n_i oblicza NWD(n_i, iloczyn pozostałych)
+/// przez drzewo iloczynów i drzewo reszt modulo n_i²
+fn batch_gcd(moduli: &[BigUint]) -> Vec<Option<BigUint>> {
+    if moduli.len() < 2 {
+        return vec![None; moduli.len()];
+    }
+
+    let mut levels = vec![moduli.to_vec()];
+    while levels.last().is_some_and(|level| level.len() > 1) {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| pair.iter().fold(BigUint::one(), |acc, value| acc * value))
+            .collect();
+        levels.push(next);
+    }
+
+    let mut remainders = levels.pop().unwrap_or_default();
+    while let Some(level) = levels.pop() {
+        remainders = level
+            .iter()
+            .enumerate()
+            .map(|(index, node)| &remainders[index / 2] % (node * node))
+            .collect();
+    }
+
+    moduli
+        .iter()
+        .zip(remainders)
+        .map(|(n, remainder)| {
+            let gcd = (remainder / n).gcd(n);
+            if gcd.is_one() { None } else { Some(gcd) }
+        })
+        .collect()
+}
+
+fn shared_factor_result(batch_size: usize, n: &BigUint, gcd: Option<BigUint>) -> RsaAttackResult {
+    let attack = RsaAttack::SharedFactor;
+    if batch_size < 2 {
+        return skipped(
+            attack,
+            "Wyszukiwanie wspólnych czynników wymaga co najmniej dwóch kluczy",
+        );
+    }
+    match gcd {
+        None => resisted(attack, "Moduł nie ma wspólnych czynników z innymi kluczami"),
+        Some(gcd) if gcd == *n => resisted(
+            attack,
+            "Wszystkie czynniki modułu występują w innych kluczach (np. ten sam moduł użyty dwukrotnie) – NWD nie rozdziela czynników",
+        ),
+        Some(gcd) => broken(
+            attack,
+            gcd,
+            "Moduł ma wspólny czynnik pierwszy z innym kluczem w partii (słaby generator liczb losowych)".to_string(),
+        ),
+    }
+}
+
+/// Rozwinięcie e/n w ułamek łańcuchowy; każdy redukt k/d jest kandydatem na
+/// (k, d) spełniające e·d = 1 + k·φ(n)
+fn wiener(key: &RsaPublicKey) -> RsaAttackResult {
+    let attack = RsaAttack::Wiener;
+    let n = &key.n;
+    let one = BigUint::one();
+
+    let (mut numerator, mut denominator) = (key.e.clone(), n.clone());
+    // Poprzednie i bieżące redukty h/k, gdzie h ≈ k (liczba całkowita z równania), k ≈ d
+    let (mut h_prev, mut h) = (BigUint::zero(), one.clone());
+    let (mut k_prev, mut k) = (one.clone(), BigUint::zero());
+    while !denominator.is_zero() {
+        let (quotient, remainder) = numerator.div_rem(&denominator);
+        let h_next = &quotient * &h + &h_prev;
+        let k_next = &quotient * &k + &k_prev;
+        (h_prev, h) = (h, h_next);
+        (k_prev, k) = (k, k_next);
+        (numerator, denominator) = (denominator, remainder);
+
+        // h/k jest reduktem e/n, więc k to kandydat na d, a h – na współczynnik
+        let (candidate_k, candidate_d) = (&h, &k);
+        if candidate_k.is_zero() {
+            continue;
+        }
+        let ed_minus_one = &key.e * candidate_d - &one;
+        if !(&ed_minus_one % candidate_k).is_zero() {
+            continue;
+        }
+        let phi = &ed_minus_one / candidate_k;
+        if phi >= *n {
+            continue;
+        }
+        // p i q są pierwiastkami x² − (n − φ + 1)x + n
+        let sum = n - &phi + &one;
+        let square = &sum * &sum;
+        let four_n = n << 2;
+        if square < four_n {
+            continue;
+        }
+        if let Some(root) = number_theory::exact_sqrt(&(square - four_n))
+            && let Some(p) = nontrivial((&sum + &root) >> 1, n)
+        {
+            return broken(
+                attack,
+                p,
+                format!(
+                    "Eksponent prywatny jest zbyt mały (d = {}, {} bitów) – odtworzony z ułamka łańcuchowego e/n",
+                    candidate_d,
+                    candidate_d.bits()
+                ),
+            );
+        }
+    }
+    resisted(
+        attack,
+        "Żaden redukt ułamka e/n nie prowadzi do faktoryzacji – d nie jest małe",
+    )
+}
+
+/// Szuka a, dla którego a² − n jest kwadratem b²; wtedy n = (a − b)(a + b)
+fn fermat(n: &BigUint, steps: u64) -> RsaAttackResult {
+    let attack = RsaAttack::Fermat;
+    if n.is_even() {
+        return broken(
+            attack,
+            BigUint::from(2u8),
+            "Moduł jest parzysty".to_string(),
+        );
+    }
+
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1u8;
+    }
+    let mut b_squared = &a * &a - n;
+    for step in 0..steps {
+        if let Some(b) = number_theory::exact_sqrt(&b_squared)
+            && let Some(p) = nontrivial(&a - &b, n)
+        {
+            return broken(
+                attack,
+                p,
+                format!(
+                    "Czynniki są zbyt blisko siebie (|p − q| = {}) – faktoryzacja po {} krokach",
+                    &b << 1,
+                    step + 1
+                ),
+            );
+        }
+        // (a + 1)² − n = a² − n + 2a + 1
+        b_squared += (&a << 1) + 1u8;
+        a += 1u8;
+    }
+    resisted(
+        attack,
+        format!(
+            "Brak faktoryzacji po {} krokach – czynniki są odległe",
+            steps
+        ),
+    )
+}
+
+/// Metoda p−1: a^M mod n dla M będącego iloczynem potęg liczb pierwszych ≤ B1.
+/// Jeśli p−1 dzieli M, to NWD(a^M − 1, n) ujawnia p.
+fn pollard_p_minus_one(n: &BigUint, bound: u32) -> RsaAttackResult {
+    let attack = RsaAttack::PollardPMinusOne;
+    let one = BigUint::one();
+    let bound_u64 = u64::from(bound);
+    let mut a = BigUint::from(2u8);
+
+    let small_primes = primes::small_primes_below(bound as usize + 1);
+    for chunk in small_primes.chunks(256) {
+        let saved = a.clone();
+        for &prime in chunk {
+            // Największa potęga liczby pierwszej nie większa od B1
+            let mut power = u64::from(prime);
+            while power * u64::from(prime) <= bound_u64 {
+                power *= u64::from(prime);
+            }
+            a = a.modpow(&BigUint::from(power), n);
+        }
+
+        let gcd = (&a + n - &one).gcd(n);
+        if gcd.is_one() {
+            continue;
+        }
+        // Wszystkie czynniki wpadły naraz – powtarzamy fragment po jednej liczbie
+        let factor = if gcd == *n {
+            retry_p_minus_one(n, saved, chunk, bound_u64)
+        } else {
+            Some(gcd)
+        };
+        return match factor {
+            Some(p) => broken(
+                attack,
+                p.clone(),
+                format!(
+                    "p − 1 ma wyłącznie dzielniki nie większe niż {} (czynnik {} bitów)",
+                    bound,
+                    p.bits()
+                ),
+            ),
+            None => resisted(
+                attack,
+                "p − 1 i q − 1 są jednocześnie gładkie – NWD nie rozdzielił czynników",
+            ),
+        };
+    }
+    resisted(attack, format!("Żadne z p − 1 nie jest {}-gładkie", bound))
+}
+
+fn retry_p_minus_one(n: &BigUint, mut a: BigUint, chunk: &[u32], bound: u64) -> Option<BigUint> {
+    let one = BigUint::one();
+    for &prime in chunk {
+        let mut power = u64::from(prime);
+        loop {
+            a = a.modpow(&BigUint::from(prime), n);
+            let gcd = (&a + n - &one).gcd(n);
+            if let Some(p) = nontrivial(gcd.clone(), n) {
+                return Some(p);
+            }
+            if gcd == *n || power * u64::from(prime) > bound {
+                break;
+            }
+            power *= u64::from(prime);
+        }
+        if (&a + n - &one).gcd(n) == *n {
+            return None;
+        }
+    }
+    None
+}
+
+/// Metoda rho Pollarda w wariancie Brenta z iloczynem różnic liczonym partiami
+fn pollard_rho(n: &BigUint, steps: u64) -> RsaAttackResult {
+    let attack = RsaAttack::PollardRho;
+    if n.is_even() {
+        return broken(
+            attack,
+            BigUint::from(2u8),
+            "Moduł jest parzysty".to_string(),
+        );
+    }
+    let small = n.to_u64().filter(|&value| value < 4);
+    if small.is_some() {
+        return resisted(attack, "Moduł jest zbyt mały do faktoryzacji");
+    }
+
+    let mut rng = OsRng;
+    let three = BigUint::from(3u8);
+    let mut total = 0u64;
+    // Kolejne stałe c próbujemy, gdy cykl obejmie naraz wszystkie czynniki;
+    // limit kroków jest wspólny dla wszystkich prób
+    'attempt: while total < steps {
+        let c = rng.gen_biguint_range(&BigUint::one(), &(n - &three));
+        let step = |x: &BigUint| (x * x + &c) % n;
+        let mut y = rng.gen_biguint_range(&BigUint::zero(), n);
+        let mut power = 1u64;
+
+        while total < steps {
+            let x = y.clone();
+            for _ in 0..power {
+                y = step(&y);
+            }
+            total += power;
+            let mut done = 0u64;
+            while done < power && total < steps {
+                let saved = y.clone();
+                let batch = RHO_BATCH.min(power - done);
+                let mut product = BigUint::one();
+                for _ in 0..batch {
+                    y = step(&y);
+                    let diff = if x > y { &x - &y } else { &y - &x };
+                    product = (product * diff) % n;
+                }
+                done += batch;
+                total += batch;
+
+                let gcd = product.gcd(n);
+                if gcd.is_one() {
+                    continue;
+                }
+                if gcd == *n {
+                    // Powrót do początku partii i sprawdzanie krok po kroku
+                    let mut y = saved;
+                    for _ in 0..batch {
+                        y = step(&y);
+                        let diff = if x > y { &x - &y } else { &y - &x };
+                        if let Some(p) = nontrivial(diff.gcd(n), n) {
+                            return rho_broken(p, total);
+                        }
+                    }
+                    continue 'attempt;
+                }
+                if let Some(p) = nontrivial(gcd, n) {
+                    return rho_broken(p, total);
+                }
+            }
+            power *= 2;
+        }
+    }
+    resisted(
+        attack,
+        format!("Brak faktoryzacji po {} krokach – czynniki są duże", steps),
+    )
+}
+
+fn rho_broken(p: BigUint, steps: u64) -> RsaAttackResult {
+    let bits = p.bits();
+    broken(
+        RsaAttack::PollardRho,
+        p,
+        format!(
+            "Znaleziono czynnik {}-bitowy po około {} krokach",
+            bits, steps
+        ),
+    )
+}
@@ -0,0 +1,273 @@
+use crate::algorithms::primes::{self, PrimalityOptions};
+use crate::algorithms::rsa::RsaKeyPolicy;
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::One;
+use rand::rngs::OsRng;
+use serde_json::{Value, json};
+
+/// Pojedynczy test wykonywany przy walidacji klucza RSA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaKeyCheck {
+    ModulusSize,
+    ModulusParity,
+    PublicExponent,
+    PrivateExponent,
+    Factors,
+    ExponentInverse,
+    Pairwise,
+}
+
+impl RsaKeyCheck {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::ModulusSize => "modulus_size",
+            Self::ModulusParity => "modulus_parity",
+            Self::PublicExponent => "public_exponent",
+            Self::PrivateExponent => "private_exponent",
+            Self::Factors => "factors",
+            Self::ExponentInverse => "exponent_inverse",
+            Self::Pairwise => "pairwise",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaCheckStatus {
+    Passed,
+    Failed,
+    /// Test wymaga składowych, których klucz nie zawiera
+    Skipped,
+}
+
+impl RsaCheckStatus {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RsaCheckResult {
+    pub check: RsaKeyCheck,
+    pub status: RsaCheckStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RsaValidationReport {
+    pub checks: Vec<RsaCheckResult>,
+}
+
+impl RsaValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &RsaCheckResult> {
+        self.checks
+            .iter()
+            .filter(|result| result.status == RsaCheckStatus::Failed)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "valid": self.is_valid(),
+            "checks": self
+                .checks
+                .iter()
+                .map(|result| json!({
+                    "check": result.check.id(),
+                    "status": result.status.id(),
+                    "message": result.message
+                }))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    fn record(&mut self, check: RsaKeyCheck, status: RsaCheckStatus, message: impl Into<String>) {
+        self.checks.push(RsaCheckResult {
+            check,
+            status,
+            message: message.into(),
+        });
+    }
+
+    fn pass(&mut self, check: RsaKeyCheck, message: impl Into<String>) {
+        self.record(check, RsaCheckStatus::Passed, message);
+    }
+
+    fn fail(&mut self, check: RsaKeyCheck, message: impl Into<String>) {
+        self.record(check, RsaCheckStatus::Failed, message);
+    }
+
+    fn skip(&mut self, check: RsaKeyCheck, message: impl Into<String>) {
+        self.record(check, RsaCheckStatus::Skipped, message);
+    }
+}
+
+/// Składowe klucza RSA poddawane walidacji; brakujące pola powodują pominięcie
+/// zależnych od nich testów
+pub struct RsaKeyComponents<'a> {
+    pub n: &'a BigUint,
+    pub e: Option<&'a BigUint>,
+    pub d: Option<&'a BigUint>,
+    pub primes: &'a [BigUint],
+}
+
+/// Sprawdza spójność klucza RSA i zwraca wynik każdego testu osobno
+pub fn validate(key: &RsaKeyComponents, policy: RsaKeyPolicy) -> RsaValidationReport {
+    let mut report = RsaValidationReport::default();
+    let one = BigUint::one();
+    let n = key.n;
+
+    let bits = usize::try_from(n.bits()).unwrap_or(usize::MAX);
+    let min_bits = policy.min_modulus_bits();
+    if bits >= min_bits {
+        report.pass(RsaKeyCheck::ModulusSize, format!("Moduł ma {} bitów", bits));
+    } else {
+        report.fail(
+            RsaKeyCheck::ModulusSize,
+            format!(
+                "Moduł ma {} bitów, wymagane jest co najmniej {}",
+                bits, min_bits
+            ),
+        );
+    }
+
+    if n.is_odd() && *n > one {
+        report.pass(RsaKeyCheck::ModulusParity, "Moduł jest nieparzysty");
+    } else {
+        report.fail(
+            RsaKeyCheck::ModulusParity,
+            "Moduł RSA musi być nieparzystą liczbą większą od 1",
+        );
+    }
+
+    match key.e {
+        None => report.skip(
+            RsaKeyCheck::PublicExponent,
+            "Brak eksponenta publicznego 'e'",
+        ),
+        Some(e) if *e < BigUint::from(3u8) || e >= n => report.fail(
+            RsaKeyCheck::PublicExponent,
+            "Eksponent publiczny musi spełniać 3 ≤ e < n",
+        ),
+        Some(e) if e.is_even() => report.fail(
+            RsaKeyCheck::PublicExponent,
+            "Eksponent publiczny musi być nieparzysty",
+        ),
+        Some(e) => report.pass(RsaKeyCheck::PublicExponent, format!("e = {}", e)),
+    }
+
+    match key.d {
+        None => report.skip(
+            RsaKeyCheck::PrivateExponent,
+            "Brak eksponenta prywatnego 'd'",
+        ),
+        Some(d) if *d <= one || d >= n => report.fail(
+            RsaKeyCheck::PrivateExponent,
+            "Eksponent prywatny musi spełniać 1 < d < n",
+        ),
+        Some(_) => report.pass(
+            RsaKeyCheck::PrivateExponent,
+            "Eksponent prywatny mieści się w zakresie",
+        ),
+    }
+
+    let factors_valid = check_factors(key, &mut report);
+
+    match (key.e, key.d, factors_valid) {
+        (Some(e), Some(d), true) => {
+            let lambda = key
+                .primes
+                .iter()
+                .fold(one.clone(), |acc, p| acc.lcm(&(p - &one)));
+            if (e * d) % &lambda == one {
+                report.pass(RsaKeyCheck::ExponentInverse, "e·d ≡ 1 (mod λ(n))");
+            } else {
+                report.fail(
+                    RsaKeyCheck::ExponentInverse,
+                    "Eksponent prywatny nie jest odwrotnością e modulo λ(n)",
+                );
+            }
+        }
+        _ => report.skip(
+            RsaKeyCheck::ExponentInverse,
+            "Test wymaga e, d oraz poprawnych czynników modułu",
+        ),
+    }
+
+    match (key.e, key.d) {
+        (Some(e), Some(d)) if *n > BigUint::from(3u8) && n.is_odd() => {
+            let message = OsRng.gen_biguint_range(&BigUint::from(2u8), &(n - &one));
+            if message.modpow(e, n).modpow(d, n) == message {
+                report.pass(
+                    RsaKeyCheck::Pairwise,
+                    "Testowe szyfrowanie i deszyfrowanie zwróciło tę samą wiadomość",
+                );
+            } else {
+                report.fail(
+                    RsaKeyCheck::Pairwise,
+                    "Testowe szyfrowanie i deszyfrowanie zwróciło inną wiadomość – e i d nie tworzą pary",
+                );
+            }
+        }
+        (Some(_), Some(_)) => report.skip(RsaKeyCheck::Pairwise, "Moduł jest zbyt mały do testu"),
+        _ => report.skip(
+            RsaKeyCheck::Pairwise,
+            "Test wymaga obu eksponentów 'e' i 'd'",
+        ),
+    }
+
+    report
+}
+
+/// Zwraca `true`, gdy czynniki są obecne i poprawne
+fn check_factors(key: &RsaKeyComponents, report: &mut RsaValidationReport) -> bool {
+    if key.primes.is_empty() {
+        report.skip(RsaKeyCheck::Factors, "Klucz nie zawiera czynników modułu");
+        return false;
+    }
+
+    let product = key.primes.iter().fold(BigUint::one(), |acc, p| acc * p);
+    if product != *key.n {
+        report.fail(
+            RsaKeyCheck::Factors,
+            "Iloczyn czynników nie jest równy modułowi",
+        );
+        return false;
+    }
+    let distinct = key
+        .primes
+        .iter()
+        .enumerate()
+        .all(|(i, p)| key.primes[..i].iter().all(|other| other != p));
+    if !distinct {
+        report.fail(RsaKeyCheck::Factors, "Czynniki modułu muszą być różne");
+        return false;
+    }
+    if let Some(index) = key
+        .primes
+        .iter()
+        .position(|p| !primes::is_prime(p, PrimalityOptions::default()))
+    {
+        report.fail(
+            RsaKeyCheck::Factors,
+            format!("Czynnik nr {} nie jest liczbą pierwszą", index + 1),
+        );
+        return false;
+    }
+
+    report.pass(
+        RsaKeyCheck::Factors,
+        format!(
+            "Iloczyn czynników pierwszych (liczba: {}) jest równy modułowi",
+            key.primes.len()
+        ),
+    );
+    true
+}
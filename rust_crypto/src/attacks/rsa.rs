@@ -0,0 +1,364 @@
+use crate::algorithms::number_theory;
+use crate::algorithms::rsa::{BLOCK_FORMAT, RsaBlockCiphertext, RsaPublicKey};
+use crate::error::CryptoError;
+use crate::utils::logger::{LogLevel, log};
+use base64::{Engine as _, engine::general_purpose};
+use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive};
+use rand::rngs::OsRng;
+use serde_json::{Value, json};
+
+/// Wiadomość odzyskana atakiem na RSA bez dopełnienia: surowe bloki m_i
+/// oraz zdekodowany tekst jawny
+#[derive(Debug, Clone)]
+pub struct RecoveredMessage {
+    pub blocks: Vec<BigUint>,
+}
+
+impl RecoveredMessage {
+    /// Dane bloków bez bajtu długości; blok bez poprawnej ramki (starszy format
+    /// jednoblokowy) jest zwracany w całości
+    pub fn plaintext(&self) -> Vec<u8> {
+        self.blocks
+            .iter()
+            .flat_map(|block| {
+                let framed = block.to_bytes_be();
+                match framed.split_first() {
+                    Some((&len, data)) if len as usize == data.len() => data.to_vec(),
+                    _ => framed,
+                }
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let plaintext = self.plaintext();
+        json!({
+            "blocks": self.blocks.iter().map(|block| format!("{:x}", block)).collect::<Vec<_>>(),
+            "base64": general_purpose::STANDARD.encode(&plaintext),
+            "text": String::from_utf8(plaintext).ok()
+        })
+    }
+}
+
+/// Bloki szyfrogramu z pliku utworzonego przez `RsaCipher` (JSON z blokami
+/// albo pojedyncza liczba szesnastkowa)
+pub fn parse_ciphertext(contents: &str) -> Result<Vec<BigUint>, CryptoError> {
+    let trimmed = contents.trim();
+    let blocks = if trimmed.starts_with('{') {
+        let ciphertext: RsaBlockCiphertext = serde_json::from_str(trimmed).map_err(|err| {
+            CryptoError::InvalidFormat(format!("Nieprawidłowa struktura szyfrogramu RSA: {}", err))
+        })?;
+        if ciphertext.format != BLOCK_FORMAT {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Nieobsługiwany format szyfrogramu: {}",
+                ciphertext.format
+            )));
+        }
+        ciphertext.blocks
+    } else {
+        vec![trimmed.chars().filter(|c| !c.is_whitespace()).collect()]
+    };
+
+    blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| {
+            BigUint::parse_bytes(block.trim().as_bytes(), 16).ok_or_else(|| {
+                CryptoError::InvalidFormat(format!(
+                    "Blok nr {} szyfrogramu nie jest liczbą szesnastkową",
+                    index + 1
+                ))
+            })
+        })
+        .collect()
+}
+
+fn small_exponent(key: &RsaPublicKey) -> Result<u32, CryptoError> {
+    key.e.to_u32().ok_or_else(|| {
+        CryptoError::InvalidKey(format!(
+            "Eksponent publiczny e = {} jest zbyt duży dla tego ataku",
+            key.e
+        ))
+    })
+}
+
+/// Atak Håstada: ta sama wiadomość zaszyfrowana przy tym samym małym `e` dla
+/// co najmniej `e` odbiorców. Z CRT otrzymujemy m^e mod ∏n_i, a ponieważ
+/// m^e < ∏n_i, wystarczy obliczyć całkowity pierwiastek stopnia e.
+pub fn hastad_broadcast(
+    targets: &[(RsaPublicKey, Vec<BigUint>)],
+) -> Result<RecoveredMessage, CryptoError> {
+    let (first_key, first_blocks) = targets.first().ok_or_else(|| {
+        CryptoError::InvalidKey("Atak Håstada wymaga co najmniej jednego klucza".to_string())
+    })?;
+    let e = small_exponent(first_key)?;
+    if targets.iter().any(|(key, _)| key.e != first_key.e) {
+        return Err(CryptoError::InvalidKey(
+            "Wszystkie klucze muszą mieć ten sam eksponent publiczny".to_string(),
+        ));
+    }
+    if targets.len() < e as usize {
+        return Err(CryptoError::InvalidKey(format!(
+            "Dla e = {} potrzeba co najmniej {} szyfrogramów tej samej wiadomości (podano {})",
+            e,
+            e,
+            targets.len()
+        )));
+    }
+    if targets
+        .iter()
+        .any(|(_, blocks)| blocks.len() != first_blocks.len())
+    {
+        return Err(CryptoError::InvalidFormat(
+            "Szyfrogramy mają różną liczbę bloków – klucze muszą mieć moduły tej samej długości"
+                .to_string(),
+        ));
+    }
+
+    let used = &targets[..e as usize];
+    for (i, (key, _)) in used.iter().enumerate() {
+        for (other, _) in &used[i + 1..] {
+            if !key.n.gcd(&other.n).is_one() {
+                return Err(CryptoError::InvalidKey(
+                    "Moduły mają wspólny czynnik – taki klucz łamie bezpośrednio audyt kluczy"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    log(
+        LogLevel::INFO,
+        "RSA-Attack",
+        format!(
+            "Atak Håstada: e = {}, {} bloków, łączenie {} szyfrogramów przez CRT",
+            e,
+            first_blocks.len(),
+            used.len()
+        ),
+    );
+    let blocks = (0..first_blocks.len())
+        .map(|index| {
+            number_theory::crt(used.iter().map(|(key, blocks)| (&blocks[index], &key.n)))
+                .and_then(|power| number_theory::exact_root(&power, e))
+                .ok_or_else(|| {
+                    CryptoError::DecryptionError(format!(
+                        "Blok nr {}: wynik CRT nie jest pełną potęgą stopnia {} – szyfrogramy nie dotyczą tej samej wiadomości",
+                        index + 1,
+                        e
+                    ))
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(RecoveredMessage { blocks })
+}
+
+/// Atak na wspólny moduł: ta sama wiadomość zaszyfrowana kluczami (n, e1)
+/// i (n, e2) przy NWD(e1, e2) = 1. Z rozszerzonego algorytmu Euklidesa
+/// a·e1 + b·e2 = 1, więc m = c1^a · c2^b mod n.
+pub fn common_modulus(
+    first: (&RsaPublicKey, &[BigUint]),
+    second: (&RsaPublicKey, &[BigUint]),
+) -> Result<RecoveredMessage, CryptoError> {
+    let ((key1, blocks1), (key2, blocks2)) = (first, second);
+    if key1.n != key2.n {
+        return Err(CryptoError::InvalidKey(
+            "Klucze muszą mieć ten sam moduł n".to_string(),
+        ));
+    }
+    if blocks1.len() != blocks2.len() {
+        return Err(CryptoError::InvalidFormat(
+            "Szyfrogramy mają różną liczbę bloków".to_string(),
+        ));
+    }
+
+    let to_signed = |value: &BigUint| BigInt::from_biguint(Sign::Plus, value.clone());
+    let (gcd, a, b) = number_theory::extended_gcd(&to_signed(&key1.e), &to_signed(&key2.e));
+    if !gcd.is_one() {
+        return Err(CryptoError::InvalidKey(format!(
+            "Eksponenty publiczne muszą być względnie pierwsze (NWD = {})",
+            gcd
+        )));
+    }
+    log(
+        LogLevel::INFO,
+        "RSA-Attack",
+        format!(
+            "Atak na wspólny moduł: {}·{} + {}·{} = 1",
+            a, key1.e, b, key2.e
+        ),
+    );
+
+    let n = &key1.n;
+    // c^x dla ujemnego x to (c^-1)^|x|
+    let power = |c: &BigUint, exponent: &BigInt| -> Option<BigUint> {
+        let magnitude = exponent.abs().to_biguint()?;
+        let base = if exponent.is_negative() {
+            number_theory::mod_inverse(c, n)?
+        } else {
+            c.clone()
+        };
+        Some(base.modpow(&magnitude, n))
+    };
+    let blocks = blocks1
+        .iter()
+        .zip(blocks2)
+        .enumerate()
+        .map(|(index, (c1, c2))| {
+            power(c1, &a)
+                .zip(power(c2, &b))
+                .map(|(p1, p2)| p1 * p2 % n)
+                .ok_or_else(|| {
+                    CryptoError::DecryptionError(format!(
+                        "Blok nr {} nie jest odwracalny modulo n",
+                        index + 1
+                    ))
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(RecoveredMessage { blocks })
+}
+
+/// Odzyskanie krótkiej wiadomości: gdy m^e < n, redukcja modulo n nie
+/// zachodzi i m jest zwykłym pierwiastkiem stopnia e z szyfrogramu
+pub fn small_message_root(
+    key: &RsaPublicKey,
+    blocks: &[BigUint],
+) -> Result<RecoveredMessage, CryptoError> {
+    let e = small_exponent(key)?;
+    log(
+        LogLevel::INFO,
+        "RSA-Attack",
+        format!(
+            "Pierwiastek stopnia {} z {} bloków szyfrogramu",
+            e,
+            blocks.len()
+        ),
+    );
+    let blocks = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| {
+            number_theory::exact_root(block, e).ok_or_else(|| {
+                CryptoError::DecryptionError(format!(
+                    "Blok nr {} nie jest pełną potęgą stopnia {} – wiadomość jest za długa (m^e ≥ n)",
+                    index + 1,
+                    e
+                ))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(RecoveredMessage { blocks })
+}
+
+/// Szyfrogram zmodyfikowany w ataku z wybranym szyfrogramem: c' = c · r^e mod n
+#[derive(Debug, Clone)]
+pub struct BlindedCiphertext {
+    pub blocks: Vec<BigUint>,
+    pub factors: Vec<BigUint>,
+}
+
+/// Pierwszy krok ataku z wybranym szyfrogramem: mnoży każdy blok przez r^e,
+/// tak że odszyfrowany wynik jest równy m · r (homomorfizm multiplikatywny)
+pub fn blind_ciphertext(key: &RsaPublicKey, blocks: &[BigUint]) -> BlindedCiphertext {
+    let mut rng = OsRng;
+    let two = BigUint::from(2u8);
+    let (blocks, factors) = blocks
+        .iter()
+        .map(|block| {
+            loop {
+                let factor = rng.gen_biguint_range(&two, &key.n);
+                if factor.gcd(&key.n).is_one() {
+                    break (block * factor.modpow(&key.e, &key.n) % &key.n, factor);
+                }
+            }
+        })
+        .unzip();
+    BlindedCiphertext { blocks, factors }
+}
+
+/// Drugi krok: z odpowiedzi wyroczni m' = m · r oblicza m = m' · r^-1 mod n
+pub fn unblind_plaintext(
+    key: &RsaPublicKey,
+    blinded: &BlindedCiphertext,
+    oracle_output: &[BigUint],
+) -> Result<RecoveredMessage, CryptoError> {
+    if oracle_output.len() != blinded.factors.len() {
+        return Err(CryptoError::InvalidFormat(
+            "Odpowiedź wyroczni ma inną liczbę bloków niż zmodyfikowany szyfrogram".to_string(),
+        ));
+    }
+    let blocks = oracle_output
+        .iter()
+        .zip(&blinded.factors)
+        .map(|(output, factor)| {
+            number_theory::mod_inverse(factor, &key.n)
+                .map(|inverse| output * inverse % &key.n)
+                .ok_or_else(|| {
+                    CryptoError::DecryptionError(
+                        "Czynnik r nie jest odwracalny modulo n".to_string(),
+                    )
+                })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(RecoveredMessage { blocks })
+}
+
+/// Przebieg ataku z wybranym szyfrogramem wraz z odpowiedziami wyroczni
+#[derive(Debug, Clone)]
+pub struct ChosenCiphertextResult {
+    pub blinded: BlindedCiphertext,
+    pub oracle_output: Vec<BigUint>,
+    pub recovered: RecoveredMessage,
+}
+
+impl ChosenCiphertextResult {
+    pub fn to_json(&self) -> Value {
+        let hex = |values: &[BigUint]| {
+            values
+                .iter()
+                .map(|value| format!("{:x}", value))
+                .collect::<Vec<_>>()
+        };
+        json!({
+            "forged": hex(&self.blinded.blocks),
+            "factors": hex(&self.blinded.factors),
+            "oracle": hex(&self.oracle_output),
+            "recovered": self.recovered.to_json()
+        })
+    }
+}
+
+/// Atak z wybranym szyfrogramem: wyrocznia odszyfrowuje dowolne bloki poza
+/// przechwyconymi, więc pytamy ją o c · r^e i dzielimy odpowiedź przez r
+pub fn chosen_ciphertext<F>(
+    key: &RsaPublicKey,
+    blocks: &[BigUint],
+    mut oracle: F,
+) -> Result<ChosenCiphertextResult, CryptoError>
+where
+    F: FnMut(&BigUint) -> Result<BigUint, CryptoError>,
+{
+    let blinded = blind_ciphertext(key, blocks);
+    log(
+        LogLevel::INFO,
+        "RSA-Attack",
+        format!(
+            "Atak z wybranym szyfrogramem: wysyłanie {} zmodyfikowanych bloków do wyroczni",
+            blinded.blocks.len()
+        ),
+    );
+    let oracle_output = blinded
+        .blocks
+        .iter()
+        .map(&mut oracle)
+        .collect::<Result<Vec<_>, _>>()?;
+    let recovered = unblind_plaintext(key, &blinded, &oracle_output)?;
+    Ok(ChosenCiphertextResult {
+        blinded,
+        oracle_output,
+        recovered,
+    })
+}
@@ -12,6 +12,7 @@ pub enum CryptoError {
     DecryptionError(String),
     InvalidFormat(String),
     LogError(String),
+    Cancelled(String),
 }
 
 impl fmt::Display for CryptoError {
@@ -47,6 +48,9 @@ impl fmt::Display for CryptoError {
             CryptoError::LogError(msg) => {
                 write!(f, "Błąd logowania: {}", msg)
             }
+            CryptoError::Cancelled(msg) => {
+                write!(f, "Operacja przerwana: {}", msg)
+            }
         }
     }
 }
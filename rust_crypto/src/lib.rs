@@ -1,19 +1,34 @@
+use napi::bindgen_prelude::AsyncTask;
+use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, Task};
 use napi_derive::napi;
 use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 mod adapter;
 mod algorithms;
+mod attacks;
 mod error;
 mod traits;
 mod utils;
 use adapter::AlgorithmAdapter;
+use algorithms::primes::PrimeSearchMonitor;
+use algorithms::rsa_keys::RsaKeyFormat;
 use base64::Engine;
+use traits::Algorithm;
 
+use crate::error::CryptoError;
 use crate::utils::logger;
 
 #[napi]
-pub fn encrypt(file_path: String, key: String, algorithm: String) -> napi::Result<String> {
-    match AlgorithmAdapter::encrypt(file_path, key, algorithm) {
+pub fn encrypt(
+    file_path: String,
+    key: String,
+    algorithm: String,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    match AlgorithmAdapter::encrypt(file_path, key, algorithm, passphrase) {
         Ok(result) => Ok(result),
         Err(e) => {
             logger::log(
@@ -27,8 +42,13 @@ pub fn encrypt(file_path: String, key: String, algorithm: String) -> napi::Resul
 }
 
 #[napi]
-pub fn decrypt(file_path: String, key: String, algorithm: String) -> napi::Result<String> {
-    match AlgorithmAdapter::decrypt(file_path, key, algorithm) {
+pub fn decrypt(
+    file_path: String,
+    key: String,
+    algorithm: String,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    match AlgorithmAdapter::decrypt(file_path, key, algorithm, passphrase) {
         Ok(result) => Ok(result),
         Err(e) => {
             logger::log(
@@ -41,33 +61,375 @@ pub fn decrypt(file_path: String, key: String, algorithm: String) -> napi::Resul
     }
 }
 
+/// Uchwyt pozwalający przerwać trwające generowanie kluczy, odpowiednik
+/// `AbortController` po stronie JS
 #[napi]
-pub fn generate_rsa_keypair(bits: u32) -> napi::Result<String> {
-    match algorithms::rsa::generate_keypair(bits as usize) {
-        Ok(keypair) => {
-            let payload = json!({
-                "public": {
-                    "n": keypair.public.n.to_str_radix(10),
-                    "e": keypair.public.e.to_str_radix(10)
-                },
-                "private": {
-                    "n": keypair.private.n.to_str_radix(10),
-                    "d": keypair.private.d.to_str_radix(10)
-                }
+#[derive(Default)]
+pub struct KeygenAbortHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[napi]
+impl KeygenAbortHandle {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[napi]
+    pub fn abort(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[napi(getter)]
+    pub fn aborted(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[napi(object)]
+pub struct RsaKeygenProgress {
+    pub candidates_tested: i64,
+    pub primes_found: u32,
+}
+
+type RsaKeygenProgressCallback =
+    ThreadsafeFunction<RsaKeygenProgress, (), RsaKeygenProgress, napi::Status, false>;
+
+pub struct RsaKeygenTask {
+    bits: usize,
+    format: RsaKeyFormat,
+    policy: algorithms::rsa::RsaKeyPolicy,
+    cancelled: Arc<AtomicBool>,
+    on_progress: Option<RsaKeygenProgressCallback>,
+    passphrase: Option<String>,
+    seed: Option<String>,
+}
+
+impl Task for RsaKeygenTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let mut monitor = PrimeSearchMonitor::new(self.cancelled.clone());
+        if let Some(callback) = self.on_progress.take() {
+            monitor = monitor.on_progress(move |progress| {
+                callback.call(
+                    RsaKeygenProgress {
+                        candidates_tested: i64::try_from(progress.candidates_tested)
+                            .unwrap_or(i64::MAX),
+                        primes_found: progress.primes_found,
+                    },
+                    ThreadsafeFunctionCallMode::NonBlocking,
+                );
             });
-            Ok(payload.to_string())
         }
+
+        let keypair = match self.seed.as_deref() {
+            Some(seed) => utils::drbg::seeded_rng(seed).and_then(|mut rng| {
+                algorithms::rsa::generate_keypair_with_rng(
+                    self.bits,
+                    self.policy,
+                    &mut monitor,
+                    &mut rng,
+                )
+            }),
+            None => algorithms::rsa::generate_keypair(self.bits, self.policy, &mut monitor),
+        };
+        match keypair.and_then(|keypair| keypair.export(self.format, self.passphrase.as_deref())) {
+            Ok(mut payload) => {
+                if self.seed.is_some() {
+                    payload["deterministic"] = json!(true);
+                }
+                Ok(payload.to_string())
+            }
+            Err(e @ CryptoError::Cancelled(_)) => {
+                logger::log(logger::LogLevel::WARN, "KeyGen", e.to_string());
+                Err(napi::Error::from(e))
+            }
+            Err(e) => {
+                logger::log(
+                    logger::LogLevel::ERROR,
+                    "KeyGen",
+                    format!("Błąd generowania kluczy RSA: {}", e),
+                );
+                Err(napi::Error::from(e))
+            }
+        }
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Parametry `generate_rsa_keypair`; pominięte pola mają wartości domyślne
+#[napi(object)]
+pub struct RsaKeygenOptions {
+    /// Długość modułu w bitach
+    pub bits: u32,
+    /// `json` (domyślnie), `pkcs1`, `pkcs8` lub `jwk`
+    pub format: Option<String>,
+    /// Dopuszcza moduły krótsze niż 2048 bitów
+    pub educational: Option<bool>,
+    /// Uzupełnia test Millera–Rabina o silny test Lucasa (Baillie–PSW)
+    pub lucas_test: Option<bool>,
+    /// Szyfruje klucz prywatny (PKCS#8 PBES2)
+    pub passphrase: Option<String>,
+    /// Liczba czynników pierwszych, 2 (domyślnie) do 5
+    pub primes: Option<u32>,
+    /// Eksponent publiczny (domyślnie 65537); małe wartości, np. 3, tylko w trybie edukacyjnym
+    pub public_exponent: Option<u32>,
+    /// Ziarno trybu deterministycznego (ChaCha20) – tylko do testów i pokazów
+    pub seed: Option<String>,
+}
+
+/// Generuje parę kluczy RSA w puli wątków libuv. Postęp jest raportowany po
+/// każdym teście pierwszości, a `abort` pozwala przerwać obliczenia. Podanie
+/// `passphrase` zwraca klucz prywatny jako zaszyfrowany PKCS#8, a `primes`
+/// (3–5) tworzy klucz wieloczynnikowy. `publicExponent` zmienia e (małe e
+/// tylko w trybie edukacyjnym). Podanie `seed` włącza tryb deterministyczny
+/// (ChaCha20) – tylko do testów i pokazów.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn generate_rsa_keypair(
+    options: RsaKeygenOptions,
+    on_progress: Option<RsaKeygenProgressCallback>,
+    abort: Option<&KeygenAbortHandle>,
+) -> napi::Result<AsyncTask<RsaKeygenTask>> {
+    let format = RsaKeyFormat::parse(options.format.as_deref().unwrap_or_default())?;
+    let policy = algorithms::rsa::RsaKeyPolicy {
+        educational: options.educational.unwrap_or(false),
+        lucas_test: options.lucas_test.unwrap_or(false),
+        prime_count: options.primes.map_or(2, |count| count as usize),
+        public_exponent: options
+            .public_exponent
+            .map_or(algorithms::rsa::DEFAULT_E, u64::from),
+    };
+    Ok(AsyncTask::new(RsaKeygenTask {
+        bits: options.bits as usize,
+        format,
+        policy,
+        cancelled: abort.map_or_else(Default::default, |handle| handle.cancelled.clone()),
+        on_progress,
+        passphrase: options.passphrase,
+        seed: options.seed,
+    }))
+}
+
+#[napi]
+pub fn export_rsa_key(
+    key: String,
+    format: String,
+    passphrase: Option<String>,
+    export_passphrase: Option<String>,
+) -> napi::Result<String> {
+    let format = RsaKeyFormat::parse(&format)?;
+    let cipher = algorithms::rsa::RsaCipher::new(&key, passphrase.as_deref())?;
+    match cipher.export(format, export_passphrase.as_deref()) {
+        Ok(payload) => Ok(payload.to_string()),
         Err(e) => {
             logger::log(
                 logger::LogLevel::ERROR,
-                "KeyGen",
-                &format!("Błąd generowania kluczy RSA: {}", e),
+                "RSA",
+                format!("Błąd eksportu klucza RSA: {}", e),
+            );
+            Err(napi::Error::from(e))
+        }
+    }
+}
+
+/// Odcisk SHA-256 klucza publicznego RSA (hex, base64, emoji i grupy cyfr)
+#[napi]
+pub fn rsa_key_fingerprint(key: String, passphrase: Option<String>) -> napi::Result<String> {
+    let cipher = algorithms::rsa::RsaCipher::load_unchecked(&key, passphrase.as_deref())?;
+    let public = cipher.public_key().ok_or_else(|| {
+        CryptoError::InvalidKey("Odcisk klucza RSA wymaga eksponenta publicznego 'e'".to_string())
+    })?;
+    Ok(public.fingerprint().to_json().to_string())
+}
+
+/// Sprawdza klucz RSA i zwraca raport JSON z wynikiem każdego testu
+#[napi]
+pub fn validate_rsa_key(
+    key: String,
+    educational: Option<bool>,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    let cipher = algorithms::rsa::RsaCipher::load_unchecked(&key, passphrase.as_deref())?;
+    let report = cipher.validate(algorithms::rsa::RsaKeyPolicy {
+        educational: educational.unwrap_or(false),
+        ..Default::default()
+    });
+    for failure in report.failures() {
+        logger::log(
+            logger::LogLevel::WARN,
+            "RSA",
+            format!("Walidacja klucza ({}): {}", failure.check.id(), failure.message),
+        );
+    }
+    Ok(report.to_json().to_string())
+}
+
+pub struct RsaAuditTask {
+    keys: Vec<algorithms::rsa::RsaPublicKey>,
+}
+
+impl Task for RsaAuditTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let report = algorithms::rsa_audit::audit(&self.keys, Default::default());
+        logger::log(
+            logger::LogLevel::INFO,
+            "RSA-Audit",
+            format!(
+                "Audyt zakończony: złamano {} z {} kluczy",
+                report.broken_count(),
+                report.keys.len()
+            ),
+        );
+        Ok(report.to_json().to_string())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Audyt partii kluczy publicznych RSA atakami faktoryzacji (Fermat, rho i
+/// p−1 Pollarda, Wiener, wspólne czynniki). Zwraca raport JSON z odtworzonymi
+/// czynnikami złamanych kluczy.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn audit_rsa_keys(
+    keys: Vec<String>,
+    passphrase: Option<String>,
+) -> napi::Result<AsyncTask<RsaAuditTask>> {
+    let keys = load_rsa_public_keys(&keys, passphrase.as_deref())?;
+    Ok(AsyncTask::new(RsaAuditTask { keys }))
+}
+
+fn load_rsa_public_keys(
+    keys: &[String],
+    passphrase: Option<&str>,
+) -> Result<Vec<algorithms::rsa::RsaPublicKey>, CryptoError> {
+    keys.iter()
+        .enumerate()
+        .map(|(index, key)| {
+            algorithms::rsa::RsaCipher::load_unchecked(key, passphrase)?
+                .public_key()
+                .ok_or_else(|| {
+                    CryptoError::InvalidKey(format!(
+                        "Klucz nr {} nie zawiera eksponenta publicznego 'e'",
+                        index + 1
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn load_rsa_public_key(key: &str) -> Result<algorithms::rsa::RsaPublicKey, CryptoError> {
+    let mut keys = load_rsa_public_keys(&[key.to_string()], None)?;
+    Ok(keys.remove(0))
+}
+
+fn log_attack_result(result: Result<serde_json::Value, CryptoError>) -> napi::Result<String> {
+    match result {
+        Ok(value) => Ok(value.to_string()),
+        Err(e) => {
+            logger::log(
+                logger::LogLevel::WARN,
+                "RSA-Attack",
+                format!("Atak nie powiódł się: {}", e),
             );
             Err(napi::Error::from(e))
         }
     }
 }
 
+/// Atak Håstada: ta sama wiadomość zaszyfrowana małym `e` dla `e` odbiorców.
+/// `keys[i]` to klucz publiczny, którym utworzono `ciphertexts[i]`.
+#[napi]
+pub fn rsa_hastad_attack(keys: Vec<String>, ciphertexts: Vec<String>) -> napi::Result<String> {
+    if keys.len() != ciphertexts.len() {
+        return Err(CryptoError::InvalidFormat(
+            "Liczba kluczy musi być równa liczbie szyfrogramów".to_string(),
+        )
+        .into());
+    }
+    let targets = load_rsa_public_keys(&keys, None)?
+        .into_iter()
+        .zip(
+            ciphertexts
+                .iter()
+                .map(|ciphertext| attacks::rsa::parse_ciphertext(ciphertext))
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+        .collect::<Vec<_>>();
+    log_attack_result(attacks::rsa::hastad_broadcast(&targets).map(|message| message.to_json()))
+}
+
+/// Atak na wspólny moduł: jedna wiadomość zaszyfrowana kluczami (n, e1) i (n, e2)
+#[napi]
+pub fn rsa_common_modulus_attack(
+    first_key: String,
+    first_ciphertext: String,
+    second_key: String,
+    second_ciphertext: String,
+) -> napi::Result<String> {
+    let first_key = load_rsa_public_key(&first_key)?;
+    let second_key = load_rsa_public_key(&second_key)?;
+    let first_blocks = attacks::rsa::parse_ciphertext(&first_ciphertext)?;
+    let second_blocks = attacks::rsa::parse_ciphertext(&second_ciphertext)?;
+    log_attack_result(
+        attacks::rsa::common_modulus((&first_key, &first_blocks), (&second_key, &second_blocks))
+            .map(|message| message.to_json()),
+    )
+}
+
+/// Odzyskuje krótką wiadomość (m^e < n) jako pierwiastek stopnia e z szyfrogramu
+#[napi]
+pub fn rsa_small_message_attack(key: String, ciphertext: String) -> napi::Result<String> {
+    let key = load_rsa_public_key(&key)?;
+    let blocks = attacks::rsa::parse_ciphertext(&ciphertext)?;
+    log_attack_result(
+        attacks::rsa::small_message_root(&key, &blocks).map(|message| message.to_json()),
+    )
+}
+
+/// Atak z wybranym szyfrogramem. Wyrocznię symuluje `oracle_key`: odszyfrowuje
+/// dowolne bloki poza tymi z `ciphertext`.
+#[napi]
+pub fn rsa_chosen_ciphertext_attack(
+    public_key: String,
+    ciphertext: String,
+    oracle_key: String,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    let key = load_rsa_public_key(&public_key)?;
+    let blocks = attacks::rsa::parse_ciphertext(&ciphertext)?;
+    let oracle = algorithms::rsa::RsaCipher::load_unchecked(&oracle_key, passphrase.as_deref())?
+        .private_key()
+        .filter(|private| private.n == key.n)
+        .ok_or_else(|| {
+            CryptoError::InvalidKey(
+                "Klucz wyroczni musi być pełnym kluczem prywatnym pary atakowanego klucza publicznego"
+                    .to_string(),
+            )
+        })?;
+    log_attack_result(
+        attacks::rsa::chosen_ciphertext(&key, &blocks, |block| {
+            if blocks.contains(block) {
+                return Err(CryptoError::DecryptionError(
+                    "Wyrocznia odmawia odszyfrowania przechwyconego szyfrogramu".to_string(),
+                ));
+            }
+            Ok(block.modpow(&oracle.d, &oracle.n))
+        })
+        .map(|result| result.to_json()),
+    )
+}
+
 #[napi]
 pub fn export_logs() -> napi::Result<String> {
     let logs = logger::get_logs();
@@ -80,60 +442,941 @@ pub fn clear_logs() {
     logger::clear_logs();
 }
 
-#[napi]
-pub fn generate_ecdh_keypair() -> napi::Result<String> {
-    let (private, public) = algorithms::ecdh::PrivateKey::generate();
-    let payload = json!({
-        "private": private.to_base64(),
-        "public": public.to_base64()
+fn ecdh_curve(curve: Option<&str>) -> napi::Result<algorithms::ecdh::Curve> {
+    algorithms::ecdh::Curve::from_name(curve.unwrap_or_default())
+        .map_err(|e| napi::Error::from_reason(e.to_string()))
+}
+
+/// Klucz prywatny ECDH, który `generate_ecdh_keypair` zapisuje w wybranym formacie
+trait EcdhKeyExport: Sized {
+    /// Formaty wymieniane w komunikacie o nieznanym formacie
+    const FORMATS: &'static str = "base64, jwk";
+
+    fn generate(curve: algorithms::ecdh::Curve) -> Result<Self, algorithms::ecdh::EcdhError>;
+    fn generate_with_rng<R: rand::RngCore + rand::CryptoRng>(
+        curve: algorithms::ecdh::Curve,
+        rng: &mut R,
+    ) -> Result<Self, algorithms::ecdh::EcdhError>;
+    /// Klucz prywatny i publiczny w formacie `format`, `None` dla nieznanego formatu
+    fn encode(&self, format: &str) -> Option<(serde_json::Value, serde_json::Value)>;
+    fn fingerprint(&self) -> utils::fingerprint::Fingerprint;
+    fn encrypted_pem(&self, passphrase: &str) -> Result<String, algorithms::ecdh::EcdhError>;
+}
+
+/// X25519 i X448 mają ten sam interfejs kluczy i te same formaty zapisu
+macro_rules! impl_okp_key_export {
+    ($key:ty) => {
+        impl EcdhKeyExport for $key {
+            fn generate(
+                _curve: algorithms::ecdh::Curve,
+            ) -> Result<Self, algorithms::ecdh::EcdhError> {
+                Ok(<$key>::generate().0)
+            }
+
+            fn generate_with_rng<R: rand::RngCore + rand::CryptoRng>(
+                _curve: algorithms::ecdh::Curve,
+                rng: &mut R,
+            ) -> Result<Self, algorithms::ecdh::EcdhError> {
+                Ok(<$key>::generate_with_rng(rng).0)
+            }
+
+            fn encode(&self, format: &str) -> Option<(serde_json::Value, serde_json::Value)> {
+                let public = self.public_key();
+                match format {
+                    "" | "base64" => Some((json!(self.to_base64()), json!(public.to_base64()))),
+                    "jwk" => Some((self.to_jwk(), public.to_jwk())),
+                    _ => None,
+                }
+            }
+
+            fn fingerprint(&self) -> utils::fingerprint::Fingerprint {
+                self.public_key().fingerprint()
+            }
+
+            fn encrypted_pem(
+                &self,
+                passphrase: &str,
+            ) -> Result<String, algorithms::ecdh::EcdhError> {
+                self.to_pem(Some(passphrase))
+            }
+        }
+    };
+}
+
+impl_okp_key_export!(algorithms::ecdh::PrivateKey);
+impl_okp_key_export!(algorithms::ecdh::x448::PrivateKey);
+
+impl EcdhKeyExport for algorithms::ecdh::nist::PrivateKey {
+    const FORMATS: &'static str = "base64, compressed, jwk";
+
+    fn generate(curve: algorithms::ecdh::Curve) -> Result<Self, algorithms::ecdh::EcdhError> {
+        Self::generate(curve).map(|(private, _)| private)
+    }
+
+    fn generate_with_rng<R: rand::RngCore + rand::CryptoRng>(
+        curve: algorithms::ecdh::Curve,
+        rng: &mut R,
+    ) -> Result<Self, algorithms::ecdh::EcdhError> {
+        Self::generate_with_rng(curve, rng).map(|(private, _)| private)
+    }
+
+    fn encode(&self, format: &str) -> Option<(serde_json::Value, serde_json::Value)> {
+        let public = self.public_key();
+        match format {
+            "" | "base64" | "compressed" => Some((
+                json!(self.to_base64()),
+                json!(public.to_base64(format == "compressed")),
+            )),
+            "jwk" => Some((self.to_jwk(), public.to_jwk())),
+            _ => None,
+        }
+    }
+
+    fn fingerprint(&self) -> utils::fingerprint::Fingerprint {
+        self.public_key().fingerprint()
+    }
+
+    fn encrypted_pem(&self, passphrase: &str) -> Result<String, algorithms::ecdh::EcdhError> {
+        self.to_pem(Some(passphrase))
+    }
+}
+
+fn ecdh_keypair<K: EcdhKeyExport>(
+    curve: algorithms::ecdh::Curve,
+    format: &str,
+    passphrase: Option<&str>,
+    seed: Option<&str>,
+) -> napi::Result<serde_json::Value> {
+    let private = match seed {
+        Some(seed) => K::generate_with_rng(curve, &mut utils::drbg::seeded_rng(seed)?),
+        None => K::generate(curve),
+    }
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let (private_key, public_key) = private.encode(format).ok_or_else(|| {
+        napi::Error::from_reason(format!(
+            "Nieznany format klucza ECDH '{}'. Dostępne: {}",
+            format,
+            K::FORMATS
+        ))
+    })?;
+    let mut payload = json!({
+        "private": private_key,
+        "public": public_key
     });
+    payload["fingerprint"] = private.fingerprint().to_json();
+    if let Some(passphrase) = passphrase {
+        payload["private"] = json!(private
+            .encrypted_pem(passphrase)
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?);
+    }
+    Ok(payload)
+}
+
+/// Generuje parę kluczy ECDH na krzywej `curve`: `x25519` (domyślnie), `x448`,
+/// `p256` lub `p384`. Klucz publiczny NIST to punkt SEC1 w base64 – nieskompresowany
+/// (`base64`) lub skompresowany (`compressed`). Podanie `seed` włącza tryb
+/// deterministyczny (ChaCha20) – tylko do testów i pokazów.
+#[napi]
+pub fn generate_ecdh_keypair(
+    format: Option<String>,
+    passphrase: Option<String>,
+    seed: Option<String>,
+    curve: Option<String>,
+) -> napi::Result<String> {
+    let curve = ecdh_curve(curve.as_deref())?;
+    let format = format.as_deref().map(str::trim).unwrap_or_default();
+    let (passphrase, seed) = (passphrase.as_deref(), seed.as_deref());
+    let mut payload = match curve {
+        algorithms::ecdh::Curve::X25519 => {
+            ecdh_keypair::<algorithms::ecdh::PrivateKey>(curve, format, passphrase, seed)?
+        }
+        algorithms::ecdh::Curve::X448 => {
+            ecdh_keypair::<algorithms::ecdh::x448::PrivateKey>(curve, format, passphrase, seed)?
+        }
+        _ => ecdh_keypair::<algorithms::ecdh::nist::PrivateKey>(curve, format, passphrase, seed)?,
+    };
+    payload["curve"] = json!(curve.name());
+    if let Some(public) = payload["public"].as_str() {
+        payload["commitment"] = json!(ecdh_commitment(public, curve)?);
+    }
+    if seed.is_some() {
+        payload["deterministic"] = json!(true);
+    }
     Ok(payload.to_string())
 }
 
+/// Odcisk SHA-256 klucza publicznego ECDH (hex, base64, emoji i grupy cyfr)
+#[napi]
+pub fn ecdh_public_key_fingerprint(
+    public_key: String,
+    curve: Option<String>,
+) -> napi::Result<String> {
+    let fingerprint = match ecdh_curve(curve.as_deref())? {
+        algorithms::ecdh::Curve::X25519 => algorithms::ecdh::PublicKeyBytes::decode(&public_key)
+            .map(|public| public.fingerprint()),
+        algorithms::ecdh::Curve::X448 => algorithms::ecdh::x448::PublicKeyBytes::decode(&public_key)
+            .map(|public| public.fingerprint()),
+        curve => algorithms::ecdh::nist::PublicKey::decode(curve, &public_key)
+            .map(|public| public.fingerprint()),
+    }
+    .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    Ok(fingerprint.to_json().to_string())
+}
+
+/// Zobowiązanie (SHA-256, base64) do klucza publicznego ECDH. Wyślij je
+/// rozmówcy przed samym kluczem, a swój klucz ujawnij dopiero po otrzymaniu
+/// jego zobowiązania – `compute_ecdh_sas` sprawdzi je wtedy z kluczem rozmówcy.
 #[napi]
-pub fn compute_ecdh_shared_secret(private_key: String, public_key: String) -> napi::Result<String> {
-    let private = algorithms::ecdh::PrivateKey::from_base64(&private_key)
+pub fn ecdh_public_key_commitment(
+    public_key: String,
+    curve: Option<String>,
+) -> napi::Result<String> {
+    ecdh_commitment(&public_key, ecdh_curve(curve.as_deref())?)
+}
+
+fn ecdh_commitment(public_key: &str, curve: algorithms::ecdh::Curve) -> napi::Result<String> {
+    let public = ecdh_public_bytes(public_key, curve)
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-    let public = algorithms::ecdh::PublicKeyBytes::from_base64(&public_key)
+    Ok(base64::engine::general_purpose::STANDARD
+        .encode(algorithms::ecdh::sas::commit(curve, &public)))
+}
+
+/// Surowy klucz publiczny w postaci używanej przez SAS (punkty NIST nieskompresowane)
+fn ecdh_public_bytes(
+    public_key: &str,
+    curve: algorithms::ecdh::Curve,
+) -> Result<Vec<u8>, algorithms::ecdh::EcdhError> {
+    match curve {
+        algorithms::ecdh::Curve::X25519 => {
+            algorithms::ecdh::PublicKeyBytes::decode(public_key).map(|k| k.as_bytes().to_vec())
+        }
+        algorithms::ecdh::Curve::X448 => algorithms::ecdh::x448::PublicKeyBytes::decode(public_key)
+            .map(|k| k.as_bytes().to_vec()),
+        curve => algorithms::ecdh::nist::PublicKey::decode(curve, public_key)
+            .map(|k| k.to_sec1_bytes(false)),
+    }
+}
+
+/// Wynik uzgodnienia ECDH: wspólny sekret i surowe klucze publiczne obu stron
+struct EcdhAgreement {
+    shared_secret: Vec<u8>,
+    own_public: Vec<u8>,
+    peer_public: Vec<u8>,
+}
+
+fn ecdh_agreement(
+    private_key: &str,
+    public_key: &str,
+    passphrase: Option<&str>,
+    curve: algorithms::ecdh::Curve,
+) -> Result<EcdhAgreement, algorithms::ecdh::EcdhError> {
+    match curve {
+        algorithms::ecdh::Curve::X25519 => {
+            let private = algorithms::ecdh::PrivateKey::decode(private_key, passphrase)?;
+            let public = algorithms::ecdh::PublicKeyBytes::decode(public_key)?;
+            Ok(EcdhAgreement {
+                shared_secret: private.compute_shared_secret(&public)?.to_vec(),
+                own_public: private.public_key().as_bytes().to_vec(),
+                peer_public: public.as_bytes().to_vec(),
+            })
+        }
+        algorithms::ecdh::Curve::X448 => {
+            let private = algorithms::ecdh::x448::PrivateKey::decode(private_key, passphrase)?;
+            let public = algorithms::ecdh::x448::PublicKeyBytes::decode(public_key)?;
+            Ok(EcdhAgreement {
+                shared_secret: private.compute_shared_secret(&public)?.to_vec(),
+                own_public: private.public_key().as_bytes().to_vec(),
+                peer_public: public.as_bytes().to_vec(),
+            })
+        }
+        curve => {
+            let private =
+                algorithms::ecdh::nist::PrivateKey::decode(curve, private_key, passphrase)?;
+            let public = algorithms::ecdh::nist::PublicKey::decode(curve, public_key)?;
+            // Punkty nieskompresowane, aby forma zapisu klucza nie zmieniała SAS
+            Ok(EcdhAgreement {
+                shared_secret: private.compute_shared_secret(&public)?,
+                own_public: private.public_key().to_sec1_bytes(false),
+                peer_public: public.to_sec1_bytes(false),
+            })
+        }
+    }
+}
+
+#[napi]
+pub fn compute_ecdh_shared_secret(
+    private_key: String,
+    public_key: String,
+    passphrase: Option<String>,
+    curve: Option<String>,
+) -> napi::Result<String> {
+    let curve = ecdh_curve(curve.as_deref())?;
+    let agreement = ecdh_agreement(&private_key, &public_key, passphrase.as_deref(), curve)
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
 
-    let shared_secret = private.compute_shared_secret(&public)
+    Ok(base64::engine::general_purpose::STANDARD.encode(agreement.shared_secret))
+}
+
+/// Krótki ciąg uwierzytelniający (SAS) do porównania poza kanałem: sześć cyfr,
+/// siedem emoji i odpowiadające im słowa. Obie strony otrzymują ten sam wynik
+/// tylko wtedy, gdy nikt nie podmienił kluczy publicznych.
+///
+/// Sześć cyfr to tylko ok. 20 bitów: pośrednik, który zna oba klucze przed
+/// wyborem własnych, może przetestować ok. 2^20 par, aż ciągi się zgodzą.
+/// Podanie `peer_commitment` (wynik `ecdh_public_key_commitment` otrzymany od
+/// rozmówcy, zanim ujawniło się własny klucz) zamyka tę lukę – klucz rozmówcy
+/// niezgodny ze zobowiązaniem kończy się błędem.
+#[napi]
+pub fn compute_ecdh_sas(
+    private_key: String,
+    public_key: String,
+    passphrase: Option<String>,
+    curve: Option<String>,
+    peer_commitment: Option<String>,
+) -> napi::Result<String> {
+    let curve = ecdh_curve(curve.as_deref())?;
+    let peer_commitment = peer_commitment
+        .as_deref()
+        .map(str::trim)
+        .filter(|commitment| !commitment.is_empty())
+        .map(|commitment| {
+            base64::engine::general_purpose::STANDARD
+                .decode(commitment)
+                .map_err(|_| napi::Error::from_reason("Zobowiązanie musi być zapisane w base64"))
+        })
+        .transpose()?;
+    let sas = ecdh_agreement(&private_key, &public_key, passphrase.as_deref(), curve)
+        .and_then(|agreement| {
+            if let Some(commitment) = &peer_commitment {
+                algorithms::ecdh::sas::verify_commitment(
+                    curve,
+                    &agreement.peer_public,
+                    commitment,
+                )?;
+            }
+            algorithms::ecdh::sas::ShortAuthString::derive(
+                curve,
+                &agreement.shared_secret,
+                &agreement.own_public,
+                &agreement.peer_public,
+            )
+        })
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
 
-    Ok(base64::engine::general_purpose::STANDARD.encode(shared_secret))
+    Ok(sas.to_json().to_string())
 }
 
+/// Parametry HKDF z argumentów napi; bez `length` wynik ma 32 bajty (lub
+/// rozmiar skrótu w trybie `extract`), jak dotychczasowe HKDF-SHA256
+fn kdf_params(
+    hash: Option<&str>,
+    length: Option<u32>,
+    mode: Option<&str>,
+) -> napi::Result<algorithms::ecdh::kdf::KdfParams> {
+    let to_napi = |e: algorithms::ecdh::EcdhError| napi::Error::from_reason(e.to_string());
+    let hash = hash
+        .map(algorithms::ecdh::kdf::KdfHash::from_name)
+        .transpose()
+        .map_err(to_napi)?
+        .unwrap_or_default();
+    let mode = mode
+        .map(algorithms::ecdh::kdf::KdfMode::from_name)
+        .transpose()
+        .map_err(to_napi)?
+        .unwrap_or_default();
+    let length = match (length, mode) {
+        (Some(length), _) => Some(length as usize),
+        (None, algorithms::ecdh::kdf::KdfMode::Extract) => None,
+        (None, _) => Some(32),
+    };
+    Ok(algorithms::ecdh::kdf::KdfParams { hash, mode, length })
+}
+
+/// HKDF (RFC 5869) ze wspólnego sekretu w base64. Opcje jak w `HkdfOptions`:
+/// `hash` to `sha256` (domyślnie), `sha384` lub `sha512`, `length` – długość
+/// wyniku w bajtach, a `mode` wybiera etapy: `extract-and-expand`
+/// (domyślnie), `extract` (zwraca PRK) lub `expand` (sekret jest już kluczem PRK).
 #[napi]
 pub fn derive_ecdh_key(
     shared_secret: String,
-    salt: Option<String>,
-    info: Option<String>,
+    options: Option<HkdfOptions>,
 ) -> napi::Result<String> {
-    let secret_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&shared_secret)
-        .map_err(|_| napi::Error::from_reason("Invalid base64 shared secret".to_string()))?;
+    let secret_bytes = decode_base64_arg(&shared_secret, "sharedSecret")?;
+    let (derived, _) = options.unwrap_or_default().derive(&secret_bytes)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(derived))
+}
 
-    let salt_bytes = if let Some(s) = salt {
-        Some(base64::engine::general_purpose::STANDARD
-            .decode(&s)
-            .map_err(|_| napi::Error::from_reason("Invalid base64 salt".to_string()))?)
-    } else {
-        None
+/// Generuje tożsamość age X25519 w formacie pliku `age-keygen` oraz
+/// odpowiadającego jej odbiorcę `age1...`
+#[napi]
+pub fn generate_age_identity() -> String {
+    let (identity, recipient) = algorithms::age::generate_identity();
+    let created = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    json!({
+        "identity": format!(
+            "# created: {}\n# public key: {}\n{}\n",
+            created, recipient, identity
+        ),
+        "recipient": recipient
+    })
+    .to_string()
+}
+
+fn ed25519_error(err: algorithms::ed25519::Ed25519Error) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
+
+fn read_file_bytes(file_path: &str) -> napi::Result<Vec<u8>> {
+    std::fs::read(file_path)
+        .map_err(|err| napi::Error::from(CryptoError::FileReadError(err.to_string())))
+}
+
+/// Generuje parę kluczy Ed25519 w formacie `base64` (domyślnie), `pem` (PKCS#8
+/// i SPKI) lub `openssh`. Podanie `passphrase` zwraca klucz prywatny jako
+/// zaszyfrowany PKCS#8, a `seed` włącza tryb deterministyczny (ChaCha20).
+#[napi]
+pub fn generate_ed25519_keypair(
+    format: Option<String>,
+    passphrase: Option<String>,
+    seed: Option<String>,
+) -> napi::Result<String> {
+    let (private, public) = match seed.as_deref() {
+        Some(seed) => {
+            algorithms::ed25519::PrivateKey::generate_with_rng(&mut utils::drbg::seeded_rng(seed)?)
+        }
+        None => algorithms::ed25519::PrivateKey::generate(),
+    };
+    let mut payload = match format.as_deref().map(str::trim).unwrap_or_default() {
+        "" | "base64" => json!({
+            "private": private.to_base64(),
+            "public": public.to_base64()
+        }),
+        "pem" => json!({
+            "private": private.to_pem(None).map_err(ed25519_error)?,
+            "public": public.to_pem()
+        }),
+        "openssh" => json!({
+            "private": private.to_openssh(""),
+            "public": public.to_openssh("")
+        }),
+        other => {
+            return Err(napi::Error::from_reason(format!(
+                "Nieznany format klucza Ed25519 '{}'. Dostępne: base64, pem, openssh",
+                other
+            )));
+        }
     };
+    payload["fingerprint"] = public.fingerprint().to_json();
+    if seed.is_some() {
+        payload["deterministic"] = json!(true);
+    }
+    if let Some(passphrase) = passphrase.as_deref() {
+        payload["private"] = json!(private.to_pem(Some(passphrase)).map_err(ed25519_error)?);
+    }
+    Ok(payload.to_string())
+}
+
+/// Odcisk SHA-256 klucza publicznego Ed25519 (base64 zgodny z `ssh-keygen -lf`)
+#[napi]
+pub fn ed25519_public_key_fingerprint(public_key: String) -> napi::Result<String> {
+    let public = algorithms::ed25519::PublicKey::decode(&public_key).map_err(ed25519_error)?;
+    Ok(public.fingerprint().to_json().to_string())
+}
+
+/// Podpisuje tekst (UTF-8) i zwraca podpis Ed25519 w base64
+#[napi]
+pub fn ed25519_sign(
+    private_key: String,
+    message: String,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    let private = algorithms::ed25519::PrivateKey::decode(&private_key, passphrase.as_deref())
+        .map_err(ed25519_error)?;
+    logger::log(
+        logger::LogLevel::INFO,
+        "Ed25519",
+        format!("Podpisywanie tekstu ({} bajtów)", message.len()),
+    );
+    Ok(private.sign(message.as_bytes()))
+}
 
-    let info_bytes = if let Some(i) = info {
-        Some(base64::engine::general_purpose::STANDARD
-            .decode(&i)
-            .map_err(|_| napi::Error::from_reason("Invalid base64 info".to_string()))?)
+/// Podpisuje zawartość pliku i zwraca podpis Ed25519 w base64
+#[napi]
+pub fn ed25519_sign_file(
+    private_key: String,
+    file_path: String,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    let private = algorithms::ed25519::PrivateKey::decode(&private_key, passphrase.as_deref())
+        .map_err(ed25519_error)?;
+    let data = read_file_bytes(&file_path)?;
+    logger::log(
+        logger::LogLevel::INFO,
+        "Ed25519",
+        format!("Podpisywanie pliku {} ({} bajtów)", file_path, data.len()),
+    );
+    Ok(private.sign(&data))
+}
+
+fn log_verification(subject: &str, valid: bool) -> bool {
+    if valid {
+        logger::log(logger::LogLevel::INFO, "Ed25519", format!("Podpis {} jest poprawny", subject));
     } else {
-        None
+        logger::log(
+            logger::LogLevel::WARN,
+            "Ed25519",
+            format!("Podpis {} jest NIEPOPRAWNY", subject),
+        );
+    }
+    valid
+}
+
+/// Weryfikuje podpis Ed25519 (base64) tekstu
+#[napi]
+pub fn ed25519_verify(public_key: String, message: String, signature: String) -> napi::Result<bool> {
+    let public = algorithms::ed25519::PublicKey::decode(&public_key).map_err(ed25519_error)?;
+    let valid = public.verify(message.as_bytes(), &signature).map_err(ed25519_error)?;
+    Ok(log_verification("tekstu", valid))
+}
+
+/// Weryfikuje podpis Ed25519 (base64) zawartości pliku
+#[napi]
+pub fn ed25519_verify_file(
+    public_key: String,
+    file_path: String,
+    signature: String,
+) -> napi::Result<bool> {
+    let public = algorithms::ed25519::PublicKey::decode(&public_key).map_err(ed25519_error)?;
+    let data = read_file_bytes(&file_path)?;
+    let valid = public.verify(&data, &signature).map_err(ed25519_error)?;
+    Ok(log_verification(&format!("pliku {}", file_path), valid))
+}
+
+fn noise_error(err: algorithms::noise::NoiseError) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
+
+fn decode_base64_arg(value: &str, name: &str) -> napi::Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|_| napi::Error::from_reason(format!("Nieprawidłowy base64 w polu {}", name)))
+}
+
+/// Sesja protokołu Noise (`Noise_{NN,XX,IK}_25519_{AESGCM,ChaChaPoly}_SHA256`).
+/// Najpierw wymienia wiadomości uzgadniania, a po ostatniej z nich te same
+/// metody szyfrują i odszyfrowują wiadomości transportowe.
+#[napi]
+pub struct NoiseSession {
+    session: algorithms::noise::Session,
+}
+
+#[napi]
+impl NoiseSession {
+    /// `staticKey` to własny klucz prywatny X25519 (base64, JWK lub PEM jak w
+    /// ECDH), `remoteStaticKey` – klucz publiczny odpowiadającego, podawany
+    /// tylko przez inicjatora IK. `prologue` (base64) musi być taki sam po obu
+    /// stronach.
+    #[napi(constructor)]
+    pub fn new(
+        protocol: String,
+        initiator: bool,
+        static_key: Option<String>,
+        remote_static_key: Option<String>,
+        prologue: Option<String>,
+        passphrase: Option<String>,
+    ) -> napi::Result<Self> {
+        let protocol =
+            algorithms::noise::Protocol::from_name(protocol.trim()).map_err(noise_error)?;
+        let local_static = static_key
+            .as_deref()
+            .map(|key| algorithms::ecdh::PrivateKey::decode(key, passphrase.as_deref()))
+            .transpose()
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+        let remote_static = remote_static_key
+            .as_deref()
+            .map(algorithms::ecdh::PublicKeyBytes::decode)
+            .transpose()
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+        let prologue = prologue
+            .as_deref()
+            .map(|prologue| decode_base64_arg(prologue, "prologue"))
+            .transpose()?
+            .unwrap_or_default();
+
+        let session = algorithms::noise::Session::new(
+            protocol,
+            initiator,
+            &prologue,
+            local_static,
+            remote_static,
+        )
+        .map_err(noise_error)?;
+        logger::log(
+            logger::LogLevel::INFO,
+            "Noise",
+            format!(
+                "Nowa sesja {} ({})",
+                protocol.name(),
+                if initiator { "inicjator" } else { "odpowiadający" }
+            ),
+        );
+        Ok(Self { session })
+    }
+
+    /// Zwraca kolejną wiadomość do wysłania (base64) z opcjonalnym ładunkiem
+    /// `payload` (base64)
+    #[napi]
+    pub fn write_message(&mut self, payload: Option<String>) -> napi::Result<String> {
+        let payload = payload
+            .as_deref()
+            .map(|payload| decode_base64_arg(payload, "payload"))
+            .transpose()?
+            .unwrap_or_default();
+        let was_finished = self.session.is_handshake_finished();
+        let message = self.session.write_message(&payload).map_err(noise_error)?;
+        log_noise_handshake(&self.session, was_finished);
+        Ok(base64::engine::general_purpose::STANDARD.encode(message))
+    }
+
+    /// Przetwarza wiadomość drugiej strony (base64) i zwraca jej ładunek (base64)
+    #[napi]
+    pub fn read_message(&mut self, message: String) -> napi::Result<String> {
+        let message = decode_base64_arg(&message, "message")?;
+        let was_finished = self.session.is_handshake_finished();
+        let payload = self.session.read_message(&message).map_err(noise_error)?;
+        log_noise_handshake(&self.session, was_finished);
+        Ok(base64::engine::general_purpose::STANDARD.encode(payload))
+    }
+
+    #[napi(getter)]
+    pub fn handshake_finished(&self) -> bool {
+        self.session.is_handshake_finished()
+    }
+
+    /// Skrót transkryptu uzgadniania (base64), taki sam po obu stronach –
+    /// można go porównać poza kanałem lub użyć do powiązania kanału
+    #[napi(getter)]
+    pub fn handshake_hash(&self) -> Option<String> {
+        self.session
+            .handshake_hash()
+            .map(|hash| base64::engine::general_purpose::STANDARD.encode(hash))
+    }
+
+    /// Uwierzytelniony klucz publiczny X25519 drugiej strony (base64)
+    #[napi(getter)]
+    pub fn remote_static_key(&self) -> Option<String> {
+        self.session.remote_static().map(|key| key.to_base64())
+    }
+}
+
+fn log_noise_handshake(session: &algorithms::noise::Session, was_finished: bool) {
+    if !was_finished && session.is_handshake_finished() {
+        logger::log(
+            logger::LogLevel::INFO,
+            "Noise",
+            format!(
+                "Uzgadnianie {} zakończone, sesja przechodzi w tryb transportowy",
+                session.protocol().name()
+            ),
+        );
+    }
+}
+
+fn ratchet_error(err: algorithms::ratchet::RatchetError) -> napi::Error {
+    napi::Error::from_reason(err.to_string())
+}
+
+fn decode_x25519_private(
+    key: &str,
+    passphrase: Option<&str>,
+) -> napi::Result<algorithms::ecdh::PrivateKey> {
+    algorithms::ecdh::PrivateKey::decode(key, passphrase)
+        .map_err(|e| napi::Error::from_reason(e.to_string()))
+}
+
+/// Generuje klucze wstępne X3DH: klucz tożsamości (chyba że podano własny
+/// `identityKey`), klucz wstępny i jednorazowy klucz wstępny. Zwraca JSON
+/// z publicznym pakietem `bundle` do przekazania rozmówcy oraz kluczami
+/// prywatnymi `private` (base64, a po podaniu `passphrase` – PEM PKCS#8
+/// zaszyfrowany PBES2 jak w `generate_ecdh_keypair`). Klucz wstępny w pakiecie
+/// jest podpisany kluczem tożsamości (XEdDSA).
+#[napi]
+pub fn generate_x3dh_prekeys(
+    identity_key: Option<String>,
+    passphrase: Option<String>,
+) -> napi::Result<String> {
+    let (identity, generated) = match identity_key.as_deref() {
+        Some(key) => (decode_x25519_private(key, passphrase.as_deref())?, false),
+        None => (algorithms::ecdh::PrivateKey::generate().0, true),
     };
+    let (prekey, prekey_public) = algorithms::ecdh::PrivateKey::generate();
+    let (one_time, one_time_public) = algorithms::ecdh::PrivateKey::generate();
+    let bundle = algorithms::ratchet::x3dh::PreKeyBundle::new(
+        &identity,
+        prekey_public,
+        Some(one_time_public),
+    );
 
-    let derived = algorithms::ecdh::PrivateKey::derive_key(
-        &secret_bytes,
-        salt_bytes.as_deref(),
-        info_bytes.as_deref(),
-    ).map_err(|e| napi::Error::from_reason(e.to_string()))?;
+    let export = |key: &algorithms::ecdh::PrivateKey| match passphrase.as_deref() {
+        Some(passphrase) => key
+            .to_pem(Some(passphrase))
+            .map_err(|e| napi::Error::from_reason(e.to_string())),
+        None => Ok(key.to_base64()),
+    };
+    let mut private = json!({
+        "prekey": export(&prekey)?,
+        "one_time_prekey": export(&one_time)?
+    });
+    // Podany klucz tożsamości zostaje u wywołującego, nie wypisujemy go jawnie
+    if generated {
+        private["identity_key"] = json!(export(&identity)?);
+    }
+    Ok(json!({
+        "bundle": serde_json::from_str::<serde_json::Value>(&bundle.to_json())
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?,
+        "private": private,
+        "fingerprint": identity.public_key().fingerprint().to_json()
+    })
+    .to_string())
+}
 
-    Ok(base64::engine::general_purpose::STANDARD.encode(derived))
+/// Sesja Double Ratchet zainicjowana przez X3DH. Każda wiadomość ma
+/// osobny klucz, a stan (`serialize`/`restore`) można zapisać między
+/// uruchomieniami aplikacji – zawiera klucze tajne.
+#[napi]
+pub struct RatchetSession {
+    session: algorithms::ratchet::Session,
+}
+
+#[napi]
+impl RatchetSession {
+    /// Rozpoczyna sesję z właścicielem pakietu kluczy wstępnych `bundle` (JSON).
+/// Pakiet z nieprawidłowym podpisem klucza wstępnego jest odrzucany.
+    #[napi(factory)]
+    pub fn initiate(
+        identity_key: String,
+        bundle: String,
+        passphrase: Option<String>,
+    ) -> napi::Result<Self> {
+        let identity = decode_x25519_private(&identity_key, passphrase.as_deref())?;
+        let bundle =
+            algorithms::ratchet::x3dh::PreKeyBundle::from_json(&bundle).map_err(ratchet_error)?;
+        let session =
+            algorithms::ratchet::Session::initiate(&identity, &bundle).map_err(ratchet_error)?;
+        logger::log(logger::LogLevel::INFO, "Double Ratchet", "Rozpoczęto sesję X3DH");
+        Ok(Self { session })
+    }
+
+    /// Przyjmuje sesję z pierwszej wiadomości inicjatora (base64). Treść tej
+    /// wiadomości odczytuje się potem zwykłym `decrypt`. Hasło `passphrase`
+    /// odszyfrowuje wszystkie klucze zapisane jako PEM przez `generate_x3dh_prekeys`.
+    #[napi(factory)]
+    pub fn respond(
+        identity_key: String,
+        prekey: String,
+        one_time_prekey: Option<String>,
+        message: String,
+        passphrase: Option<String>,
+    ) -> napi::Result<Self> {
+        let identity = decode_x25519_private(&identity_key, passphrase.as_deref())?;
+        let prekey = decode_x25519_private(&prekey, passphrase.as_deref())?;
+        let one_time_prekey = one_time_prekey
+            .as_deref()
+            .map(|key| decode_x25519_private(key, passphrase.as_deref()))
+            .transpose()?;
+        let message = decode_base64_arg(&message, "message")?;
+        let session = algorithms::ratchet::Session::respond(
+            &identity,
+            &prekey,
+            one_time_prekey.as_ref(),
+            &message,
+        )
+        .map_err(ratchet_error)?;
+        logger::log(logger::LogLevel::INFO, "Double Ratchet", "Przyjęto sesję X3DH");
+        Ok(Self { session })
+    }
+
+    /// Odtwarza sesję ze stanu zwróconego przez `serialize`
+    #[napi(factory)]
+    pub fn restore(state: String) -> napi::Result<Self> {
+        let session = algorithms::ratchet::Session::from_json(&state).map_err(ratchet_error)?;
+        Ok(Self { session })
+    }
+
+    /// Stan sesji jako JSON; należy go zapisać po każdym `encrypt` i `decrypt`
+    #[napi]
+    pub fn serialize(&self) -> String {
+        self.session.to_json()
+    }
+
+    /// Szyfruje `plaintext` (base64) i zwraca wiadomość (base64)
+    #[napi]
+    pub fn encrypt(&mut self, plaintext: String) -> napi::Result<String> {
+        let plaintext = decode_base64_arg(&plaintext, "plaintext")?;
+        let message = self.session.encrypt(&plaintext).map_err(ratchet_error)?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(message))
+    }
+
+    /// Odszyfrowuje wiadomość (base64), także spóźnioną lub w innej kolejności;
+    /// przy błędzie stan sesji się nie zmienia
+    #[napi]
+    pub fn decrypt(&mut self, message: String) -> napi::Result<String> {
+        let message = decode_base64_arg(&message, "message")?;
+        let plaintext = self.session.decrypt(&message).map_err(|err| {
+            logger::log(
+                logger::LogLevel::WARN,
+                "Double Ratchet",
+                format!("Odrzucono wiadomość: {}", err),
+            );
+            ratchet_error(err)
+        })?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(plaintext))
+    }
+
+    /// Klucz tożsamości X25519 rozmówcy (base64)
+    #[napi(getter)]
+    pub fn remote_identity_key(&self) -> String {
+        self.session.remote_identity().to_base64()
+    }
+}
+
+/// Klucz AES wyprowadzony przez HKDF, który nie opuszcza Rusta – JS dostaje
+/// tylko uchwyt szyfrujący i odszyfrowujący pliki tak jak algorytm `aes-gcm`
+#[napi]
+pub struct AesKey {
+    cipher: algorithms::aes::AesCipher,
+    bits: u32,
+}
+
+/// Parametry HKDF dla `deriveEcdhKey` i `AesKey`
+#[napi(object)]
+#[derive(Default)]
+pub struct HkdfOptions {
+    /// Sól w base64
+    pub salt: Option<String>,
+    /// Kontekst `info` w base64
+    pub info: Option<String>,
+    /// `sha256` (domyślnie), `sha384` lub `sha512`
+    pub hash: Option<String>,
+    /// Długość wyniku w bajtach (domyślnie 32); klucz `AesKey` ma 16, 24 lub 32 bajty
+    pub length: Option<u32>,
+    /// `extract-and-expand` (domyślnie), `extract` lub `expand`
+    pub mode: Option<String>,
+}
+
+impl HkdfOptions {
+    fn derive(
+        self,
+        secret: &[u8],
+    ) -> napi::Result<(Vec<u8>, algorithms::ecdh::kdf::KdfParams)> {
+        let params = kdf_params(self.hash.as_deref(), self.length, self.mode.as_deref())?;
+        let salt = self
+            .salt
+            .map(|salt| decode_base64_arg(&salt, "salt"))
+            .transpose()?;
+        let info = self
+            .info
+            .map(|info| decode_base64_arg(&info, "info"))
+            .transpose()?;
+        let derived = params
+            .derive(secret, salt.as_deref(), info.as_deref())
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+        Ok((derived, params))
+    }
+}
+
+impl AesKey {
+    fn derive(secret: &[u8], options: Option<HkdfOptions>) -> napi::Result<Self> {
+        let (key, params) = options.unwrap_or_default().derive(secret)?;
+        let cipher = algorithms::aes::AesCipher::from_bytes(&key).map_err(napi::Error::from)?;
+        logger::log(
+            logger::LogLevel::INFO,
+            "HKDF",
+            format!(
+                "Wyprowadzono klucz AES-{} (HKDF-{})",
+                key.len() * 8,
+                params.hash.name()
+            ),
+        );
+        Ok(Self {
+            cipher,
+            bits: key.len() as u32 * 8,
+        })
+    }
+}
+
+#[napi]
+impl AesKey {
+    /// Wyprowadza klucz ze wspólnego sekretu (base64) tak jak `deriveEcdhKey`;
+    /// domyślnie powstaje 32-bajtowy klucz AES-256
+    #[napi(factory)]
+    pub fn from_shared_secret(
+        shared_secret: String,
+        options: Option<HkdfOptions>,
+    ) -> napi::Result<Self> {
+        let secret = decode_base64_arg(&shared_secret, "sharedSecret")?;
+        Self::derive(&secret, options)
+    }
+
+    /// Wykonuje ECDH i HKDF w jednym kroku, więc wspólny sekret nie trafia do JS.
+    /// Tryb `expand` jest odrzucany: wynik ECDH nie jest jednorodnym kluczem PRK.
+    #[napi(factory)]
+    pub fn from_ecdh(
+        private_key: String,
+        public_key: String,
+        passphrase: Option<String>,
+        curve: Option<String>,
+        options: Option<HkdfOptions>,
+    ) -> napi::Result<Self> {
+        let expand_only = options
+            .as_ref()
+            .and_then(|options| options.mode.as_deref())
+            .is_some_and(|mode| {
+                matches!(
+                    algorithms::ecdh::kdf::KdfMode::from_name(mode),
+                    Ok(algorithms::ecdh::kdf::KdfMode::Expand)
+                )
+            });
+        if expand_only {
+            return Err(napi::Error::from_reason(
+                "Tryb 'expand' wymaga klucza PRK, a wspólny sekret ECDH nie jest jednorodny – użyj 'extract-and-expand'",
+            ));
+        }
+        let curve = ecdh_curve(curve.as_deref())?;
+        let agreement = ecdh_agreement(&private_key, &public_key, passphrase.as_deref(), curve)
+            .map_err(|e| napi::Error::from_reason(e.to_string()))?;
+        Self::derive(&agreement.shared_secret, options)
+    }
+
+    /// Szyfruje plik AES-GCM i zwraca ścieżkę wyniku (format jak `aes-gcm`)
+    #[napi]
+    pub fn encrypt_file(&self, file_path: String) -> napi::Result<String> {
+        self.cipher.encrypt(&file_path).map_err(|e| {
+            logger::log(
+                logger::LogLevel::ERROR,
+                "Encrypt",
+                format!("Błąd szyfrowania: {}", e),
+            );
+            napi::Error::from(e)
+        })
+    }
+
+    /// Odszyfrowuje plik zaszyfrowany tym samym kluczem
+    #[napi]
+    pub fn decrypt_file(&self, file_path: String) -> napi::Result<String> {
+        self.cipher.decrypt(&file_path).map_err(|e| {
+            logger::log(
+                logger::LogLevel::ERROR,
+                "Decrypt",
+                format!("Błąd deszyfrowania: {}", e),
+            );
+            napi::Error::from(e)
+        })
+    }
+
+    /// Długość klucza w bitach (128, 192 lub 256)
+    #[napi(getter)]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
 }
@@ -1,5 +1,6 @@
 mod adapter;
 mod algorithms;
+mod attacks;
 mod error;
 mod traits;
 mod utils;
@@ -13,6 +14,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "./src/example.txt".to_string(),
         "kot".to_string(),
         "vigenere-cipher".to_string(),
+        None,
     )?;
     println!("Encrypted: {}", encryption_result);
 
@@ -20,6 +22,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "./src/example_encrypted.txt".to_string(),
         "kot".to_string(),
         "vigenere-cipher".to_string(),
+        None,
     )?;
     println!("Decrypted: {}", decryption_result);
     Ok(())
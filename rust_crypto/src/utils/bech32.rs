@@ -0,0 +1,174 @@
+use crate::error::CryptoError;
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+const CHECKSUM_LEN: usize = 6;
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = (checksum & 0x1ff_ffff) << 5 ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if top >> i & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let bytes = hrp.as_bytes();
+    let mut expanded: Vec<u8> = bytes.iter().map(|b| b >> 5).collect();
+    expanded.push(0);
+    expanded.extend(bytes.iter().map(|b| b & 31));
+    expanded
+}
+
+/// Przepakowuje bity między grupami o szerokości `from` i `to`
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        acc = acc << from | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push((acc >> bits & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push((acc << (to - bits) & max) as u8);
+        }
+    } else if bits >= from || acc << (to - bits) & max != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+/// Kodowanie Bech32 (BIP 173) bez limitu 90 znaków, zgodnie z formatem kluczy age
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    let hrp = hrp.to_ascii_lowercase();
+    let values = convert_bits(data, 8, 5, true).unwrap_or_default();
+    let checksum = polymod(
+        hrp_expand(&hrp)
+            .into_iter()
+            .chain(values.iter().copied())
+            .chain([0; CHECKSUM_LEN]),
+    ) ^ 1;
+
+    let mut out = format!("{}1", hrp);
+    out.extend(values.iter().map(|&v| CHARSET[v as usize] as char));
+    out.extend(
+        (0..CHECKSUM_LEN).map(|i| CHARSET[(checksum >> (5 * (5 - i)) & 31) as usize] as char),
+    );
+    out
+}
+
+/// Dekoduje napis Bech32 i zwraca część czytelną (małymi literami) oraz dane
+pub fn decode(text: &str) -> Result<(String, Vec<u8>), CryptoError> {
+    let invalid =
+        |reason: &str| CryptoError::InvalidFormat(format!("Niepoprawny Bech32: {}", reason));
+    if text.chars().any(|c| c.is_ascii_lowercase()) && text.chars().any(|c| c.is_ascii_uppercase())
+    {
+        return Err(invalid("mieszana wielkość liter"));
+    }
+    let lower = text.to_ascii_lowercase();
+    let separator = lower.rfind('1').ok_or_else(|| invalid("brak separatora"))?;
+    let (hrp, rest) = (&lower[..separator], &lower[separator + 1..]);
+    if hrp.is_empty() || rest.len() < CHECKSUM_LEN || hrp.bytes().any(|b| !(33..=126).contains(&b))
+    {
+        return Err(invalid("niepoprawna długość lub część czytelna"));
+    }
+
+    let values = rest
+        .bytes()
+        .map(|b| CHARSET.iter().position(|&c| c == b).map(|v| v as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("niedozwolony znak"))?;
+    if polymod(hrp_expand(hrp).into_iter().chain(values.iter().copied())) != 1 {
+        return Err(invalid("błędna suma kontrolna"));
+    }
+
+    let data = convert_bits(&values[..values.len() - CHECKSUM_LEN], 5, 8, false)
+        .ok_or_else(|| invalid("niepoprawne dopełnienie"))?;
+    Ok((hrp.to_string(), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_vectors::hex;
+
+    /// Wektory poprawne z BIP 173 oraz odbiorca z README narzędzia age
+    #[test]
+    fn decodes_known_answer_vectors() {
+        let vectors: [(&str, &str, &str); 8] = [
+            ("A12UEL5L", "a", ""),
+            ("a12uel5l", "a", ""),
+            (
+                "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+                "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio",
+                "",
+            ),
+            (
+                "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+                "abcdef",
+                "00443214c74254b635cf84653a56d7c675be77df",
+            ),
+            (
+                "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+                "split",
+                "c5f38b70305f519bf66d85fb6cf03058f3dde463ecd7918f2dc743918f2d",
+            ),
+            ("?1ezyfcl", "?", ""),
+            (
+                "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p",
+                "age",
+                "07e22f5e44a542e8dc8e753a42251e1010cc79d192b3f71c5b1c95645209997a",
+            ),
+            (
+                "11qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqc8247j",
+                "1",
+                "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+        for (text, hrp, data) in vectors {
+            let (decoded_hrp, decoded) = decode(text).unwrap();
+            assert_eq!((decoded_hrp.as_str(), decoded.clone()), (hrp, hex(data)));
+            if !text.bytes().any(|b| b.is_ascii_uppercase()) {
+                assert_eq!(encode(hrp, &decoded), text);
+            }
+        }
+        assert_eq!(
+            encode(
+                "AGE",
+                &hex("07e22f5e44a542e8dc8e753a42251e1010cc79d192b3f71c5b1c95645209997a")
+            ),
+            "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p"
+        );
+    }
+
+    /// Wektory niepoprawne z BIP 173
+    #[test]
+    fn rejects_invalid_strings() {
+        for text in [
+            "\x201nwldj5",
+            "pzry9x0s0muk",
+            "1pzry9x0s0muk",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "A1G7SGD8",
+            "10a06t8",
+            "1qzzfhee",
+            "A12uEL5L",
+            "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8q",
+        ] {
+            assert!(decode(text).is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use crate::error::CryptoError;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_SEQUENCE: u8 = 0x30;
+
+/// Koduje długość pola w formie krótkiej (< 128) lub długiej (0x8N + N bajtów)
+fn encode_length(len: usize, out: &mut Vec<u8>) {
+    if len < 0x80 {
+        out.push(len as u8);
+        return;
+    }
+
+    let bytes = len.to_be_bytes();
+    let first = bytes
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(bytes.len() - 1);
+    let significant = &bytes[first..];
+    out.push(0x80 | significant.len() as u8);
+    out.extend_from_slice(significant);
+}
+
+pub fn encode_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 6);
+    out.push(tag);
+    encode_length(content.len(), &mut out);
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn encode_sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    encode_tlv(TAG_SEQUENCE, &items.concat())
+}
+
+/// INTEGER w DER jest liczbą ze znakiem, więc dodatnie wartości z ustawionym
+/// najstarszym bitem muszą zostać poprzedzone bajtem 0x00
+pub fn encode_integer(value: &BigUint) -> Vec<u8> {
+    let mut bytes = if value.is_zero() {
+        vec![0u8]
+    } else {
+        value.to_bytes_be()
+    };
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    encode_tlv(TAG_INTEGER, &bytes)
+}
+
+pub fn encode_small_integer(value: u64) -> Vec<u8> {
+    encode_integer(&BigUint::from(value))
+}
+
+pub fn encode_null() -> Vec<u8> {
+    encode_tlv(TAG_NULL, &[])
+}
+
+pub fn encode_octet_string(content: &[u8]) -> Vec<u8> {
+    encode_tlv(TAG_OCTET_STRING, content)
+}
+
+pub fn encode_bit_string(content: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(content.len() + 1);
+    body.push(0);
+    body.extend_from_slice(content);
+    encode_tlv(TAG_BIT_STRING, &body)
+}
+
+pub fn encode_oid(arcs: &[u64]) -> Vec<u8> {
+    let mut body = Vec::new();
+    let first = arcs.first().copied().unwrap_or(0) * 40 + arcs.get(1).copied().unwrap_or(0);
+    let mut push_base128 = |mut value: u64| {
+        let mut chunk = vec![(value & 0x7f) as u8];
+        value >>= 7;
+        while value > 0 {
+            chunk.push(0x80 | (value & 0x7f) as u8);
+            value >>= 7;
+        }
+        chunk.reverse();
+        body.extend_from_slice(&chunk);
+    };
+
+    push_base128(first);
+    for &arc in arcs.iter().skip(2) {
+        push_base128(arc);
+    }
+    encode_tlv(TAG_OID, &body)
+}
+
+/// Czytnik struktur ASN.1 DER działający na wycinku bajtów
+pub struct DerReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Sprawdza, czy cała struktura została odczytana
+    pub fn finish(&self) -> Result<(), CryptoError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidFormat(
+                "Nadmiarowe bajty na końcu struktury DER".to_string(),
+            ))
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<u8, CryptoError> {
+        let byte = self.data.get(self.pos).copied().ok_or_else(|| {
+            CryptoError::InvalidFormat("Nieoczekiwany koniec danych DER".to_string())
+        })?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_length(&mut self) -> Result<usize, CryptoError> {
+        let first = self.read_byte()?;
+        if first < 0x80 {
+            return Ok(first as usize);
+        }
+
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() {
+            return Err(CryptoError::InvalidFormat(
+                "Nieobsługiwane kodowanie długości DER".to_string(),
+            ));
+        }
+
+        let mut len = 0usize;
+        for _ in 0..count {
+            len = (len << 8) | self.read_byte()? as usize;
+        }
+        // Forma długa tylko dla długości >= 128 i bez zerowego bajtu wiodącego
+        if len < 0x80 || len >> (8 * (count - 1)) == 0 {
+            return Err(CryptoError::InvalidFormat(
+                "Długość DER nie jest zakodowana w najkrótszej postaci".to_string(),
+            ));
+        }
+        Ok(len)
+    }
+
+    /// Odczytuje element TLV i zwraca jego tag oraz zawartość
+    pub fn read_any(&mut self) -> Result<(u8, &'a [u8]), CryptoError> {
+        let tag = self.read_byte()?;
+        let len = self.read_length()?;
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| {
+                CryptoError::InvalidFormat("Długość elementu DER przekracza dane".to_string())
+            })?;
+        let content = &self.data[self.pos..end];
+        self.pos = end;
+        Ok((tag, content))
+    }
+
+    pub fn read_tlv(&mut self, expected_tag: u8) -> Result<&'a [u8], CryptoError> {
+        let (tag, content) = self.read_any()?;
+        if tag != expected_tag {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Oczekiwano tagu DER 0x{:02x}, znaleziono 0x{:02x}",
+                expected_tag, tag
+            )));
+        }
+        Ok(content)
+    }
+
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>, CryptoError> {
+        Ok(DerReader::new(self.read_tlv(TAG_SEQUENCE)?))
+    }
+
+    pub fn read_integer(&mut self) -> Result<BigUint, CryptoError> {
+        let content = self.read_tlv(TAG_INTEGER)?;
+        if content.is_empty() {
+            return Err(CryptoError::InvalidFormat(
+                "Pusta liczba INTEGER w DER".to_string(),
+            ));
+        }
+        if content[0] & 0x80 != 0 {
+            return Err(CryptoError::InvalidFormat(
+                "Ujemne liczby INTEGER nie są obsługiwane".to_string(),
+            ));
+        }
+        Ok(BigUint::from_bytes_be(content))
+    }
+
+    pub fn read_small_integer(&mut self) -> Result<u64, CryptoError> {
+        let value = self.read_integer()?;
+        let digits = value.to_u64_digits();
+        match digits.as_slice() {
+            [] => Ok(0),
+            [single] => Ok(*single),
+            _ => Err(CryptoError::InvalidFormat(
+                "Wartość INTEGER jest zbyt duża".to_string(),
+            )),
+        }
+    }
+
+    pub fn read_null(&mut self) -> Result<(), CryptoError> {
+        let content = self.read_tlv(TAG_NULL)?;
+        if !content.is_empty() {
+            return Err(CryptoError::InvalidFormat(
+                "Niepusty element NULL w DER".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], CryptoError> {
+        self.read_tlv(TAG_OCTET_STRING)
+    }
+
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8], CryptoError> {
+        let content = self.read_tlv(TAG_BIT_STRING)?;
+        match content.split_first() {
+            Some((0, rest)) => Ok(rest),
+            _ => Err(CryptoError::InvalidFormat(
+                "BIT STRING musi zawierać pełne bajty".to_string(),
+            )),
+        }
+    }
+
+    pub fn read_oid(&mut self) -> Result<Vec<u64>, CryptoError> {
+        let content = self.read_tlv(TAG_OID)?;
+        let mut arcs = Vec::new();
+        let mut value = 0u64;
+        for (i, &byte) in content.iter().enumerate() {
+            if value > (u64::MAX >> 7) {
+                return Err(CryptoError::InvalidFormat("Zbyt duży łuk OID".to_string()));
+            }
+            value = (value << 7) | (byte & 0x7f) as u64;
+            if byte & 0x80 == 0 {
+                if arcs.is_empty() {
+                    let first = (value / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(value - first * 40);
+                } else {
+                    arcs.push(value);
+                }
+                value = 0;
+            } else if i == content.len() - 1 {
+                return Err(CryptoError::InvalidFormat(
+                    "Niekompletny identyfikator OID".to_string(),
+                ));
+            }
+        }
+        if arcs.is_empty() {
+            return Err(CryptoError::InvalidFormat(
+                "Pusty identyfikator OID".to_string(),
+            ));
+        }
+        Ok(arcs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element_length(encoded: &[u8]) -> Result<usize, CryptoError> {
+        let mut data = vec![TAG_OCTET_STRING];
+        data.extend_from_slice(encoded);
+        data.resize(data.len() + 0x1_0000, 0);
+        DerReader::new(&data)
+            .read_any()
+            .map(|(_, content)| content.len())
+    }
+
+    #[test]
+    fn lengths_must_use_the_shortest_form() {
+        for len in [0, 0x7f, 0x80, 0xff, 0x100, 0xffff] {
+            let mut encoded = Vec::new();
+            encode_length(len, &mut encoded);
+            assert_eq!(element_length(&encoded).unwrap(), len);
+        }
+
+        for encoded in [
+            &[0x81, 0x05][..],
+            &[0x82, 0x00, 0xff],
+            &[0x82, 0x00, 0x05],
+            &[0x83, 0x00, 0x01, 0x00],
+            &[0x80],
+        ] {
+            assert!(element_length(encoded).is_err(), "{:02x?}", encoded);
+        }
+    }
+}
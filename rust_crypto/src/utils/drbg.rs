@@ -0,0 +1,23 @@
+use crate::error::CryptoError;
+use crate::utils::logger::{LogLevel, log};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+/// Deterministyczny generator ChaCha20 z ziarnem SHA-256(`seed`). Służy
+/// wyłącznie do testów i pokazów: każdy, kto zna ziarno, odtworzy klucz.
+pub fn seeded_rng(seed: &str) -> Result<ChaCha20Rng, CryptoError> {
+    if seed.is_empty() {
+        return Err(CryptoError::InvalidKey(
+            "Ziarno trybu deterministycznego nie może być puste".to_string(),
+        ));
+    }
+    log(
+        LogLevel::WARN,
+        "DRBG",
+        "Tryb deterministyczny: klucz zostanie wyprowadzony z ziarna i NIE jest bezpieczny",
+    );
+    Ok(ChaCha20Rng::from_seed(
+        Sha256::digest(seed.as_bytes()).into(),
+    ))
+}
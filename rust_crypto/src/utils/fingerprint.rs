@@ -0,0 +1,145 @@
+use base64::{Engine as _, engine::general_purpose};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+/// Liczba emoji w skróconym odcisku (6 bitów na emoji)
+const EMOJI_COUNT: usize = 8;
+/// Liczba pięciocyfrowych grup w odcisku numerycznym (5 bajtów na grupę)
+const NUMERIC_GROUPS: usize = 6;
+
+/// 64 symbole używane do zapisu bitów jako emoji (kolejność jak w Matrix SAS)
+pub const EMOJI_TABLE: [(&str, &str); 64] = [
+    ("🐶", "pies"),
+    ("🐱", "kot"),
+    ("🦁", "lew"),
+    ("🐎", "koń"),
+    ("🦄", "jednorożec"),
+    ("🐷", "świnia"),
+    ("🐘", "słoń"),
+    ("🐰", "królik"),
+    ("🐼", "panda"),
+    ("🐓", "kogut"),
+    ("🐧", "pingwin"),
+    ("🐢", "żółw"),
+    ("🐟", "ryba"),
+    ("🐙", "ośmiornica"),
+    ("🦋", "motyl"),
+    ("🌷", "kwiat"),
+    ("🌳", "drzewo"),
+    ("🌵", "kaktus"),
+    ("🍄", "grzyb"),
+    ("🌏", "glob"),
+    ("🌙", "księżyc"),
+    ("☁️", "chmura"),
+    ("🔥", "ogień"),
+    ("🍌", "banan"),
+    ("🍎", "jabłko"),
+    ("🍓", "truskawka"),
+    ("🌽", "kukurydza"),
+    ("🍕", "pizza"),
+    ("🎂", "tort"),
+    ("❤️", "serce"),
+    ("😀", "uśmiech"),
+    ("🤖", "robot"),
+    ("🎩", "kapelusz"),
+    ("👓", "okulary"),
+    ("🔧", "narzędzie"),
+    ("🎅", "mikołaj"),
+    ("👍", "kciuk"),
+    ("☂️", "parasol"),
+    ("⌛", "klepsydra"),
+    ("⏰", "budzik"),
+    ("🎁", "prezent"),
+    ("💡", "żarówka"),
+    ("📕", "książka"),
+    ("✏️", "ołówek"),
+    ("📎", "spinacz"),
+    ("✂️", "nożyczki"),
+    ("🔒", "kłódka"),
+    ("🔑", "klucz"),
+    ("🔨", "młotek"),
+    ("☎️", "telefon"),
+    ("🏁", "flaga"),
+    ("🚂", "pociąg"),
+    ("🚲", "rower"),
+    ("✈️", "samolot"),
+    ("🚀", "rakieta"),
+    ("🏆", "puchar"),
+    ("⚽", "piłka"),
+    ("🎸", "gitara"),
+    ("🎺", "trąbka"),
+    ("🔔", "dzwonek"),
+    ("⚓", "kotwica"),
+    ("🎧", "słuchawki"),
+    ("📁", "teczka"),
+    ("📌", "pinezka"),
+];
+
+/// Zamienia kolejne 6-bitowe fragmenty `bytes` na pozycje tabeli emoji
+pub fn emoji_sequence(bytes: &[u8], count: usize) -> Vec<(&'static str, &'static str)> {
+    (0..count)
+        .map(|i| {
+            let bit = i * 6;
+            let pair =
+                u16::from_be_bytes([bytes[bit / 8], bytes.get(bit / 8 + 1).copied().unwrap_or(0)]);
+            let index = (pair >> (10 - bit % 8)) & 0x3f;
+            EMOJI_TABLE[index as usize]
+        })
+        .collect()
+}
+
+/// Odcisk SHA-256 klucza publicznego w formach do porównywania przez ludzi
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Odcisk z zakodowanej struktury klucza (np. SubjectPublicKeyInfo w DER)
+    pub fn of(encoded_key: &[u8]) -> Self {
+        Self(Sha256::digest(encoded_key).into())
+    }
+
+    /// Bajty szesnastkowo, rozdzielone dwukropkami
+    pub fn to_hex(&self) -> String {
+        self.0
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    /// Zapis w stylu OpenSSH: `SHA256:` i base64 bez dopełnienia
+    pub fn to_base64(&self) -> String {
+        format!("SHA256:{}", general_purpose::STANDARD_NO_PAD.encode(self.0))
+    }
+
+    /// Grupy po pięć cyfr, każda z 5 kolejnych bajtów modulo 100000
+    pub fn to_numeric(&self) -> String {
+        self.0
+            .chunks_exact(5)
+            .take(NUMERIC_GROUPS)
+            .map(|chunk| {
+                let value = chunk
+                    .iter()
+                    .fold(0u64, |acc, &byte| (acc << 8) | byte as u64);
+                format!("{:05}", value % 100_000)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn to_emoji(&self) -> Vec<(&'static str, &'static str)> {
+        emoji_sequence(&self.0, EMOJI_COUNT)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let emoji = self.to_emoji();
+        json!({
+            "algorithm": "SHA-256",
+            "hex": self.to_hex(),
+            "base64": self.to_base64(),
+            "numeric": self.to_numeric(),
+            "emoji": emoji.iter().map(|(symbol, _)| *symbol).collect::<Vec<_>>().join(" "),
+            "words": emoji.iter().map(|(_, word)| *word).collect::<Vec<_>>().join(" ")
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use crate::error::CryptoError;
+use base64::{Engine as _, engine::general_purpose};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+pub fn b64url_encode(bytes: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn b64url_decode(value: &str) -> Result<Vec<u8>, CryptoError> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| CryptoError::InvalidFormat("Nieprawidłowe kodowanie base64url w JWK".into()))
+}
+
+pub fn is_jwk(value: &Value) -> bool {
+    value.get("kty").is_some()
+}
+
+pub fn require_kty(value: &Value, expected: &str) -> Result<(), CryptoError> {
+    match value.get("kty").and_then(Value::as_str) {
+        Some(kty) if kty == expected => Ok(()),
+        Some(kty) => Err(CryptoError::InvalidKey(format!(
+            "Oczekiwano klucza JWK typu '{}', otrzymano '{}'",
+            expected, kty
+        ))),
+        None => Err(CryptoError::InvalidKey("Brak pola 'kty' w JWK".into())),
+    }
+}
+
+/// Odczytuje pole base64url z JWK, zwraca `None`, gdy pole nie występuje
+pub fn optional_member(value: &Value, name: &str) -> Result<Option<Vec<u8>>, CryptoError> {
+    match value.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(encoded)) => b64url_decode(encoded).map(Some),
+        Some(_) => Err(CryptoError::InvalidKey(format!(
+            "Pole '{}' w JWK musi być napisem base64url",
+            name
+        ))),
+    }
+}
+
+pub fn required_member(value: &Value, name: &str) -> Result<Vec<u8>, CryptoError> {
+    optional_member(value, name)?
+        .ok_or_else(|| CryptoError::InvalidKey(format!("Brak wymaganego pola '{}' w JWK", name)))
+}
+
+/// Odcisk klucza JWK zgodnie z RFC 7638: SHA-256 z kanonicznego JSON-a
+/// zawierającego tylko wymagane pola w kolejności leksykograficznej
+pub fn thumbprint(members: &[(&str, &str)]) -> String {
+    let mut sorted = members.to_vec();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+
+    let canonical = sorted
+        .iter()
+        .map(|(name, value)| format!("\"{}\":\"{}\"", name, value))
+        .collect::<Vec<_>>()
+        .join(",");
+    b64url_encode(&Sha256::digest(format!("{{{}}}", canonical).as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 7638, sekcja 3.1 – pola podane w innej kolejności niż kanoniczna
+    #[test]
+    fn rsa_thumbprint_vector() {
+        let n = "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw";
+        assert_eq!(
+            thumbprint(&[("n", n), ("kty", "RSA"), ("e", "AQAB")]),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+
+    /// RFC 8037, dodatek A.3
+    #[test]
+    fn okp_thumbprint_vector() {
+        assert_eq!(
+            thumbprint(&[
+                ("kty", "OKP"),
+                ("crv", "Ed25519"),
+                ("x", "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"),
+            ]),
+            "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+        );
+    }
+
+    #[test]
+    fn base64url_accepts_padding_but_not_the_standard_alphabet() {
+        assert_eq!(b64url_decode("AQAB").unwrap(), [1, 0, 1]);
+        assert_eq!(b64url_decode("_w==").unwrap(), [0xff]);
+        assert!(b64url_decode("/w").is_err());
+    }
+}
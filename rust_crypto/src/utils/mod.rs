@@ -1,2 +1,11 @@
+pub mod bech32;
+pub mod der;
+pub mod drbg;
 pub mod file_handler;
+pub mod fingerprint;
+pub mod jwk;
 pub mod logger;
+pub mod pem;
+pub mod ssh;
+#[cfg(test)]
+pub mod test_vectors;
@@ -0,0 +1,67 @@
+use crate::error::CryptoError;
+use base64::{Engine as _, engine::general_purpose};
+
+const LINE_WIDTH: usize = 64;
+
+pub fn is_pem(text: &str) -> bool {
+    text.trim_start().starts_with("-----BEGIN ")
+}
+
+/// Opakowuje dane DER w pancerz PEM z liniami po 64 znaki (RFC 7468)
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let encoded = general_purpose::STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(LINE_WIDTH) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Dekoduje pierwszy blok PEM i zwraca jego etykietę oraz zawartość DER
+pub fn decode(text: &str) -> Result<(String, Vec<u8>), CryptoError> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    let header = lines.next().unwrap_or_default();
+    let label = header
+        .strip_prefix("-----BEGIN ")
+        .and_then(|rest| rest.strip_suffix("-----"))
+        .ok_or_else(|| CryptoError::InvalidFormat("Brak nagłówka PEM".to_string()))?
+        .to_string();
+    let footer = format!("-----END {}-----", label);
+
+    let mut body = String::new();
+    let mut closed = false;
+    for line in lines {
+        if line == footer {
+            closed = true;
+            break;
+        }
+        // Nagłówki w stylu RFC 1421 (np. Proc-Type) nie są obsługiwane
+        if line.contains(':') {
+            return Err(CryptoError::InvalidFormat(format!(
+                "Nieobsługiwany nagłówek PEM: {}",
+                line
+            )));
+        }
+        body.push_str(line);
+    }
+
+    if !closed {
+        return Err(CryptoError::InvalidFormat(format!(
+            "Brak stopki PEM dla etykiety '{}'",
+            label
+        )));
+    }
+
+    let der = general_purpose::STANDARD
+        .decode(body.as_bytes())
+        .map_err(|err| {
+            CryptoError::InvalidFormat(format!("Nieprawidłowe kodowanie base64 w PEM: {}", err))
+        })?;
+    Ok((label, der))
+}
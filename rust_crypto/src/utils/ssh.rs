@@ -0,0 +1,45 @@
+use crate::error::CryptoError;
+
+/// Napis w formacie protokołu SSH (RFC 4251): długość u32 big-endian i bajty
+pub fn encode_string(content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 4);
+    out.extend_from_slice(&(content.len() as u32).to_be_bytes());
+    out.extend_from_slice(content);
+    out
+}
+
+/// Czytnik pól `uint32` i `string` struktur OpenSSH
+pub struct SshReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SshReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], CryptoError> {
+        if self.data.len() < len {
+            return Err(CryptoError::InvalidFormat(
+                "Nieoczekiwany koniec danych SSH".to_string(),
+            ));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, CryptoError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn read_string(&mut self) -> Result<&'a [u8], CryptoError> {
+        let len = self.read_u32()? as usize;
+        self.read_bytes(len)
+    }
+}
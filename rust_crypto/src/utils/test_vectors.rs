@@ -0,0 +1,10 @@
+/// Dekoduje wektor testowy zapisany szesnastkowo; białe znaki są pomijane,
+/// żeby długie wartości z RFC dało się łamać na kilka linii
+pub fn hex(text: &str) -> Vec<u8> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    assert!(digits.len().is_multiple_of(2), "odd number of hex digits");
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
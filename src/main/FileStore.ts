@@ -8,6 +8,7 @@ export type FileData = {
 export type ProcessOptions = {
   key: string
   algorithm: string
+  passphrase?: string
 }
 
 class FileStore {
@@ -6,12 +6,115 @@ import path from 'path'
 import { Result, ok, err } from '../shared/result-util'
 
 const require = createRequire(import.meta.url)
+
+type RsaKeygenProgress = { candidatesTested: number; primesFound: number }
+type RsaKeygenOptions = {
+  bits: number
+  format?: string
+  educational?: boolean
+  lucasTest?: boolean
+  passphrase?: string
+  primes?: number
+  publicExponent?: number
+  seed?: string
+}
+type RsaKeygenRequest = Pick<
+  RsaKeygenOptions,
+  'bits' | 'educational' | 'passphrase' | 'primes' | 'publicExponent' | 'seed'
+>
+type KeygenAbortHandle = { abort: () => void; readonly aborted: boolean }
+type KeyFingerprint = {
+  algorithm: string
+  hex: string
+  base64: string
+  numeric: string
+  emoji: string
+  words: string
+}
+type ShortAuthString = { decimal: string; emoji: string; words: string }
+type RsaValidationReport = {
+  valid: boolean
+  checks: { check: string; status: 'passed' | 'failed' | 'skipped'; message: string }[]
+}
+
+type RsaAuditReport = {
+  total: number
+  broken: number
+  keys: {
+    index: number
+    bits: number
+    broken: boolean
+    attacks: {
+      attack: string
+      status: 'broken' | 'resisted' | 'skipped'
+      message: string
+    }[]
+    factors?: [string, string]
+    d?: string
+  }[]
+}
+type RecoveredMessage = { blocks: string[]; base64: string; text: string | null }
+type ChosenCiphertextReport = {
+  forged: string[]
+  factors: string[]
+  oracle: string[]
+  recovered: RecoveredMessage
+}
+type AgeIdentity = { identity: string; recipient: string }
+
 const rustCrypto = require('../../rust_crypto/index.node') as {
-  encrypt: (filepath: string, key: string, algorithm: string) => string
-  decrypt: (filePath: string, key: string, algorithm: string) => string
-  generateRsaKeypair: (bits: number) => string
-  generateEcdhKeypair: () => string
-  computeEcdhSharedSecret: (privateKey: string, publicKey: string) => string
+  encrypt: (filepath: string, key: string, algorithm: string, passphrase?: string) => string
+  decrypt: (filePath: string, key: string, algorithm: string, passphrase?: string) => string
+  generateRsaKeypair: (
+    options: RsaKeygenOptions,
+    onProgress?: (progress: RsaKeygenProgress) => void,
+    abort?: KeygenAbortHandle
+  ) => Promise<string>
+  KeygenAbortHandle: new () => KeygenAbortHandle
+  validateRsaKey: (key: string, educational?: boolean, passphrase?: string) => string
+  rsaKeyFingerprint: (key: string, passphrase?: string) => string
+  auditRsaKeys: (keys: string[], passphrase?: string) => Promise<string>
+  rsaHastadAttack: (keys: string[], ciphertexts: string[]) => string
+  rsaCommonModulusAttack: (
+    firstKey: string,
+    firstCiphertext: string,
+    secondKey: string,
+    secondCiphertext: string
+  ) => string
+  rsaSmallMessageAttack: (key: string, ciphertext: string) => string
+  rsaChosenCiphertextAttack: (
+    publicKey: string,
+    ciphertext: string,
+    oracleKey: string,
+    passphrase?: string
+  ) => string
+  generateEcdhKeypair: (
+    format?: string,
+    passphrase?: string,
+    seed?: string,
+    curve?: string
+  ) => string
+  computeEcdhSharedSecret: (
+    privateKey: string,
+    publicKey: string,
+    passphrase?: string,
+    curve?: string
+  ) => string
+  ecdhPublicKeyFingerprint: (publicKey: string, curve?: string) => string
+  computeEcdhSas: (
+    privateKey: string,
+    publicKey: string,
+    passphrase?: string,
+    curve?: string,
+    peerCommitment?: string
+  ) => string
+  generateEd25519Keypair: (format?: string, passphrase?: string, seed?: string) => string
+  ed25519PublicKeyFingerprint: (publicKey: string) => string
+  ed25519Sign: (privateKey: string, message: string, passphrase?: string) => string
+  ed25519SignFile: (privateKey: string, filePath: string, passphrase?: string) => string
+  ed25519Verify: (publicKey: string, message: string, signature: string) => boolean
+  ed25519VerifyFile: (publicKey: string, filePath: string, signature: string) => boolean
+  generateAgeIdentity: () => string
   exportLogs: () => string
   clearLogs: () => void
 }
@@ -19,12 +122,31 @@ const {
   encrypt,
   decrypt,
   generateRsaKeypair,
+  KeygenAbortHandle,
+  validateRsaKey,
+  rsaKeyFingerprint,
+  auditRsaKeys,
+  rsaHastadAttack,
+  rsaCommonModulusAttack,
+  rsaSmallMessageAttack,
+  rsaChosenCiphertextAttack,
   generateEcdhKeypair,
   computeEcdhSharedSecret,
+  ecdhPublicKeyFingerprint,
+  computeEcdhSas,
+  generateEd25519Keypair,
+  ed25519PublicKeyFingerprint,
+  ed25519Sign,
+  ed25519SignFile,
+  ed25519Verify,
+  ed25519VerifyFile,
+  generateAgeIdentity,
   exportLogs,
   clearLogs
 } = rustCrypto
 
+let activeRsaKeygen: KeygenAbortHandle | null = null
+
 export function setupFileHandlers(app: Electron.App): void {
   ipcMain.handle(
     'file:store',
@@ -98,11 +220,11 @@ export function setupFileHandlers(app: Electron.App): void {
         console.log(`Starting ${operation} with algorithm: ${options.algorithm}`)
         switch (operation) {
           case 'encrypt':
-            result = encrypt(fileData.path, options.key, options.algorithm)
+            result = encrypt(fileData.path, options.key, options.algorithm, options.passphrase)
             console.log('Encrypt completed, result:', result)
             break
           case 'decrypt':
-            result = decrypt(fileData.path, options.key, options.algorithm)
+            result = decrypt(fileData.path, options.key, options.algorithm, options.passphrase)
             console.log('Decrypt completed, result:', result)
             break
           default:
@@ -221,23 +343,167 @@ export function setupFileHandlers(app: Electron.App): void {
 
   ipcMain.handle(
     'rsa:generateKeypair',
-    async (_event, bits: number): Promise<Result<string, string>> => {
-      try {
-        const numericBits = Number.isFinite(bits) ? Math.floor(bits) : NaN
-        if (!Number.isFinite(numericBits) || numericBits < 16) {
-          return err('Długość klucza RSA musi być liczbą większą lub równą 16.')
-        }
+    async (
+      event,
+      { bits, educational, passphrase, primes, publicExponent, seed }: RsaKeygenRequest
+    ): Promise<Result<string, string>> => {
+      const numericBits = Number.isFinite(bits) ? Math.floor(bits) : NaN
+      const minBits = educational ? 32 : 2048
+      if (!Number.isFinite(numericBits) || numericBits < minBits) {
+        return err(`Długość klucza RSA musi być liczbą większą lub równą ${minBits}.`)
+      }
 
-        const safeBits = Math.max(16, Math.min(8192, numericBits))
-        const payload = generateRsaKeypair(safeBits)
+      activeRsaKeygen?.abort()
+      const handle = new KeygenAbortHandle()
+      activeRsaKeygen = handle
+
+      try {
+        const safeBits = Math.min(8192, numericBits)
+        const payload = await generateRsaKeypair(
+          {
+            bits: safeBits,
+            educational: educational === true,
+            passphrase: passphrase || undefined,
+            primes,
+            publicExponent,
+            seed: seed || undefined
+          },
+          (progress) => {
+            if (!event.sender.isDestroyed()) {
+              event.sender.send('rsa:keygenProgress', progress)
+            }
+          },
+          handle
+        )
         return ok(payload)
       } catch (error) {
+        if (handle.aborted) {
+          return err('Generowanie kluczy RSA zostało przerwane')
+        }
         console.error('Error generating RSA keypair:', error)
-        return err('Nie udało się wygenerować kluczy RSA')
+        return err(
+          error instanceof Error ? error.message : 'Nie udało się wygenerować kluczy RSA'
+        )
+      } finally {
+        if (activeRsaKeygen === handle) {
+          activeRsaKeygen = null
+        }
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'rsa:validateKey',
+    async (
+      _event,
+      key: string,
+      passphrase?: string
+    ): Promise<Result<RsaValidationReport, string>> => {
+      try {
+        // Walidacja przy wczytywaniu dopuszcza krótkie klucze jak tryb edukacyjny
+        const report = validateRsaKey(key, true, passphrase || undefined)
+        return ok(JSON.parse(report) as RsaValidationReport)
+      } catch (error) {
+        console.error('Error validating RSA key:', error)
+        return err(error instanceof Error ? error.message : 'Nie udało się sprawdzić klucza RSA')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'rsa:fingerprint',
+    async (_event, key: string, passphrase?: string): Promise<Result<KeyFingerprint, string>> => {
+      try {
+        return ok(JSON.parse(rsaKeyFingerprint(key, passphrase || undefined)) as KeyFingerprint)
+      } catch (error) {
+        console.error('Error computing RSA fingerprint:', error)
+        return err(
+          error instanceof Error ? error.message : 'Nie udało się obliczyć odcisku klucza'
+        )
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'rsa:audit',
+    async (
+      _event,
+      keys: string[],
+      passphrase?: string
+    ): Promise<Result<RsaAuditReport, string>> => {
+      try {
+        const report = await auditRsaKeys(keys, passphrase || undefined)
+        return ok(JSON.parse(report) as RsaAuditReport)
+      } catch (error) {
+        console.error('Error auditing RSA keys:', error)
+        return err(
+          error instanceof Error ? error.message : 'Nie udało się przeprowadzić audytu kluczy'
+        )
       }
     }
   )
 
+  const runAttack = <T>(name: string, attack: () => string): Result<T, string> => {
+    try {
+      return ok(JSON.parse(attack()) as T)
+    } catch (error) {
+      console.error(`Error running RSA ${name} attack:`, error)
+      return err(error instanceof Error ? error.message : 'Atak nie powiódł się')
+    }
+  }
+
+  ipcMain.handle(
+    'rsaAttack:hastad',
+    async (
+      _event,
+      keys: string[],
+      ciphertexts: string[]
+    ): Promise<Result<RecoveredMessage, string>> =>
+      runAttack('Håstad', () => rsaHastadAttack(keys, ciphertexts))
+  )
+
+  ipcMain.handle(
+    'rsaAttack:commonModulus',
+    async (
+      _event,
+      firstKey: string,
+      firstCiphertext: string,
+      secondKey: string,
+      secondCiphertext: string
+    ): Promise<Result<RecoveredMessage, string>> =>
+      runAttack('common modulus', () =>
+        rsaCommonModulusAttack(firstKey, firstCiphertext, secondKey, secondCiphertext)
+      )
+  )
+
+  ipcMain.handle(
+    'rsaAttack:smallMessage',
+    async (_event, key: string, ciphertext: string): Promise<Result<RecoveredMessage, string>> =>
+      runAttack('small message', () => rsaSmallMessageAttack(key, ciphertext))
+  )
+
+  ipcMain.handle(
+    'rsaAttack:chosenCiphertext',
+    async (
+      _event,
+      publicKey: string,
+      ciphertext: string,
+      oracleKey: string,
+      passphrase?: string
+    ): Promise<Result<ChosenCiphertextReport, string>> =>
+      runAttack('chosen ciphertext', () =>
+        rsaChosenCiphertextAttack(publicKey, ciphertext, oracleKey, passphrase || undefined)
+      )
+  )
+
+  ipcMain.handle('rsa:cancelKeygen', async (): Promise<Result<boolean, string>> => {
+    if (!activeRsaKeygen) {
+      return ok(false)
+    }
+    activeRsaKeygen.abort()
+    return ok(true)
+  })
+
   ipcMain.handle(
     'rsa:saveKey',
     async (_event, payload: string, defaultName?: string): Promise<Result<boolean, string>> => {
@@ -276,21 +542,46 @@ export function setupFileHandlers(app: Electron.App): void {
     }
   )
 
-  ipcMain.handle('ecdh:generateKeypair', async (): Promise<Result<string, string>> => {
-    try {
-      const payload = generateEcdhKeypair()
-      return ok(payload)
-    } catch (error) {
-      console.error('Error generating ECDH keypair:', error)
-      return err('Nie udało się wygenerować pary kluczy ECDH')
+  ipcMain.handle(
+    'ecdh:generateKeypair',
+    async (
+      _event,
+      passphrase?: string,
+      seed?: string,
+      curve?: string,
+      format?: string
+    ): Promise<Result<string, string>> => {
+      try {
+        const payload = generateEcdhKeypair(
+          format,
+          passphrase || undefined,
+          seed || undefined,
+          curve
+        )
+        return ok(payload)
+      } catch (error) {
+        console.error('Error generating ECDH keypair:', error)
+        return err('Nie udało się wygenerować pary kluczy ECDH')
+      }
     }
-  })
+  )
 
   ipcMain.handle(
     'ecdh:computeSharedSecret',
-    async (_event, privateKey: string, publicKey: string): Promise<Result<string, string>> => {
+    async (
+      _event,
+      privateKey: string,
+      publicKey: string,
+      passphrase?: string,
+      curve?: string
+    ): Promise<Result<string, string>> => {
       try {
-        const secret = computeEcdhSharedSecret(privateKey, publicKey)
+        const secret = computeEcdhSharedSecret(
+          privateKey,
+          publicKey,
+          passphrase || undefined,
+          curve
+        )
         return ok(secret)
       } catch (error) {
         console.error('Error computing ECDH shared secret:', error)
@@ -299,6 +590,153 @@ export function setupFileHandlers(app: Electron.App): void {
     }
   )
 
+  ipcMain.handle(
+    'ecdh:fingerprint',
+    async (_event, publicKey: string, curve?: string): Promise<Result<KeyFingerprint, string>> => {
+      try {
+        return ok(JSON.parse(ecdhPublicKeyFingerprint(publicKey, curve)) as KeyFingerprint)
+      } catch (error) {
+        console.error('Error computing ECDH fingerprint:', error)
+        return err('Nieprawidłowy klucz publiczny')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ecdh:sas',
+    async (
+      _event,
+      privateKey: string,
+      publicKey: string,
+      passphrase?: string,
+      curve?: string,
+      peerCommitment?: string
+    ): Promise<Result<ShortAuthString, string>> => {
+      try {
+        const sas = computeEcdhSas(
+          privateKey,
+          publicKey,
+          passphrase || undefined,
+          curve,
+          peerCommitment || undefined
+        )
+        return ok(JSON.parse(sas) as ShortAuthString)
+      } catch (error) {
+        console.error('Error computing ECDH SAS:', error)
+        if (error instanceof Error && error.message.includes('commitment')) {
+          return err('Klucz publiczny rozmówcy nie zgadza się z jego zobowiązaniem')
+        }
+        return err('Nie udało się obliczyć ciągu uwierzytelniającego')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:generateKeypair',
+    async (
+      _event,
+      format?: string,
+      passphrase?: string,
+      seed?: string
+    ): Promise<Result<string, string>> => {
+      try {
+        return ok(generateEd25519Keypair(format, passphrase || undefined, seed || undefined))
+      } catch (error) {
+        console.error('Error generating Ed25519 keypair:', error)
+        return err(
+          error instanceof Error ? error.message : 'Nie udało się wygenerować kluczy Ed25519'
+        )
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:fingerprint',
+    async (_event, publicKey: string): Promise<Result<KeyFingerprint, string>> => {
+      try {
+        return ok(JSON.parse(ed25519PublicKeyFingerprint(publicKey)) as KeyFingerprint)
+      } catch (error) {
+        console.error('Error computing Ed25519 fingerprint:', error)
+        return err('Nieprawidłowy klucz publiczny')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:sign',
+    async (
+      _event,
+      privateKey: string,
+      message: string,
+      passphrase?: string
+    ): Promise<Result<string, string>> => {
+      try {
+        return ok(ed25519Sign(privateKey, message, passphrase || undefined))
+      } catch (error) {
+        console.error('Error signing with Ed25519:', error)
+        return err(error instanceof Error ? error.message : 'Nie udało się podpisać wiadomości')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:signFile',
+    async (_event, privateKey: string, passphrase?: string): Promise<Result<string, string>> => {
+      const fileData = fileStore.getFileData()
+      if (!fileData) {
+        return err('Brak zapisanego pliku')
+      }
+      try {
+        return ok(ed25519SignFile(privateKey, fileData.path, passphrase || undefined))
+      } catch (error) {
+        console.error('Error signing file with Ed25519:', error)
+        return err(error instanceof Error ? error.message : 'Nie udało się podpisać pliku')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:verify',
+    async (
+      _event,
+      publicKey: string,
+      message: string,
+      signature: string
+    ): Promise<Result<boolean, string>> => {
+      try {
+        return ok(ed25519Verify(publicKey, message, signature))
+      } catch (error) {
+        console.error('Error verifying Ed25519 signature:', error)
+        return err(error instanceof Error ? error.message : 'Nie udało się zweryfikować podpisu')
+      }
+    }
+  )
+
+  ipcMain.handle(
+    'ed25519:verifyFile',
+    async (_event, publicKey: string, signature: string): Promise<Result<boolean, string>> => {
+      const fileData = fileStore.getFileData()
+      if (!fileData) {
+        return err('Brak zapisanego pliku')
+      }
+      try {
+        return ok(ed25519VerifyFile(publicKey, fileData.path, signature))
+      } catch (error) {
+        console.error('Error verifying Ed25519 file signature:', error)
+        return err(error instanceof Error ? error.message : 'Nie udało się zweryfikować podpisu')
+      }
+    }
+  )
+
+  ipcMain.handle('age:generateIdentity', async (): Promise<Result<AgeIdentity, string>> => {
+    try {
+      return ok(JSON.parse(generateAgeIdentity()) as AgeIdentity)
+    } catch (error) {
+      console.error('Error generating age identity:', error)
+      return err('Nie udało się wygenerować tożsamości age')
+    }
+  })
+
   ipcMain.handle('logs:get', async (): Promise<Result<string, string>> => {
     try {
       const logs = exportLogs()
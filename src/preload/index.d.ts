@@ -9,6 +9,67 @@ type FileData = {
   length?: number
 }
 
+type RsaKeygenProgress = {
+  candidatesTested: number
+  primesFound: number
+}
+
+type RsaKeygenRequest = {
+  bits: number
+  educational?: boolean
+  passphrase?: string
+  primes?: number
+  publicExponent?: number
+  seed?: string
+}
+
+type EcdhCurve = 'x25519' | 'x448' | 'p256' | 'p384'
+
+type KeyFingerprint = {
+  algorithm: string
+  hex: string
+  base64: string
+  numeric: string
+  emoji: string
+  words: string
+}
+
+type ShortAuthString = {
+  decimal: string
+  emoji: string
+  words: string
+}
+
+type RsaValidationReport = {
+  valid: boolean
+  checks: { check: string; status: 'passed' | 'failed' | 'skipped'; message: string }[]
+}
+
+type RsaAuditReport = {
+  total: number
+  broken: number
+  keys: {
+    index: number
+    bits: number
+    broken: boolean
+    attacks: {
+      attack: string
+      status: 'broken' | 'resisted' | 'skipped'
+      message: string
+    }[]
+    factors?: [string, string]
+    d?: string
+  }[]
+}
+type RecoveredMessage = { blocks: string[]; base64: string; text: string | null }
+type ChosenCiphertextReport = {
+  forged: string[]
+  factors: string[]
+  oracle: string[]
+  recovered: RecoveredMessage
+}
+type AgeIdentity = { identity: string; recipient: string }
+
 declare global {
   interface Window {
     electron: ElectronAPI
@@ -25,15 +86,80 @@ declare global {
         preview: (filePath?: string) => Promise<Result<boolean, string>>
       }
       rsa: {
-        generateKeypair: (bits: number) => Promise<Result<string, string>>
+        generateKeypair: (options: RsaKeygenRequest) => Promise<Result<string, string>>
+        cancelKeygen: () => Promise<Result<boolean, string>>
+        validateKey: (
+          key: string,
+          passphrase?: string
+        ) => Promise<Result<RsaValidationReport, string>>
+        fingerprint: (key: string, passphrase?: string) => Promise<Result<KeyFingerprint, string>>
+        audit: (keys: string[], passphrase?: string) => Promise<Result<RsaAuditReport, string>>
+        onKeygenProgress: (callback: (progress: RsaKeygenProgress) => void) => () => void
         saveKey: (payload: string, defaultName?: string) => Promise<Result<boolean, string>>
       }
+      rsaAttack: {
+        hastad: (keys: string[], ciphertexts: string[]) => Promise<Result<RecoveredMessage, string>>
+        commonModulus: (
+          firstKey: string,
+          firstCiphertext: string,
+          secondKey: string,
+          secondCiphertext: string
+        ) => Promise<Result<RecoveredMessage, string>>
+        smallMessage: (key: string, ciphertext: string) => Promise<Result<RecoveredMessage, string>>
+        chosenCiphertext: (
+          publicKey: string,
+          ciphertext: string,
+          oracleKey: string,
+          passphrase?: string
+        ) => Promise<Result<ChosenCiphertextReport, string>>
+      }
       ecdh: {
-        generateKeypair: () => Promise<Result<string, string>>
+        generateKeypair: (
+          passphrase?: string,
+          seed?: string,
+          curve?: EcdhCurve,
+          format?: 'base64' | 'compressed' | 'jwk'
+        ) => Promise<Result<string, string>>
         computeSharedSecret: (
           privateKey: string,
-          publicKey: string
+          publicKey: string,
+          passphrase?: string,
+          curve?: EcdhCurve
         ) => Promise<Result<string, string>>
+        fingerprint: (
+          publicKey: string,
+          curve?: EcdhCurve
+        ) => Promise<Result<KeyFingerprint, string>>
+        sas: (
+          privateKey: string,
+          publicKey: string,
+          passphrase?: string,
+          curve?: EcdhCurve,
+          peerCommitment?: string
+        ) => Promise<Result<ShortAuthString, string>>
+      }
+      ed25519: {
+        generateKeypair: (
+          format?: 'base64' | 'pem' | 'openssh',
+          passphrase?: string,
+          seed?: string
+        ) => Promise<Result<string, string>>
+        fingerprint: (publicKey: string) => Promise<Result<KeyFingerprint, string>>
+        sign: (
+          privateKey: string,
+          message: string,
+          passphrase?: string
+        ) => Promise<Result<string, string>>
+        signFile: (privateKey: string, passphrase?: string) => Promise<Result<string, string>>
+        verify: (
+          publicKey: string,
+          message: string,
+          signature: string
+        ) => Promise<Result<boolean, string>>
+        verifyFile: (publicKey: string, signature: string) => Promise<Result<boolean, string>>
+      }
+      age: {
+        generateIdentity: () => Promise<Result<AgeIdentity, string>>
       }
       logs: {
         get: () => Promise<Result<string, string>>
@@ -1,7 +1,17 @@
-import { contextBridge, ipcRenderer } from 'electron'
+import { contextBridge, ipcRenderer, IpcRendererEvent } from 'electron'
 import { electronAPI } from '@electron-toolkit/preload'
 import { ProcessOptions } from 'src/main/FileStore'
 
+type RsaKeygenProgress = { candidatesTested: number; primesFound: number }
+type RsaKeygenRequest = {
+  bits: number
+  educational?: boolean
+  passphrase?: string
+  primes?: number
+  publicExponent?: number
+  seed?: string
+}
+
 // Custom APIs for renderer
 const api = {
   file: {
@@ -15,14 +25,86 @@ const api = {
     preview: (filePath?: string) => ipcRenderer.invoke('file:preview', filePath)
   },
   rsa: {
-    generateKeypair: (bits: number) => ipcRenderer.invoke('rsa:generateKeypair', bits),
+    generateKeypair: (options: RsaKeygenRequest) =>
+      ipcRenderer.invoke('rsa:generateKeypair', options),
+    cancelKeygen: () => ipcRenderer.invoke('rsa:cancelKeygen'),
+    validateKey: (key: string, passphrase?: string) =>
+      ipcRenderer.invoke('rsa:validateKey', key, passphrase),
+    fingerprint: (key: string, passphrase?: string) =>
+      ipcRenderer.invoke('rsa:fingerprint', key, passphrase),
+    audit: (keys: string[], passphrase?: string) =>
+      ipcRenderer.invoke('rsa:audit', keys, passphrase),
+    onKeygenProgress: (callback: (progress: RsaKeygenProgress) => void) => {
+      const listener = (_event: IpcRendererEvent, progress: RsaKeygenProgress): void =>
+        callback(progress)
+      ipcRenderer.on('rsa:keygenProgress', listener)
+      return () => {
+        ipcRenderer.removeListener('rsa:keygenProgress', listener)
+      }
+    },
     saveKey: (payload: string, defaultName?: string) =>
       ipcRenderer.invoke('rsa:saveKey', payload, defaultName)
   },
+  rsaAttack: {
+    hastad: (keys: string[], ciphertexts: string[]) =>
+      ipcRenderer.invoke('rsaAttack:hastad', keys, ciphertexts),
+    commonModulus: (
+      firstKey: string,
+      firstCiphertext: string,
+      secondKey: string,
+      secondCiphertext: string
+    ) =>
+      ipcRenderer.invoke(
+        'rsaAttack:commonModulus',
+        firstKey,
+        firstCiphertext,
+        secondKey,
+        secondCiphertext
+      ),
+    smallMessage: (key: string, ciphertext: string) =>
+      ipcRenderer.invoke('rsaAttack:smallMessage', key, ciphertext),
+    chosenCiphertext: (
+      publicKey: string,
+      ciphertext: string,
+      oracleKey: string,
+      passphrase?: string
+    ) =>
+      ipcRenderer.invoke('rsaAttack:chosenCiphertext', publicKey, ciphertext, oracleKey, passphrase)
+  },
   ecdh: {
-    generateKeypair: () => ipcRenderer.invoke('ecdh:generateKeypair'),
-    computeSharedSecret: (privateKey: string, publicKey: string) =>
-      ipcRenderer.invoke('ecdh:computeSharedSecret', privateKey, publicKey)
+    generateKeypair: (passphrase?: string, seed?: string, curve?: string, format?: string) =>
+      ipcRenderer.invoke('ecdh:generateKeypair', passphrase, seed, curve, format),
+    computeSharedSecret: (
+      privateKey: string,
+      publicKey: string,
+      passphrase?: string,
+      curve?: string
+    ) => ipcRenderer.invoke('ecdh:computeSharedSecret', privateKey, publicKey, passphrase, curve),
+    fingerprint: (publicKey: string, curve?: string) =>
+      ipcRenderer.invoke('ecdh:fingerprint', publicKey, curve),
+    sas: (
+      privateKey: string,
+      publicKey: string,
+      passphrase?: string,
+      curve?: string,
+      peerCommitment?: string
+    ) => ipcRenderer.invoke('ecdh:sas', privateKey, publicKey, passphrase, curve, peerCommitment)
+  },
+  ed25519: {
+    generateKeypair: (format?: string, passphrase?: string, seed?: string) =>
+      ipcRenderer.invoke('ed25519:generateKeypair', format, passphrase, seed),
+    fingerprint: (publicKey: string) => ipcRenderer.invoke('ed25519:fingerprint', publicKey),
+    sign: (privateKey: string, message: string, passphrase?: string) =>
+      ipcRenderer.invoke('ed25519:sign', privateKey, message, passphrase),
+    signFile: (privateKey: string, passphrase?: string) =>
+      ipcRenderer.invoke('ed25519:signFile', privateKey, passphrase),
+    verify: (publicKey: string, message: string, signature: string) =>
+      ipcRenderer.invoke('ed25519:verify', publicKey, message, signature),
+    verifyFile: (publicKey: string, signature: string) =>
+      ipcRenderer.invoke('ed25519:verifyFile', publicKey, signature)
+  },
+  age: {
+    generateIdentity: () => ipcRenderer.invoke('age:generateIdentity')
   },
   logs: {
     get: () => ipcRenderer.invoke('logs:get'),
@@ -4,6 +4,8 @@ import AlgorithmsPage from './pages/algorithms'
 import AlgorithmPage from './pages/algorithm'
 import ResultPage from './pages/result'
 import KeyExchangePage from './pages/key-exchange'
+import RsaAttacksPage from './pages/rsa-attacks'
+import SignaturesPage from './pages/signatures'
 
 function App(): React.JSX.Element {
   return (
@@ -12,6 +14,8 @@ function App(): React.JSX.Element {
       <Route path="/encrypt" element={<HomePage />} />
       <Route path="/decrypt" element={<HomePage />} />
       <Route path="/key-exchange" element={<KeyExchangePage />} />
+      <Route path="/signatures" element={<SignaturesPage />} />
+      <Route path="/rsa-attacks" element={<RsaAttacksPage />} />
       <Route path="/encrypt/algorithms" element={<AlgorithmsPage operation="encrypt" />} />
       <Route path="/decrypt/algorithms" element={<AlgorithmsPage operation="decrypt" />} />
       <Route path="/encrypt/algorithm/:id" element={<AlgorithmPage operation="encrypt" />} />
@@ -5,11 +5,44 @@ import { CopyButton } from './CopyButton'
 import { toast } from 'sonner'
 import { PasteButton } from './PasteButton'
 
+type KeyFingerprint = { emoji: string; words: string; numeric: string }
+type ShortAuthString = { decimal: string; emoji: string; words: string }
+type EcdhCurve = 'x25519' | 'x448' | 'p256' | 'p384'
+
+const CURVES: { id: EcdhCurve; name: string }[] = [
+  { id: 'x25519', name: 'X25519 (Curve25519)' },
+  { id: 'x448', name: 'X448 (Curve448)' },
+  { id: 'p256', name: 'NIST P-256' },
+  { id: 'p384', name: 'NIST P-384' }
+]
+
+function FingerprintLine({ fingerprint }: { fingerprint: KeyFingerprint }): React.ReactNode {
+  return (
+    <p className="text-xs text-text-subtle mt-1" title={fingerprint.words}>
+      Odcisk: <span className="text-base">{fingerprint.emoji}</span>{' '}
+      <code className="font-mono">{fingerprint.numeric}</code>
+    </p>
+  )
+}
+
 export default function ECDH(): React.ReactNode {
-  const [keypair, setKeypair] = useState<{ private: string; public: string } | null>(null)
+  const [keypair, setKeypair] = useState<{
+    private: string
+    public: string
+    fingerprint?: KeyFingerprint
+    commitment?: string
+    deterministic?: boolean
+  } | null>(null)
   const [peerPublicKey, setPeerPublicKey] = useState<string>('')
+  const [peerCommitment, setPeerCommitment] = useState<string>('')
+  const [peerFingerprint, setPeerFingerprint] = useState<KeyFingerprint | null>(null)
   const [sharedSecret, setSharedSecret] = useState<string | null>(null)
+  const [sas, setSas] = useState<ShortAuthString | null>(null)
   const [error, setError] = useState<string | null>(null)
+  const [passphrase, setPassphrase] = useState<string>('')
+  const [seed, setSeed] = useState<string>('')
+  const [curve, setCurve] = useState<EcdhCurve>('x25519')
+  const [compressed, setCompressed] = useState(false)
 
   useEffect(() => {
     const keyDownHandler = (event: KeyboardEvent): void => {
@@ -32,14 +65,44 @@ export default function ECDH(): React.ReactNode {
     }
   }, [keypair?.public])
 
+  useEffect(() => {
+    let cancelled = false
+    if (!peerPublicKey.trim()) {
+      setPeerFingerprint(null)
+      return
+    }
+    window.api.ecdh.fingerprint(peerPublicKey.trim(), curve).then((result) => {
+      if (!cancelled) {
+        setPeerFingerprint(result.ok ? result.value : null)
+      }
+    })
+    return () => {
+      cancelled = true
+    }
+  }, [peerPublicKey, curve])
+
+  const selectCurve = (value: EcdhCurve): void => {
+    setCurve(value)
+    setKeypair(null)
+    setSharedSecret(null)
+    setSas(null)
+    setError(null)
+  }
+
   const generateKeys = async (): Promise<void> => {
     try {
-      const result = await window.api.ecdh.generateKeypair()
+      const result = await window.api.ecdh.generateKeypair(
+        passphrase || undefined,
+        seed || undefined,
+        curve,
+        compressed && (curve === 'p256' || curve === 'p384') ? 'compressed' : undefined
+      )
       if (result.ok) {
         const keys = JSON.parse(result.value)
         setKeypair(keys)
         setError(null)
         setSharedSecret(null)
+        setSas(null)
       } else {
         setError(result.error || 'Błąd generowania kluczy')
       }
@@ -53,9 +116,28 @@ export default function ECDH(): React.ReactNode {
     if (!keypair || !peerPublicKey) return
 
     try {
-      const result = await window.api.ecdh.computeSharedSecret(keypair.private, peerPublicKey)
+      const result = await window.api.ecdh.computeSharedSecret(
+        keypair.private,
+        peerPublicKey,
+        passphrase || undefined,
+        curve
+      )
       if (result.ok) {
+        const sasResult = await window.api.ecdh.sas(
+          keypair.private,
+          peerPublicKey,
+          passphrase || undefined,
+          curve,
+          peerCommitment.trim() || undefined
+        )
+        if (!sasResult.ok && peerCommitment.trim()) {
+          setSharedSecret(null)
+          setSas(null)
+          setError(sasResult.error)
+          return
+        }
         setSharedSecret(result.value)
+        setSas(sasResult.ok ? sasResult.value : null)
         setError(null)
       } else {
         setError(result.error || 'Błąd obliczania sekretu')
@@ -73,6 +155,55 @@ export default function ECDH(): React.ReactNode {
           <TypographyH2>Twoje klucze</TypographyH2>
           <Button onClick={generateKeys}>Generuj nową parę</Button>
         </div>
+        <div className="flex flex-col gap-2">
+          <label className="text-sm text-text-secondary" htmlFor="ecdhCurve">
+            Krzywa:
+          </label>
+          <select
+            id="ecdhCurve"
+            className="h-9 rounded-md border px-2 text-sm bg-transparent"
+            value={curve}
+            onChange={(e) => selectCurve(e.target.value as EcdhCurve)}
+          >
+            {CURVES.map((entry) => (
+              <option key={entry.id} value={entry.id}>
+                {entry.name}
+              </option>
+            ))}
+          </select>
+          {(curve === 'p256' || curve === 'p384') && (
+            <label className="flex items-center gap-2 text-sm text-text-secondary">
+              <input
+                type="checkbox"
+                checked={compressed}
+                onChange={(e) => setCompressed(e.target.checked)}
+              />
+              Skompresowany punkt SEC1 (krótszy klucz publiczny)
+            </label>
+          )}
+          <label className="text-sm text-text-secondary" htmlFor="ecdhPassphrase">
+            Hasło klucza prywatnego (opcjonalne, szyfruje klucz jako PKCS#8):
+          </label>
+          <input
+            type="password"
+            id="ecdhPassphrase"
+            autoComplete="off"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            className="p-2 rounded border border-border-primary bg-background-primary text-text-primary focus:border-primary outline-none transition"
+          />
+          <label className="text-sm text-text-secondary" htmlFor="ecdhSeed">
+            Ziarno (tryb deterministyczny, tylko do testów i pokazów):
+          </label>
+          <input
+            type="text"
+            id="ecdhSeed"
+            autoComplete="off"
+            value={seed}
+            onChange={(e) => setSeed(e.target.value)}
+            className="p-2 rounded border border-border-primary bg-background-primary text-text-primary focus:border-primary outline-none transition"
+          />
+        </div>
 
         {keypair && (
           <div className="grid gap-4 p-4 border border-border-primary rounded-lg bg-background-secondary/50">
@@ -86,6 +217,26 @@ export default function ECDH(): React.ReactNode {
                 </code>
                 <CopyButton valueToCopy={keypair.public} title="Skopiuj klucz (Ctrl+C)" />
               </div>
+              {keypair.fingerprint && <FingerprintLine fingerprint={keypair.fingerprint} />}
+              {keypair.commitment && (
+                <div className="mt-2">
+                  <TypographyP className="text-text-subtle text-sm mb-1">
+                    Zobowiązanie (wyślij je przed kluczem publicznym, a klucz dopiero po
+                    otrzymaniu zobowiązania rozmówcy):
+                  </TypographyP>
+                  <div className="flex gap-2">
+                    <code className="flex-1 p-2 bg-background-primary rounded border border-border-primary break-all text-xs font-mono">
+                      {keypair.commitment}
+                    </code>
+                    <CopyButton valueToCopy={keypair.commitment} title="Skopiuj zobowiązanie" />
+                  </div>
+                </div>
+              )}
+              {keypair.deterministic && (
+                <p className="text-xs text-destructive mt-1">
+                  Klucz wyprowadzony z ziarna – każdy, kto zna ziarno, odtworzy klucz prywatny.
+                </p>
+              )}
             </div>
             <div>
               <TypographyP className="text-text-subtle text-sm mb-1">
@@ -116,6 +267,29 @@ export default function ECDH(): React.ReactNode {
             />
             <PasteButton setPastedValue={setPeerPublicKey} className="h-auto" />
           </div>
+          {peerFingerprint && (
+            <>
+              <FingerprintLine fingerprint={peerFingerprint} />
+              <p className="text-xs text-text-subtle">
+                Porównaj odcisk z rozmówcą innym kanałem (np. telefonicznie), aby upewnić się, że
+                klucz nie został podmieniony.
+              </p>
+            </>
+          )}
+          <label className="text-sm text-text-secondary" htmlFor="peerCommitment">
+            Zobowiązanie drugiej strony (otrzymane przed jej kluczem publicznym):
+          </label>
+          <div className="flex gap-2">
+            <input
+              type="text"
+              id="peerCommitment"
+              value={peerCommitment}
+              onChange={(e) => setPeerCommitment(e.target.value)}
+              placeholder="Wklej zobowiązanie rozmówcy..."
+              className="flex-1 p-2 rounded border border-border-primary bg-background-primary text-text-primary focus:border-primary outline-none transition"
+            />
+            <PasteButton setPastedValue={setPeerCommitment} className="h-auto" />
+          </div>
         </div>
 
         <Button
@@ -149,6 +323,30 @@ export default function ECDH(): React.ReactNode {
               className="h-auto"
             />
           </div>
+          {sas && (
+            <div className="mt-4 flex flex-col gap-1">
+              <TypographyP className="text-text-secondary">
+                Ciąg uwierzytelniający (SAS) – przeczytajcie go sobie nawzajem innym kanałem. Jeśli
+                się różni, ktoś pośredniczy w wymianie kluczy.
+              </TypographyP>
+              <p className="text-2xl" title={sas.words}>
+                {sas.emoji}
+              </p>
+              <p className="text-sm text-text-subtle">{sas.words}</p>
+              <code className="font-mono text-xl text-primary">{sas.decimal}</code>
+              {peerCommitment.trim() ? (
+                <p className="text-xs text-text-subtle">
+                  Klucz rozmówcy zgadza się z jego zobowiązaniem.
+                </p>
+              ) : (
+                <p className="text-xs text-destructive">
+                  Bez wymiany zobowiązań pośrednik, który zna oba klucze, może przetestować ok.
+                  miliona własnych par kluczy, aż sześć cyfr się zgodzi – porównujcie wtedy
+                  także emoji lub odciski kluczy.
+                </p>
+              )}
+            </div>
+          )}
         </div>
       )}
     </div>
@@ -0,0 +1,254 @@
+import React, { useState } from 'react'
+import { Button } from './ui/button'
+import { TypographyH2, TypographyP } from './ui/typography'
+import { CopyButton } from './CopyButton'
+
+type AttackId = 'hastad' | 'commonModulus' | 'smallMessage' | 'chosenCiphertext'
+type Target = { key: string; ciphertext: string }
+type RecoveredMessage = { blocks: string[]; base64: string; text: string | null }
+
+const ATTACKS: { id: AttackId; name: string; description: string; targets: number }[] = [
+  {
+    id: 'hastad',
+    name: 'Atak Håstada',
+    description:
+      'Ta sama wiadomość zaszyfrowana małym wykładnikiem e (np. 3) dla co najmniej e odbiorców. CRT łączy szyfrogramy w m^e, z którego wystarczy wyciągnąć pierwiastek.',
+    targets: 3
+  },
+  {
+    id: 'commonModulus',
+    name: 'Wspólny moduł',
+    description:
+      'Ta sama wiadomość zaszyfrowana dwoma kluczami o tym samym module n i względnie pierwszych wykładnikach e1, e2.',
+    targets: 2
+  },
+  {
+    id: 'smallMessage',
+    name: 'Krótka wiadomość',
+    description:
+      'Gdy m^e < n, szyfrowanie nie redukuje wyniku modulo n – wiadomość to zwykły pierwiastek stopnia e z szyfrogramu.',
+    targets: 1
+  },
+  {
+    id: 'chosenCiphertext',
+    name: 'Wybrany szyfrogram',
+    description:
+      'Wyrocznia odszyfrowuje wszystko poza przechwyconym szyfrogramem. Wysyłamy jej c·r^e i dzielimy odpowiedź przez r (homomorfizm multiplikatywny RSA).',
+    targets: 1
+  }
+]
+
+const fieldClasses =
+  'p-2 rounded border border-border-primary bg-background-primary text-text-primary focus:border-primary outline-none transition font-mono text-xs'
+
+const emptyTargets = (count: number): Target[] =>
+  Array.from({ length: count }, () => ({ key: '', ciphertext: '' }))
+
+export default function RsaAttacks(): React.ReactNode {
+  const [attack, setAttack] = useState<AttackId>('hastad')
+  const [targets, setTargets] = useState<Target[]>(emptyTargets(3))
+  const [oracleKey, setOracleKey] = useState('')
+  const [passphrase, setPassphrase] = useState('')
+  const [recovered, setRecovered] = useState<RecoveredMessage | null>(null)
+  const [forged, setForged] = useState<string[] | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  const selected = ATTACKS.find((entry) => entry.id === attack) ?? ATTACKS[0]
+
+  const selectAttack = (id: AttackId): void => {
+    const entry = ATTACKS.find((candidate) => candidate.id === id)
+    setAttack(id)
+    setTargets(emptyTargets(entry?.targets ?? 1))
+    setRecovered(null)
+    setForged(null)
+    setError(null)
+  }
+
+  const updateTarget = (index: number, field: keyof Target, value: string): void => {
+    setTargets((current) =>
+      current.map((target, i) => (i === index ? { ...target, [field]: value } : target))
+    )
+  }
+
+  const loadFile = async (
+    event: React.ChangeEvent<HTMLInputElement>,
+    apply: (text: string) => void
+  ): Promise<void> => {
+    const file = event.target.files?.[0]
+    if (file) {
+      apply(await file.text())
+    }
+    event.target.value = ''
+  }
+
+  const runAttack = async (): Promise<void> => {
+    setRecovered(null)
+    setForged(null)
+    setError(null)
+
+    const api = window.api.rsaAttack
+    const [first, second] = targets
+    if (attack === 'chosenCiphertext') {
+      const result = await api.chosenCiphertext(
+        first.key,
+        first.ciphertext,
+        oracleKey,
+        passphrase || undefined
+      )
+      if (result.ok) {
+        setForged(result.value.forged)
+        setRecovered(result.value.recovered)
+      } else {
+        setError(result.error)
+      }
+      return
+    }
+
+    const result =
+      attack === 'hastad'
+        ? await api.hastad(
+            targets.map((target) => target.key),
+            targets.map((target) => target.ciphertext)
+          )
+        : attack === 'commonModulus'
+          ? await api.commonModulus(first.key, first.ciphertext, second.key, second.ciphertext)
+          : await api.smallMessage(first.key, first.ciphertext)
+    if (result.ok) {
+      setRecovered(result.value)
+    } else {
+      setError(result.error)
+    }
+  }
+
+  return (
+    <div className="flex flex-col gap-8 w-full max-w-3xl mx-auto p-6">
+      <div className="flex flex-col gap-4">
+        <TypographyH2>Ataki na RSA bez dopełnienia</TypographyH2>
+        <TypographyP className="text-text-secondary">
+          Szyfrowanie RSA w aplikacji nie używa dopełnienia (OAEP), dlatego jest podatne na
+          klasyczne ataki. Wklej klucze publiczne (PEM, JWK lub JSON) i zawartość plików z
+          szyfrogramami utworzonych przez aplikację. Klucze z małym wykładnikiem (np.{' '}
+          <code>e = 3</code>) potrzebne do ataku Håstada wygeneruj w zakładce RSA w trybie
+          edukacyjnym.
+        </TypographyP>
+        <div className="flex flex-wrap gap-2">
+          {ATTACKS.map((entry) => (
+            <Button
+              key={entry.id}
+              variant={entry.id === attack ? 'default' : 'outline'}
+              onClick={() => selectAttack(entry.id)}
+            >
+              {entry.name}
+            </Button>
+          ))}
+        </div>
+        <TypographyP className="text-text-subtle text-sm">{selected.description}</TypographyP>
+      </div>
+
+      <div className="flex flex-col gap-4">
+        {targets.map((target, index) => (
+          <div
+            key={index}
+            className="grid gap-2 p-4 border border-border-primary rounded-lg bg-background-secondary/50"
+          >
+            <label className="text-sm text-text-secondary" htmlFor={`attackKey${index}`}>
+              Klucz publiczny {targets.length > 1 ? `nr ${index + 1}` : ''}
+            </label>
+            <textarea
+              id={`attackKey${index}`}
+              value={target.key}
+              onChange={(e) => updateTarget(index, 'key', e.target.value)}
+              className={`${fieldClasses} h-24`}
+            />
+            <input
+              type="file"
+              accept=".json,.pem"
+              aria-label="Wczytaj klucz z pliku"
+              onChange={(e) => loadFile(e, (text) => updateTarget(index, 'key', text))}
+            />
+            <label className="text-sm text-text-secondary" htmlFor={`attackCiphertext${index}`}>
+              Szyfrogram
+            </label>
+            <textarea
+              id={`attackCiphertext${index}`}
+              value={target.ciphertext}
+              onChange={(e) => updateTarget(index, 'ciphertext', e.target.value)}
+              className={`${fieldClasses} h-24`}
+            />
+            <input
+              type="file"
+              aria-label="Wczytaj szyfrogram z pliku"
+              onChange={(e) => loadFile(e, (text) => updateTarget(index, 'ciphertext', text))}
+            />
+          </div>
+        ))}
+        {attack === 'hastad' && (
+          <Button
+            variant="outline"
+            className="self-start"
+            onClick={() => setTargets((current) => [...current, { key: '', ciphertext: '' }])}
+          >
+            Dodaj odbiorcę
+          </Button>
+        )}
+        {attack === 'chosenCiphertext' && (
+          <div className="grid gap-2">
+            <label className="text-sm text-text-secondary" htmlFor="oracleKey">
+              Klucz prywatny wyroczni (symuluje serwer odszyfrowujący dowolne dane):
+            </label>
+            <textarea
+              id="oracleKey"
+              value={oracleKey}
+              onChange={(e) => setOracleKey(e.target.value)}
+              className={`${fieldClasses} h-24`}
+            />
+            <input
+              type="password"
+              autoComplete="off"
+              placeholder="Hasło klucza wyroczni (opcjonalne)"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              className={fieldClasses}
+            />
+          </div>
+        )}
+        <Button onClick={runAttack} className="w-full sm:w-auto self-end">
+          Przeprowadź atak
+        </Button>
+      </div>
+
+      {error && (
+        <div className="p-4 bg-destructive/10 border border-destructive rounded-md text-destructive">
+          {error}
+        </div>
+      )}
+
+      {recovered && (
+        <div className="flex flex-col gap-2 p-6 border border-primary/50 rounded-lg bg-primary/5">
+          <TypographyH2 className="text-primary">Odzyskana wiadomość</TypographyH2>
+          {forged && (
+            <TypographyP className="text-text-secondary text-sm">
+              Wyrocznia odszyfrowała {forged.length} zmodyfikowanych bloków, żadnego z
+              przechwyconych.
+            </TypographyP>
+          )}
+          <div className="flex gap-2 mt-2">
+            <code className="flex-1 p-3 bg-background-primary rounded border border-primary/30 break-all font-mono text-primary whitespace-pre-wrap">
+              {recovered.text ?? recovered.base64}
+            </code>
+            <CopyButton
+              valueToCopy={recovered.text ?? recovered.base64}
+              title="Kopiuj wiadomość"
+              className="h-auto"
+            />
+          </div>
+          {recovered.text === null && (
+            <p className="text-xs text-text-subtle">
+              Dane nie są tekstem UTF-8 – pokazano je w base64.
+            </p>
+          )}
+        </div>
+      )}
+    </div>
+  )
+}
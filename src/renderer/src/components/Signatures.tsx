@@ -0,0 +1,268 @@
+import React, { useEffect, useState } from 'react'
+import { Button } from './ui/button'
+import { TypographyH2, TypographyP } from './ui/typography'
+import { CopyButton } from './CopyButton'
+
+type KeyFingerprint = { base64: string; emoji: string; numeric: string; words: string }
+type KeyFormat = 'base64' | 'pem' | 'openssh'
+type Subject = 'text' | 'file'
+
+const fieldClasses =
+  'p-2 rounded border border-border-primary bg-background-primary text-text-primary focus:border-primary outline-none transition font-mono text-xs'
+
+const FORMATS: { id: KeyFormat; name: string }[] = [
+  { id: 'base64', name: 'Base64 (32 bajty)' },
+  { id: 'pem', name: 'PEM (PKCS#8 / SPKI)' },
+  { id: 'openssh', name: 'OpenSSH (ssh-ed25519)' }
+]
+
+export default function Signatures(): React.ReactNode {
+  const [format, setFormat] = useState<KeyFormat>('base64')
+  const [passphrase, setPassphrase] = useState('')
+  const [seed, setSeed] = useState('')
+  const [privateKey, setPrivateKey] = useState('')
+  const [publicKey, setPublicKey] = useState('')
+  const [fingerprint, setFingerprint] = useState<KeyFingerprint | null>(null)
+  const [deterministic, setDeterministic] = useState(false)
+  const [subject, setSubject] = useState<Subject>('text')
+  const [message, setMessage] = useState('')
+  const [fileName, setFileName] = useState<string | null>(null)
+  const [signature, setSignature] = useState('')
+  const [verified, setVerified] = useState<boolean | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    let cancelled = false
+    if (!publicKey.trim()) {
+      setFingerprint(null)
+      return
+    }
+    window.api.ed25519.fingerprint(publicKey.trim()).then((result) => {
+      if (!cancelled) {
+        setFingerprint(result.ok ? result.value : null)
+      }
+    })
+    return () => {
+      cancelled = true
+    }
+  }, [publicKey])
+
+  useEffect(() => {
+    setVerified(null)
+  }, [publicKey, message, fileName, signature, subject])
+
+  const generateKeys = async (): Promise<void> => {
+    const result = await window.api.ed25519.generateKeypair(
+      format,
+      passphrase || undefined,
+      seed || undefined
+    )
+    if (result.ok) {
+      const keys = JSON.parse(result.value)
+      setPrivateKey(keys.private)
+      setPublicKey(keys.public)
+      setDeterministic(Boolean(keys.deterministic))
+      setSignature('')
+      setError(null)
+    } else {
+      setError(result.error)
+    }
+  }
+
+  const loadFile = async (event: React.ChangeEvent<HTMLInputElement>): Promise<void> => {
+    const file = event.target.files?.[0]
+    event.target.value = ''
+    if (!file) {
+      return
+    }
+    const result = await window.api.file.store(file.name, new Uint8Array(await file.arrayBuffer()))
+    if (result.ok) {
+      setFileName(result.value.name)
+      setError(null)
+    } else {
+      setFileName(null)
+      setError(result.error)
+    }
+  }
+
+  const sign = async (): Promise<void> => {
+    const api = window.api.ed25519
+    const result =
+      subject === 'text'
+        ? await api.sign(privateKey, message, passphrase || undefined)
+        : await api.signFile(privateKey, passphrase || undefined)
+    if (result.ok) {
+      setSignature(result.value)
+      setError(null)
+    } else {
+      setError(result.error)
+    }
+  }
+
+  const verify = async (): Promise<void> => {
+    const api = window.api.ed25519
+    const result =
+      subject === 'text'
+        ? await api.verify(publicKey, message, signature.trim())
+        : await api.verifyFile(publicKey, signature.trim())
+    if (result.ok) {
+      setVerified(result.value)
+      setError(null)
+    } else {
+      setVerified(null)
+      setError(result.error)
+    }
+  }
+
+  const hasSubject = subject === 'text' || fileName !== null
+
+  return (
+    <div className="flex flex-col gap-8 w-full max-w-2xl mx-auto p-6">
+      <div className="flex flex-col gap-4">
+        <div className="flex justify-between items-center">
+          <TypographyH2>Klucze Ed25519</TypographyH2>
+          <Button onClick={generateKeys}>Generuj nową parę</Button>
+        </div>
+        <div className="grid gap-2">
+          <label className="text-sm text-text-secondary" htmlFor="ed25519Format">
+            Format kluczy:
+          </label>
+          <select
+            id="ed25519Format"
+            className="h-9 rounded-md border px-2 text-sm bg-transparent"
+            value={format}
+            onChange={(e) => setFormat(e.target.value as KeyFormat)}
+          >
+            {FORMATS.map((entry) => (
+              <option key={entry.id} value={entry.id}>
+                {entry.name}
+              </option>
+            ))}
+          </select>
+          <label className="text-sm text-text-secondary" htmlFor="ed25519Passphrase">
+            Hasło klucza prywatnego (opcjonalne, szyfruje klucz jako PKCS#8):
+          </label>
+          <input
+            type="password"
+            id="ed25519Passphrase"
+            autoComplete="off"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            className={fieldClasses}
+          />
+          <label className="text-sm text-text-secondary" htmlFor="ed25519Seed">
+            Ziarno (tryb deterministyczny, tylko do testów i pokazów):
+          </label>
+          <input
+            type="text"
+            id="ed25519Seed"
+            autoComplete="off"
+            value={seed}
+            onChange={(e) => setSeed(e.target.value)}
+            className={fieldClasses}
+          />
+          <label className="text-sm text-text-secondary" htmlFor="ed25519Private">
+            Klucz prywatny (base64, PEM lub plik OpenSSH):
+          </label>
+          <textarea
+            id="ed25519Private"
+            value={privateKey}
+            onChange={(e) => setPrivateKey(e.target.value)}
+            className={`${fieldClasses} h-24`}
+          />
+          <label className="text-sm text-text-secondary" htmlFor="ed25519Public">
+            Klucz publiczny (base64, PEM lub linia ssh-ed25519):
+          </label>
+          <div className="flex gap-2">
+            <textarea
+              id="ed25519Public"
+              value={publicKey}
+              onChange={(e) => setPublicKey(e.target.value)}
+              className={`${fieldClasses} flex-1 h-16`}
+            />
+            <CopyButton valueToCopy={publicKey} title="Kopiuj klucz publiczny" className="h-auto" />
+          </div>
+          {fingerprint && (
+            <p className="text-xs text-text-subtle" title={fingerprint.words}>
+              Odcisk: <span className="text-base">{fingerprint.emoji}</span>{' '}
+              <code className="font-mono">{fingerprint.base64}</code>
+            </p>
+          )}
+          {deterministic && (
+            <p className="text-xs text-destructive">
+              Klucz wyprowadzony z ziarna – każdy, kto zna ziarno, odtworzy klucz prywatny.
+            </p>
+          )}
+        </div>
+      </div>
+
+      <div className="flex flex-col gap-4">
+        <TypographyH2>Podpis</TypographyH2>
+        <div className="flex gap-2">
+          <Button
+            variant={subject === 'text' ? 'default' : 'outline'}
+            onClick={() => setSubject('text')}
+          >
+            Tekst
+          </Button>
+          <Button
+            variant={subject === 'file' ? 'default' : 'outline'}
+            onClick={() => setSubject('file')}
+          >
+            Plik
+          </Button>
+        </div>
+        {subject === 'text' ? (
+          <textarea
+            aria-label="Podpisywana wiadomość"
+            value={message}
+            onChange={(e) => setMessage(e.target.value)}
+            className={`${fieldClasses} h-24`}
+          />
+        ) : (
+          <div className="grid gap-2">
+            <input type="file" aria-label="Wybierz podpisywany plik" onChange={loadFile} />
+            {fileName && (
+              <TypographyP className="text-sm text-text-subtle">{fileName}</TypographyP>
+            )}
+          </div>
+        )}
+        <label className="text-sm text-text-secondary" htmlFor="ed25519Signature">
+          Podpis (base64):
+        </label>
+        <div className="flex gap-2">
+          <textarea
+            id="ed25519Signature"
+            value={signature}
+            onChange={(e) => setSignature(e.target.value)}
+            className={`${fieldClasses} flex-1 h-16`}
+          />
+          <CopyButton valueToCopy={signature} title="Kopiuj podpis" className="h-auto" />
+        </div>
+        <div className="flex gap-2 self-end">
+          <Button onClick={sign} disabled={!privateKey.trim() || !hasSubject}>
+            Podpisz
+          </Button>
+          <Button
+            variant="outline"
+            onClick={verify}
+            disabled={!publicKey.trim() || !signature.trim() || !hasSubject}
+          >
+            Weryfikuj
+          </Button>
+        </div>
+        {verified !== null && (
+          <p className={verified ? 'text-primary' : 'text-destructive'}>
+            {verified ? 'Podpis jest poprawny' : 'Podpis jest NIEPOPRAWNY'}
+          </p>
+        )}
+      </div>
+
+      {error && (
+        <div className="p-4 bg-destructive/10 border border-destructive rounded-md text-destructive">
+          {error}
+        </div>
+      )}
+    </div>
+  )
+}
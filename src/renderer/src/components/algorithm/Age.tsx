@@ -0,0 +1,134 @@
+import React, { useEffect, useState } from 'react'
+import { CipherProps } from './cipher-props'
+import { TypographyLabel } from '../ui/typography'
+import { Field, FieldLabel } from '../ui/field'
+import { Textarea } from '../ui/textarea'
+import { Input } from '../ui/input'
+import { Button } from '../ui/button'
+import { CopyButton } from '../CopyButton'
+
+type AgeIdentity = { identity: string; recipient: string }
+type AgeMode = 'keys' | 'passphrase'
+
+export default function Age({
+  description,
+  operation,
+  setKey,
+  setIsValid,
+  setPassphrase
+}: CipherProps): React.ReactNode {
+  const [mode, setMode] = useState<AgeMode>('keys')
+  const [keyValue, setKeyValue] = useState('')
+  const [passphraseValue, setPassphraseValue] = useState('')
+  const [generated, setGenerated] = useState<AgeIdentity | null>(null)
+  const [generatorMessage, setGeneratorMessage] = useState<string | null>(null)
+
+  useEffect(() => {
+    if (mode === 'passphrase') {
+      // Formularz wymaga niepustego klucza; w trybie hasła Rust używa tylko hasła
+      setKey(passphraseValue)
+      setPassphrase?.(passphraseValue)
+      setIsValid(passphraseValue.length > 0)
+    } else {
+      setKey(keyValue)
+      setPassphrase?.('')
+      setIsValid(keyValue.trim().length > 0)
+    }
+  }, [mode, keyValue, passphraseValue, setKey, setPassphrase, setIsValid])
+
+  const generateIdentity = async (): Promise<void> => {
+    const result = await window.api.age.generateIdentity()
+    if (result.ok) {
+      setGenerated(result.value)
+      setGeneratorMessage(null)
+      setKeyValue(operation === 'encrypt' ? result.value.recipient : result.value.identity)
+    } else {
+      setGeneratorMessage(result.error)
+    }
+  }
+
+  return (
+    <>
+      {description && <TypographyLabel>{description}</TypographyLabel>}
+      <div className="flex gap-2">
+        <Button
+          type="button"
+          variant={mode === 'keys' ? 'default' : 'outline'}
+          aria-pressed={mode === 'keys'}
+          onClick={() => setMode('keys')}
+        >
+          Klucze age
+        </Button>
+        <Button
+          type="button"
+          variant={mode === 'passphrase' ? 'default' : 'outline'}
+          aria-pressed={mode === 'passphrase'}
+          onClick={() => setMode('passphrase')}
+        >
+          Hasło
+        </Button>
+      </div>
+      {mode === 'keys' ? (
+        <>
+          <Field className="text-left">
+            <FieldLabel htmlFor="age-key">
+              {operation === 'encrypt'
+                ? 'Odbiorcy (age1..., po jednym w linii)'
+                : 'Tożsamość (AGE-SECRET-KEY-1...)'}
+            </FieldLabel>
+            <Textarea
+              id="age-key"
+              value={keyValue}
+              onChange={(event) => setKeyValue(event.target.value)}
+              className="h-24 font-mono max-w-[90vw]"
+            />
+            <p className="text-xs text-muted-foreground mt-2">
+              Pliki są zgodne z narzędziem <code>age</code>: można je odszyfrować poleceniem{' '}
+              <code>age -d -i klucz.txt</code>, a pliki z <code>age -r</code> otworzyć tutaj. Tekst,
+              który nie jest kluczem age, jest odrzucany – do szyfrowania hasłem wybierz tryb
+              „Hasło”.
+            </p>
+          </Field>
+          <div className="flex flex-col gap-2 text-left">
+            <Button
+              type="button"
+              variant="outline"
+              className="self-start"
+              onClick={generateIdentity}
+            >
+              Generuj tożsamość age
+            </Button>
+            {generatorMessage && <p className="text-destructive text-sm">{generatorMessage}</p>}
+            {generated && (
+              <div className="flex gap-2">
+                <code className="flex-1 p-2 rounded border break-all text-xs font-mono whitespace-pre-wrap">
+                  {generated.identity}
+                </code>
+                <CopyButton
+                  type="button"
+                  valueToCopy={generated.identity}
+                  title="Kopiuj tożsamość (zapisz ją w pliku, np. klucz.txt)"
+                  className="h-auto"
+                />
+              </div>
+            )}
+          </div>
+        </>
+      ) : (
+        <Field className="text-left">
+          <FieldLabel htmlFor="age-passphrase">Hasło (scrypt)</FieldLabel>
+          <Input
+            id="age-passphrase"
+            type="password"
+            autoComplete="off"
+            value={passphraseValue}
+            onChange={(event) => setPassphraseValue(event.target.value)}
+          />
+          <p className="text-xs text-muted-foreground mt-2">
+            Zgodne z <code>age -p</code> i <code>age -d</code> dla plików zaszyfrowanych hasłem.
+          </p>
+        </Field>
+      )}
+    </>
+  )
+}
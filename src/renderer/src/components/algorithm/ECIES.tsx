@@ -0,0 +1,87 @@
+import React, { useEffect, useState } from 'react'
+import { CipherProps } from './cipher-props'
+import { TypographyLabel } from '../ui/typography'
+import { Field, FieldLabel } from '../ui/field'
+import { Textarea } from '../ui/textarea'
+import { Input } from '../ui/input'
+
+type KeyFingerprint = { emoji: string; words: string; numeric: string }
+
+export default function ECIES({
+  description,
+  operation,
+  setKey,
+  setIsValid,
+  setPassphrase
+}: CipherProps): React.ReactNode {
+  const [keyValue, setKeyValue] = useState('')
+  const [passphraseValue, setPassphraseValue] = useState('')
+  const [fingerprint, setFingerprint] = useState<KeyFingerprint | null>(null)
+
+  useEffect(() => {
+    const trimmed = keyValue.trim()
+    setKey(trimmed)
+    setIsValid(trimmed.length > 0)
+
+    if (operation !== 'encrypt' || !trimmed) {
+      setFingerprint(null)
+      return
+    }
+    let cancelled = false
+    window.api?.ecdh?.fingerprint?.(trimmed).then((result) => {
+      if (!cancelled) {
+        setFingerprint(result.ok ? result.value : null)
+      }
+    })
+    return () => {
+      cancelled = true
+    }
+  }, [keyValue, operation, setKey, setIsValid])
+
+  const passphraseChangeHandler = (event: React.ChangeEvent<HTMLInputElement>): void => {
+    setPassphraseValue(event.target.value)
+    setPassphrase?.(event.target.value)
+  }
+
+  return (
+    <>
+      {description && <TypographyLabel>{description}</TypographyLabel>}
+      <Field className="text-left">
+        <FieldLabel htmlFor="ecies-key">
+          {operation === 'encrypt'
+            ? 'Klucz publiczny odbiorcy (X25519)'
+            : 'Twój klucz prywatny (X25519)'}
+        </FieldLabel>
+        <Textarea
+          id="ecies-key"
+          value={keyValue}
+          onChange={(event) => setKeyValue(event.target.value)}
+          className="h-24 font-mono max-w-[90vw]"
+        />
+        <p className="text-xs text-muted-foreground mt-2">
+          Klucze z zakładki ECDH: base64, JWK lub PEM (PKCS#8). Plik jest szyfrowany AES-GCM kluczem
+          wyprowadzonym (HKDF-SHA256) z wymiany z jednorazowym kluczem efemerycznym, którego część
+          publiczna trafia do szyfrogramu.
+        </p>
+        {fingerprint && (
+          <p className="text-xs text-muted-foreground mt-2" title={fingerprint.words}>
+            Odcisk klucza odbiorcy: <span className="text-base">{fingerprint.emoji}</span>{' '}
+            <code>{fingerprint.numeric}</code>
+          </p>
+        )}
+      </Field>
+      {operation === 'decrypt' && (
+        <Field className="text-left">
+          <FieldLabel htmlFor="ecies-passphrase">Hasło klucza prywatnego (opcjonalne)</FieldLabel>
+          <Input
+            id="ecies-passphrase"
+            type="password"
+            autoComplete="off"
+            value={passphraseValue}
+            onChange={passphraseChangeHandler}
+          />
+        </Field>
+      )}
+    </>
+  )
+}
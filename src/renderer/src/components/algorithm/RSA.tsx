@@ -10,6 +10,20 @@ import { ClipboardPaste } from 'lucide-react'
 import { err, ok, Result } from '@shared/result-util'
 import { useClipboard } from '@renderer/hooks/useClipboard'
 
+type AuditedKey = {
+  label: string
+  broken: boolean
+  findings: string[]
+}
+
+const ATTACK_NAMES: Record<string, string> = {
+  shared_factor: 'wspólny czynnik',
+  wiener: 'atak Wienera',
+  fermat: 'faktoryzacja Fermata',
+  pollard_p_minus_1: 'metoda p−1 Pollarda',
+  pollard_rho: 'metoda rho Pollarda'
+}
+
 type NormalizedKey = {
   n: string
   e?: string
@@ -33,11 +47,39 @@ function resolveNestedField(source: unknown, field: keyof NormalizedKey): string
   return pickString(record[field])
 }
 
+function resolveJwk(
+  data: Record<string, unknown>,
+  operation: 'encrypt' | 'decrypt'
+): Record<string, unknown> | undefined {
+  if (typeof data.kty === 'string') {
+    return data
+  }
+
+  const candidates = operation === 'decrypt' ? [data.private] : [data.public, data.private]
+  return candidates.find(
+    (candidate): candidate is Record<string, unknown> =>
+      !!candidate &&
+      typeof candidate === 'object' &&
+      typeof (candidate as Record<string, unknown>).kty === 'string'
+  )
+}
+
 function validateKeyPayload(raw: string, operation: 'encrypt' | 'decrypt'): Result<string, string> {
   if (!raw || raw.trim().length === 0) {
     return err('Klucz RSA jest wymagany.')
   }
 
+  const trimmed = raw.trim()
+  if (trimmed.startsWith('-----BEGIN ')) {
+    if (
+      operation === 'decrypt' &&
+      !/-----BEGIN (RSA |ENCRYPTED )?PRIVATE KEY-----/.test(trimmed)
+    ) {
+      return err('Do deszyfrowania wymagany jest klucz prywatny PEM.')
+    }
+    return ok(trimmed)
+  }
+
   let parsed: unknown
   try {
     parsed = JSON.parse(raw)
@@ -50,11 +92,28 @@ function validateKeyPayload(raw: string, operation: 'encrypt' | 'decrypt'): Resu
   }
 
   const data = parsed as Record<string, unknown>
+  // Generator z hasłem zwraca klucz prywatny jako zaszyfrowany PEM
+  const privatePem = pickString(data.private)
+  if (operation === 'decrypt' && privatePem?.startsWith('-----BEGIN ')) {
+    return validateKeyPayload(privatePem, operation)
+  }
+
+  const jwk = resolveJwk(data, operation)
+  if (jwk) {
+    if (operation === 'decrypt' && !pickString(jwk.d)) {
+      return err('Do deszyfrowania wymagany jest klucz prywatny JWK (pole "d").')
+    }
+    return ok(JSON.stringify(jwk))
+  }
+
   const modulus =
     pickString(data.n) ??
     resolveNestedField(data.public, 'n') ??
     resolveNestedField(data.private, 'n')
-  const publicExp = pickString(data.e) ?? resolveNestedField(data.public, 'e')
+  const publicExp =
+    pickString(data.e) ??
+    resolveNestedField(data.public, 'e') ??
+    resolveNestedField(data.private, 'e')
   const privateExp = pickString(data.d) ?? resolveNestedField(data.private, 'd')
 
   if (!modulus) {
@@ -69,6 +128,10 @@ function validateKeyPayload(raw: string, operation: 'encrypt' | 'decrypt'): Resu
     return err('Do deszyfrowania wymagane jest pole "d" w kluczu prywatnym.')
   }
 
+  if (operation === 'decrypt' && !publicExp) {
+    return err('Do deszyfrowania wymagane jest także pole "e" (maskowanie szyfrogramu).')
+  }
+
   const normalized: NormalizedKey = { n: modulus }
   if (publicExp) {
     normalized.e = publicExp
@@ -84,19 +147,36 @@ export default function RSA({
   description,
   operation,
   setKey,
-  setIsValid
+  setIsValid,
+  setPassphrase
 }: CipherProps): React.ReactNode {
   const [keyValue, setKeyValue] = useState('')
+  const [passphraseValue, setPassphraseValue] = useState('')
   const [validationMessage, setValidationMessage] = useState<string | null>(null)
   const [hasInteracted, setHasInteracted] = useState(false)
-  const [bitLength, setBitLength] = useState(512)
+  const [bitLength, setBitLength] = useState(2048)
+  const [educational, setEducational] = useState(false)
+  const [primeCount, setPrimeCount] = useState(2)
+  const [publicExponent, setPublicExponent] = useState(65537)
+  const [seed, setSeed] = useState('')
   const [generatorMessage, setGeneratorMessage] = useState<string | null>(null)
   const [isGenerating, setIsGenerating] = useState(false)
+  const [keygenProgress, setKeygenProgress] = useState<{
+    candidatesTested: number
+    primesFound: number
+  } | null>(null)
   const [isSaving, setIsSaving] = useState(false)
+  const [fingerprint, setFingerprint] = useState<{ base64: string; words: string } | null>(
+    null
+  )
+  const [auditFiles, setAuditFiles] = useState<{ name: string; text: string }[]>([])
+  const [auditResults, setAuditResults] = useState<AuditedKey[] | null>(null)
+  const [isAuditing, setIsAuditing] = useState(false)
   const { readLastCopiedText } = useClipboard()
 
   useEffect(() => {
     const result = validateKeyPayload(keyValue, operation)
+    let cancelled = false
 
     if (result.ok) {
       setKey(result.value)
@@ -104,13 +184,44 @@ export default function RSA({
       if (hasInteracted) {
         setValidationMessage(null)
       }
+
+      window.api?.rsa?.validateKey?.(result.value, passphraseValue).then((response) => {
+        if (cancelled) {
+          return
+        }
+        const failures = response.ok
+          ? response.value.checks.filter((check) => check.status === 'failed')
+          : []
+        const message = response.ok
+          ? failures.map((check) => check.message).join(' ')
+          : response.error
+        if (message) {
+          setIsValid(false)
+          setValidationMessage(message)
+        }
+      })
+      window.api?.rsa?.fingerprint?.(result.value, passphraseValue).then((response) => {
+        if (!cancelled) {
+          setFingerprint(response.ok ? response.value : null)
+        }
+      })
     } else {
+      setFingerprint(null)
       setIsValid(false)
       if (hasInteracted) {
         setValidationMessage(result.error)
       }
     }
-  }, [keyValue, operation, setIsValid, setKey, hasInteracted])
+
+    return () => {
+      cancelled = true
+    }
+  }, [keyValue, passphraseValue, operation, setIsValid, setKey, hasInteracted])
+
+  const passphraseChangeHandler = (event: React.ChangeEvent<HTMLInputElement>): void => {
+    setPassphraseValue(event.target.value)
+    setPassphrase?.(event.target.value)
+  }
 
   const changeHandler = (event: React.ChangeEvent<HTMLTextAreaElement>): void => {
     setHasInteracted(true)
@@ -155,7 +266,7 @@ export default function RSA({
   const bitLengthChangeHandler = (event: React.ChangeEvent<HTMLInputElement>): void => {
     const value = Number(event.target.value)
     if (Number.isNaN(value)) {
-      setBitLength(512)
+      setBitLength(2048)
     } else {
       setBitLength(value)
     }
@@ -167,17 +278,29 @@ export default function RSA({
       return
     }
 
-    const normalizedBits = Math.min(4096, Math.max(256, Math.floor(bitLength)))
+    const minBits = educational ? 32 : 2048
+    const normalizedBits = Math.min(4096, Math.max(minBits, Math.floor(bitLength)))
 
     setIsGenerating(true)
     setGeneratorMessage(null)
+    setKeygenProgress(null)
+    const unsubscribe = window.api.rsa.onKeygenProgress?.(setKeygenProgress)
     try {
-      const response = await window.api.rsa.generateKeypair(normalizedBits)
+      const response = await window.api.rsa.generateKeypair({
+        bits: normalizedBits,
+        educational,
+        passphrase: passphraseValue || undefined,
+        primes: primeCount,
+        publicExponent: educational ? publicExponent : undefined,
+        seed: seed || undefined
+      })
       if (response.ok) {
         setKeyValue(response.value)
         setHasInteracted(true)
         setGeneratorMessage(
-          `Wygenerowano klucze RSA (${normalizedBits} bitów). Możesz je edytować przed użyciem.`
+          seed
+            ? `Wygenerowano klucze RSA z ziarna (${normalizedBits} bitów). To samo ziarno da te same klucze – nie używaj ich do ochrony danych.`
+            : `Wygenerowano klucze RSA (${normalizedBits} bitów, czynników: ${primeCount}, e = ${educational ? publicExponent : 65537}). Możesz je edytować przed użyciem.`
         )
       } else {
         setGeneratorMessage(response.error || 'Nie udało się wygenerować kluczy RSA.')
@@ -186,10 +309,16 @@ export default function RSA({
       console.error('Nie udało się wygenerować kluczy RSA:', error)
       setGeneratorMessage('Wystąpił błąd podczas generowania kluczy RSA.')
     } finally {
+      unsubscribe?.()
+      setKeygenProgress(null)
       setIsGenerating(false)
     }
   }
 
+  const cancelKeygen = async (): Promise<void> => {
+    await window.api?.rsa?.cancelKeygen?.()
+  }
+
   const saveKeyToFile = async (): Promise<void> => {
     if (!window.api?.rsa?.saveKey) {
       setGeneratorMessage('Zapisywanie kluczy nie jest dostępne w tej wersji aplikacji.')
@@ -226,10 +355,63 @@ export default function RSA({
     }
   }
 
+  const auditFilesHandler = async (event: React.ChangeEvent<HTMLInputElement>): Promise<void> => {
+    const files = Array.from(event.target.files ?? [])
+    const loaded = await Promise.all(
+      files.map(async (file) => ({ name: file.name, text: await file.text() }))
+    )
+    setAuditFiles(loaded)
+    setAuditResults(null)
+  }
+
+  const auditKeys = async (): Promise<void> => {
+    if (!window.api?.rsa?.audit) {
+      setGeneratorMessage('Audyt kluczy nie jest dostępny w tej wersji aplikacji.')
+      return
+    }
+
+    const current = validateKeyPayload(keyValue, 'encrypt')
+    const entries = [
+      ...(current.ok ? [{ name: 'bieżący klucz', text: current.value }] : []),
+      ...auditFiles
+    ]
+    if (entries.length === 0) {
+      setValidationMessage('Brak kluczy do audytu.')
+      return
+    }
+
+    setIsAuditing(true)
+    setAuditResults(null)
+    try {
+      const response = await window.api.rsa.audit(
+        entries.map((entry) => entry.text),
+        passphraseValue || undefined
+      )
+      if (!response.ok) {
+        setValidationMessage(response.error || 'Nie udało się przeprowadzić audytu kluczy.')
+        return
+      }
+      setAuditResults(
+        response.value.keys.map((key) => ({
+          label: `${entries[key.index].name} (${key.bits} bitów)`,
+          broken: key.broken,
+          findings: key.attacks
+            .filter((attack) => attack.status === 'broken')
+            .map((attack) => `${ATTACK_NAMES[attack.attack] ?? attack.attack}: ${attack.message}`)
+        }))
+      )
+    } catch (error) {
+      console.error('Nie udało się przeprowadzić audytu kluczy RSA:', error)
+      setValidationMessage('Wystąpił błąd podczas audytu kluczy RSA.')
+    } finally {
+      setIsAuditing(false)
+    }
+  }
+
   const requirementMessage =
     operation === 'encrypt'
       ? 'Do szyfrowania potrzebujesz modułu "n" oraz eksponenta publicznego "e".'
-      : 'Do deszyfrowania potrzebujesz modułu "n" oraz eksponenta prywatnego "d".'
+      : 'Do deszyfrowania potrzebujesz modułu "n", eksponenta prywatnego "d" oraz publicznego "e".'
 
   return (
     <>
@@ -261,7 +443,27 @@ export default function RSA({
         <p className="text-xs text-muted-foreground mt-2">
           Obsługiwane są oba formaty: płaski obiekt{' '}
           <code>{'{ "n": "...", "e": "...", "d": "..." }'}</code> oraz wynik generatora{' '}
-          <code>{'{ "public": { ... }, "private": { ... } }'}</code>.
+          <code>{'{ "public": { ... }, "private": { ... } }'}</code>, a także klucze PEM (PKCS#1,
+          PKCS#8, SPKI) np. z OpenSSL oraz JWK (RFC 7517).
+        </p>
+        {fingerprint && (
+          <p className="text-xs text-muted-foreground mt-2">
+            Odcisk klucza publicznego: <code>{fingerprint.base64}</code> ({fingerprint.words})
+          </p>
+        )}
+      </Field>
+      <Field className="text-left">
+        <FieldLabel htmlFor="rsa-passphrase">Hasło klucza prywatnego (opcjonalne)</FieldLabel>
+        <Input
+          id="rsa-passphrase"
+          type="password"
+          autoComplete="off"
+          value={passphraseValue}
+          onChange={passphraseChangeHandler}
+        />
+        <p className="text-xs text-muted-foreground mt-2">
+          Wymagane przy kluczu <code>ENCRYPTED PRIVATE KEY</code>. Jeśli je podasz przed
+          generowaniem, klucz prywatny zostanie zapisany jako zaszyfrowany PKCS#8.
         </p>
       </Field>
       <Field className="text-left">
@@ -269,7 +471,7 @@ export default function RSA({
         <Input
           id="rsa-key-file"
           type="file"
-          accept=".json,application/json"
+          accept=".json,.pem,application/json"
           onChange={fileHandler}
         />
       </Field>
@@ -279,19 +481,86 @@ export default function RSA({
           <Input
             id="rsa-bit-length"
             type="number"
-            min={256}
+            min={educational ? 32 : 2048}
             max={4096}
-            step={64}
+            step={educational ? 16 : 1024}
             className="md:w-32"
             value={bitLength}
             onChange={bitLengthChangeHandler}
           />
+          <select
+            id="rsa-prime-count"
+            aria-label="Liczba czynników pierwszych"
+            className="h-9 rounded-md border px-2 text-sm bg-transparent"
+            value={primeCount}
+            onChange={(event) => setPrimeCount(Number(event.target.value))}
+          >
+            {[2, 3, 4, 5].map((count) => (
+              <option key={count} value={count}>
+                {count === 2 ? '2 czynniki (p, q)' : `${count} czynniki`}
+              </option>
+            ))}
+          </select>
+          {educational && (
+            <select
+              id="rsa-public-exponent"
+              aria-label="Eksponent publiczny"
+              className="h-9 rounded-md border px-2 text-sm bg-transparent"
+              value={publicExponent}
+              onChange={(event) => setPublicExponent(Number(event.target.value))}
+            >
+              {[65537, 17, 3].map((exponent) => (
+                <option key={exponent} value={exponent}>
+                  e = {exponent}
+                </option>
+              ))}
+            </select>
+          )}
           <Button type="button" onClick={generateKeypair} disabled={isGenerating}>
             {isGenerating ? 'Generowanie...' : 'Generuj klucze'}
           </Button>
+          {isGenerating && (
+            <Button type="button" variant="outline" onClick={cancelKeygen}>
+              Przerwij
+            </Button>
+          )}
+        </div>
+        {isGenerating && keygenProgress && (
+          <p className="text-xs text-muted-foreground mt-2">
+            Przetestowano kandydatów: {keygenProgress.candidatesTested}, znalezione liczby
+            pierwsze: {keygenProgress.primesFound}
+          </p>
+        )}
+        <div className="flex items-center gap-2 mt-2">
+          <input
+            id="rsa-educational"
+            type="checkbox"
+            checked={educational}
+            onChange={(event) => {
+              setEducational(event.target.checked)
+              if (!event.target.checked) setPublicExponent(65537)
+            }}
+          />
+          <Label htmlFor="rsa-educational">
+            Tryb edukacyjny (pozwala na klucze krótsze niż 2048 bitów)
+          </Label>
         </div>
+        <Input
+          id="rsa-seed"
+          aria-label="Ziarno generatora"
+          placeholder="Ziarno (tryb deterministyczny, tylko do pokazów)"
+          autoComplete="off"
+          className="mt-2"
+          value={seed}
+          onChange={(event) => setSeed(event.target.value)}
+        />
         <p className="text-xs text-muted-foreground mt-2">
-          Klucze są generowane lokalnie i zawierają zarówno część publiczną, jak i prywatną.
+          Klucze są generowane lokalnie i zawierają zarówno część publiczną, jak i prywatną. Długość
+          oznacza rozmiar modułu <code>n</code>; liczby pierwsze <code>p</code> i <code>q</code> mają
+          po połowie tej długości. Klucz wieloczynnikowy (RFC 8017) ma 3–5 czynników i szybciej
+          deszyfruje; poza trybem edukacyjnym 4 czynniki wymagają co najmniej 4096 bitów. W trybie
+          edukacyjnym można wybrać mały eksponent publiczny (np. <code>e = 3</code>), żeby
+          przygotować szyfrogramy do ataku Håstada lub ataku na krótką wiadomość.
         </p>
       </Field>
       <Field className="text-left">
@@ -312,6 +581,40 @@ export default function RSA({
           JSON.
         </p>
       </Field>
+      <Field className="text-left">
+        <FieldLabel htmlFor="rsa-audit-files">Audyt słabych kluczy</FieldLabel>
+        <div className="flex flex-col gap-2 md:flex-row md:items-center">
+          <Input
+            id="rsa-audit-files"
+            type="file"
+            multiple
+            accept=".json,.pem,application/json"
+            onChange={auditFilesHandler}
+          />
+          <Button type="button" variant="outline" onClick={auditKeys} disabled={isAuditing}>
+            {isAuditing ? 'Trwa audyt...' : 'Sprawdź klucze'}
+          </Button>
+        </div>
+        <p className="text-xs text-muted-foreground mt-2">
+          Sprawdza bieżący klucz i wczytane pliki atakami faktoryzacji: Fermata (bliskie czynniki),
+          rho i p−1 Pollarda, Wienera (małe <code>d</code>) oraz wyszukiwaniem wspólnych czynników
+          między modułami.
+        </p>
+        {auditResults && (
+          <ul className="text-xs mt-2 space-y-1">
+            {auditResults.map((result, index) => (
+              <li key={index} className={result.broken ? 'text-destructive' : undefined}>
+                {result.label}: {result.broken ? 'złamany' : 'odporny na sprawdzone ataki'}
+                {result.findings.map((finding) => (
+                  <div key={finding} className="ml-4">
+                    {finding}
+                  </div>
+                ))}
+              </li>
+            ))}
+          </ul>
+        )}
+      </Field>
       {validationMessage && <p className="text-destructive">{validationMessage}</p>}
       {generatorMessage && <p className="text-sm text-muted-foreground">{generatorMessage}</p>}
     </>
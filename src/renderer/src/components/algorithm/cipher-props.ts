@@ -3,4 +3,5 @@ export interface CipherProps {
   operation: 'encrypt' | 'decrypt'
   setKey: (key: string) => void
   setIsValid: (isValid: boolean) => void
+  setPassphrase?: (passphrase: string) => void
 }
@@ -17,6 +17,14 @@ export default function HeaderContent(): React.ReactNode {
       <span className="text-text-subtle">/</span>{' '}
       <NavLink to="/key-exchange" className={navLinkClasses}>
         Wymiana kluczy
+      </NavLink>{' '}
+      <span className="text-text-subtle">/</span>{' '}
+      <NavLink to="/signatures" className={navLinkClasses}>
+        Podpisy
+      </NavLink>{' '}
+      <span className="text-text-subtle">/</span>{' '}
+      <NavLink to="/rsa-attacks" className={navLinkClasses}>
+        Ataki RSA
       </NavLink>
     </TypographyH1>
   )
@@ -1,6 +1,8 @@
 import algorithms from '@renderer/assets/algorithms.json'
 import AES from '@renderer/components/algorithm/AES'
+import Age from '@renderer/components/algorithm/Age'
 import CaesarCipher from '@renderer/components/algorithm/CaesarCipher'
+import ECIES from '@renderer/components/algorithm/ECIES'
 import { CipherProps } from '@renderer/components/algorithm/cipher-props'
 import RSA from '@renderer/components/algorithm/RSA'
 import RunningKeyCipher from '@renderer/components/algorithm/RunningKeyCipher'
@@ -46,6 +48,11 @@ export const getAlgorithmComponent = (
       return <AES {...props} />
     case 'rsa':
       return <RSA {...props} />
+    case 'x25519-ecies':
+      return <ECIES {...props} />
+    case 'age':
+    case 'age-armor':
+      return <Age {...props} />
     default:
       return undefined
   }
@@ -16,6 +16,7 @@ export default function AlgorithmPage({
   const { id } = useParams<{ id: string }>()
   const navigate = useNavigate()
   const [key, setKey] = useState<string>('')
+  const [passphrase, setPassphrase] = useState<string>('')
   const [isValid, setIsValid] = useState<boolean>(false)
   const [errorMessage, setErrorMessage] = useState<string | null>(null)
 
@@ -50,7 +51,8 @@ export default function AlgorithmPage({
 
       const result = await window.api.file.process(operation, {
         key,
-        algorithm: algorithm.id
+        algorithm: algorithm.id,
+        passphrase: passphrase || undefined
       })
       if (result.ok) {
         console.log('Algorithm result:', result.value)
@@ -72,7 +74,8 @@ export default function AlgorithmPage({
     description: algorithm.description,
     operation,
     setKey,
-    setIsValid
+    setIsValid,
+    setPassphrase
   })
 
   const algorithmComponent = mappedComponent || algorithmNotImplemented
@@ -0,0 +1,21 @@
+import React from 'react'
+import Header from '@renderer/components/Header'
+import HeaderContent from '@renderer/components/home/HeaderContent'
+import Main from '@renderer/components/Main'
+import RsaAttacks from '@renderer/components/RsaAttacks'
+import { useTitle } from '@renderer/hooks/useTitle'
+
+export default function RsaAttacksPage(): React.ReactNode {
+  useTitle('Ataki na RSA')
+
+  return (
+    <>
+      <Header>
+        <HeaderContent />
+      </Header>
+      <Main>
+        <RsaAttacks />
+      </Main>
+    </>
+  )
+}
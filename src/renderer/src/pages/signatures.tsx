@@ -0,0 +1,21 @@
+import React from 'react'
+import Header from '@renderer/components/Header'
+import HeaderContent from '@renderer/components/home/HeaderContent'
+import Main from '@renderer/components/Main'
+import Signatures from '@renderer/components/Signatures'
+import { useTitle } from '@renderer/hooks/useTitle'
+
+export default function SignaturesPage(): React.ReactNode {
+  useTitle('Podpisy cyfrowe (Ed25519)')
+
+  return (
+    <>
+      <Header>
+        <HeaderContent />
+      </Header>
+      <Main>
+        <Signatures />
+      </Main>
+    </>
+  )
+}